@@ -291,8 +291,28 @@ impl<I: Interner> MayInvalidate<'_, I> {
                 !c1.const_eq(new_ty, c2, interner)
             }
 
-            // Only variants left are placeholder = concrete, which always fails
-            (ConstValue::Placeholder(_), _) | (ConstValue::Concrete(_), _) => true,
+            (ConstValue::Projection(p1), ConstValue::Projection(p2)) => self
+                .aggregate_name_and_substs(
+                    p1.associated_const_id,
+                    &p1.substitution,
+                    p2.associated_const_id,
+                    &p2.substitution,
+                ),
+
+            (ConstValue::Unevaluated(u1), ConstValue::Unevaluated(u2)) => self
+                .aggregate_name_and_substs(
+                    &u1.interned,
+                    &u1.substitution,
+                    &u2.interned,
+                    &u2.substitution,
+                ),
+
+            // Only variants left are mismatches between placeholders, concrete
+            // consts, projections and unevaluated consts, which always fail
+            (ConstValue::Placeholder(_), _)
+            | (ConstValue::Concrete(_), _)
+            | (ConstValue::Projection(_), _)
+            | (ConstValue::Unevaluated(_), _) => true,
         }
     }
 
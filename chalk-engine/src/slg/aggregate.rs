@@ -548,9 +548,50 @@ impl<I: Interner> AntiUnifier<'_, '_, I> {
                 }
             }
 
-            (ConstValue::Placeholder(_), _) | (_, ConstValue::Placeholder(_)) => {
-                self.new_const_variable(ty)
-            }
+            (ConstValue::Projection(p1), ConstValue::Projection(p2)) => self
+                .aggregate_name_and_substs(
+                    p1.associated_const_id,
+                    &p1.substitution,
+                    p2.associated_const_id,
+                    &p2.substitution,
+                )
+                .map(|(associated_const_id, substitution)| {
+                    ConstData {
+                        ty: ty.clone(),
+                        value: ConstValue::Projection(ProjectionConst {
+                            associated_const_id,
+                            substitution,
+                        }),
+                    }
+                    .intern(interner)
+                })
+                .unwrap_or_else(|| self.new_const_variable(ty)),
+
+            (ConstValue::Unevaluated(u1), ConstValue::Unevaluated(u2)) => self
+                .aggregate_name_and_substs(
+                    &u1.interned,
+                    &u1.substitution,
+                    &u2.interned,
+                    &u2.substitution,
+                )
+                .map(|(interned, substitution)| {
+                    ConstData {
+                        ty: ty.clone(),
+                        value: ConstValue::Unevaluated(UnevaluatedConst {
+                            interned: interned.clone(),
+                            substitution,
+                        }),
+                    }
+                    .intern(interner)
+                })
+                .unwrap_or_else(|| self.new_const_variable(ty)),
+
+            (ConstValue::Placeholder(_), _)
+            | (_, ConstValue::Placeholder(_))
+            | (ConstValue::Projection(_), _)
+            | (_, ConstValue::Projection(_))
+            | (ConstValue::Unevaluated(_), _)
+            | (_, ConstValue::Unevaluated(_)) => self.new_const_variable(ty),
         }
     }
 
@@ -687,6 +687,20 @@ impl<'i, I: Interner> Zipper<'i, I> for AnswerSubstitutor<'i, I> {
                 Ok(())
             }
 
+            (ConstValue::Projection(answer), ConstValue::Projection(pending)) => {
+                Zip::zip_with(self, variance, answer, pending)
+            }
+
+            (ConstValue::Unevaluated(answer), ConstValue::Unevaluated(pending)) => {
+                assert_eq!(answer.interned, pending.interned);
+                self.zip_substs(
+                    variance,
+                    None,
+                    answer.substitution.as_slice(interner),
+                    pending.substitution.as_slice(interner),
+                )
+            }
+
             (ConstValue::InferenceVar(_), _) | (_, ConstValue::InferenceVar(_)) => panic!(
                 "unexpected inference var in answer `{:?}` or pending goal `{:?}`",
                 answer, pending,
@@ -694,7 +708,9 @@ impl<'i, I: Interner> Zipper<'i, I> for AnswerSubstitutor<'i, I> {
 
             (ConstValue::BoundVar(_), _)
             | (ConstValue::Placeholder(_), _)
-            | (ConstValue::Concrete(_), _) => panic!(
+            | (ConstValue::Concrete(_), _)
+            | (ConstValue::Projection(_), _)
+            | (ConstValue::Unevaluated(_), _) => panic!(
                 "structural mismatch between answer `{:?}` and pending goal `{:?}`",
                 answer, pending,
             ),
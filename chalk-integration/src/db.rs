@@ -7,18 +7,21 @@ use crate::{
     tls, SolverChoice,
 };
 use chalk_ir::{
-    AdtId, AssocTypeId, Binders, Canonical, CanonicalVarKinds, ClosureId, ConstrainedSubst,
-    Environment, FnDefId, GeneratorId, GenericArg, Goal, ImplId, InEnvironment, OpaqueTyId,
-    ProgramClause, ProgramClauses, Substitution, TraitId, Ty, TyKind, UCanonical,
-    UnificationDatabase, Variances,
+    AdtId, AssocConstId, AssocTypeId, Binders, Canonical, CanonicalVarKinds, ClosureId,
+    ConcreteConst, ConstrainedSubst, DomainGoal, Environment, Floundered, FnDefId, GeneratorId,
+    GenericArg, Goal, ImplId, InEnvironment, OpaqueTyId, ProgramClause, ProgramClauses,
+    Substitution, TraitId, Ty, TyKind, UCanonical, UnevaluatedConst, UnificationDatabase,
+    Variances,
 };
 use chalk_solve::rust_ir::{
-    AdtDatum, AdtRepr, AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind,
-    FnDefDatum, FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum,
-    OpaqueTyDatum, TraitDatum, WellKnownTrait,
+    AdtDatum, AdtRepr, AssociatedConstDatum, AssociatedConstValue, AssociatedConstValueId,
+    AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind, FnDefDatum,
+    FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum, OpaqueTyDatum,
+    TraitDatum, WellKnownTrait,
 };
-use chalk_solve::{RustIrDatabase, Solution, SubstitutionResult};
+use chalk_solve::{ProofTree, RustIrDatabase, Solution, SubstitutionResult, UnprovableGoal};
 use salsa::Database;
+use std::collections::HashSet;
 use std::fmt;
 use std::sync::Arc;
 
@@ -35,6 +38,8 @@ impl ChalkDatabase {
         let mut db = ChalkDatabase::default();
         db.set_program_text(Arc::new(program_text.to_string()));
         db.set_solver_choice(solver_choice);
+        db.set_program_path(None);
+        db.set_prelude(None);
         db
     }
 
@@ -45,7 +50,10 @@ impl ChalkDatabase {
 
     pub fn parse_and_lower_goal(&self, text: &str) -> Result<Goal<ChalkIr>, ChalkError> {
         let program = self.checked_program()?;
-        Ok(lower_goal(&*chalk_parse::parse_goal(text)?, &*program)?)
+        lower_goal(&*chalk_parse::parse_goal(text)?, &*program).map_err(|e| {
+            let span = e.span();
+            ChalkError::spanned(e, text, span)
+        })
     }
 
     pub fn solve(
@@ -57,6 +65,58 @@ impl ChalkDatabase {
         solution
     }
 
+    /// Solves a given goal, producing the solution. Solving is stopped
+    /// as soon as `should_continue` returns `false`, in which case an
+    /// ambiguous solution is returned.
+    pub fn solve_limited(
+        &self,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+        should_continue: &dyn Fn() -> bool,
+    ) -> Option<Solution<ChalkIr>> {
+        let solver = self.solver();
+        let solution = solver
+            .lock()
+            .unwrap()
+            .solve_limited(self, goal, should_continue);
+        solution
+    }
+
+    /// Solves a given goal, producing the solution along with a proof tree
+    /// that shows how it was proven.
+    pub fn solve_with_proof_tree(
+        &self,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+    ) -> Option<(Solution<ChalkIr>, ProofTree<ChalkIr>)> {
+        let solver = self.solver();
+        let solution = solver.lock().unwrap().solve_with_proof_tree(self, goal);
+        solution
+    }
+
+    /// Explains why a given goal can't be solved, by returning the subgoals
+    /// that caused it to fail.
+    pub fn explain_failure(
+        &self,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+    ) -> Vec<UnprovableGoal<ChalkIr>> {
+        let solver = self.solver();
+        let causes = solver.lock().unwrap().explain_failure(self, goal);
+        causes
+    }
+
+    /// Returns the program clauses that the solvers consider when proving
+    /// a given domain goal, including the clauses from its environment.
+    /// Clauses that are produced more than once (e.g. a `FromEnv` rule that
+    /// comes from both the program and the environment) are only listed once.
+    pub fn program_clauses_for_goal(
+        &self,
+        goal: &UCanonical<InEnvironment<DomainGoal<ChalkIr>>>,
+    ) -> Result<Vec<ProgramClause<ChalkIr>>, Floundered> {
+        let mut seen = HashSet::new();
+        let mut clauses = chalk_solve::clauses::program_clauses_for_goal(self, goal)?;
+        clauses.retain(|clause| seen.insert(clause.clone()));
+        Ok(clauses)
+    }
+
     /// Solves a given goal, producing the solution. This will do only
     /// as much work towards `goal` as it has to (and that works is
     /// cached for future attempts). Calls provided function `f` to
@@ -106,6 +166,20 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
         self.program_ir().unwrap().associated_ty_values[&id].clone()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        self.program_ir().unwrap().associated_const_data(id)
+    }
+
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        self.program_ir().unwrap().associated_const_value(id)
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
         self.program_ir().unwrap().opaque_ty_data(id)
     }
@@ -235,6 +309,10 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
         self.program_ir().unwrap().assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<ChalkIr>) -> String {
+        self.program_ir().unwrap().assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<ChalkIr>) -> String {
         self.program_ir().unwrap().opaque_type_name(opaque_ty_id)
     }
@@ -246,6 +324,16 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
     fn discriminant_type(&self, ty: Ty<ChalkIr>) -> Ty<ChalkIr> {
         self.program_ir().unwrap().discriminant_type(ty)
     }
+
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<ChalkIr>,
+        unevaluated: &UnevaluatedConst<ChalkIr>,
+    ) -> Option<ConcreteConst<ChalkIr>> {
+        self.program_ir()
+            .unwrap()
+            .try_evaluate_const(ty, unevaluated)
+    }
 }
 
 impl fmt::Debug for ChalkDatabase {
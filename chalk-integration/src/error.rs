@@ -1,5 +1,5 @@
 use crate::interner::ChalkIr;
-use chalk_parse::ast::{Identifier, Kind};
+use chalk_parse::ast::{Identifier, Kind, Span};
 use chalk_solve::coherence::CoherenceError;
 use chalk_solve::wf::WfError;
 use string_cache::DefaultAtom as Atom;
@@ -13,6 +13,42 @@ pub struct ChalkError {
     error_text: String,
 }
 
+impl ChalkError {
+    /// Creates an error that shows where in the program (or goal) `text` it
+    /// occurred, by rendering the line and column of each of `spans` along
+    /// with the text they point at.
+    pub fn spanned(
+        error: impl std::fmt::Display,
+        text: &str,
+        spans: impl IntoIterator<Item = Span>,
+    ) -> Self {
+        Self::with_positions(
+            error,
+            spans.into_iter().map(|span| span.position_string(text)),
+        )
+    }
+
+    /// Creates an error followed by the given renderings of where it
+    /// occurred.
+    pub(crate) fn with_positions(
+        error: impl std::fmt::Display,
+        positions: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let mut error_text = error.to_string();
+        for position in positions {
+            error_text.push('\n');
+            error_text.push_str(&position);
+        }
+        ChalkError { error_text }
+    }
+}
+
+impl From<String> for ChalkError {
+    fn from(error_text: String) -> Self {
+        ChalkError { error_text }
+    }
+}
+
 impl From<Box<dyn std::error::Error>> for ChalkError {
     fn from(value: Box<dyn std::error::Error>) -> Self {
         ChalkError {
@@ -59,13 +95,16 @@ pub enum RustIrError {
     InvalidTraitName(Identifier),
     NotTrait(Identifier),
     NotStruct(Identifier),
-    DuplicateOrShadowedParameters,
+    /// The span is that of the innermost type, where clause or item that
+    /// declares the parameters, once it is known.
+    DuplicateOrShadowedParameters(Option<Span>),
     AutoTraitAssociatedTypes(Identifier),
     AutoTraitParameters(Identifier),
     AutoTraitWhereClauses(Identifier),
     InvalidFundamentalTypesParameters(Identifier),
     NegativeImplAssociatedValues(Identifier),
     MissingAssociatedType(Identifier),
+    MissingAssociatedConst(Identifier),
     IncorrectNumberOfVarianceParameters {
         identifier: Identifier,
         expected: usize,
@@ -96,8 +135,75 @@ pub enum RustIrError {
         expected: Kind,
         actual: Kind,
     },
+    IncorrectConstType {
+        identifier: Option<Identifier>,
+        expected: chalk_ir::Ty<ChalkIr>,
+        actual: chalk_ir::Ty<ChalkIr>,
+    },
+    ConstLiteralOutOfRange {
+        value: i128,
+        ty: chalk_ir::Ty<ChalkIr>,
+        span: Option<Span>,
+    },
     CannotApplyTypeParameter(Identifier),
-    InvalidExternAbi(Atom),
+    InvalidExternAbi(Atom, Option<Span>),
+}
+
+impl RustIrError {
+    /// The span of the program text that the error is about, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            RustIrError::InvalidParameterName(name)
+            | RustIrError::InvalidTraitName(name)
+            | RustIrError::NotTrait(name)
+            | RustIrError::NotStruct(name)
+            | RustIrError::AutoTraitAssociatedTypes(name)
+            | RustIrError::AutoTraitParameters(name)
+            | RustIrError::AutoTraitWhereClauses(name)
+            | RustIrError::InvalidFundamentalTypesParameters(name)
+            | RustIrError::NegativeImplAssociatedValues(name)
+            | RustIrError::MissingAssociatedType(name)
+            | RustIrError::MissingAssociatedConst(name)
+            | RustIrError::CannotApplyTypeParameter(name) => Some(name.span),
+            RustIrError::IncorrectNumberOfVarianceParameters { identifier, .. }
+            | RustIrError::IncorrectNumberOfTypeParameters { identifier, .. }
+            | RustIrError::IncorrectNumberOfAssociatedTypeParameters { identifier, .. }
+            | RustIrError::IncorrectParameterKind { identifier, .. }
+            | RustIrError::IncorrectTraitParameterKind { identifier, .. }
+            | RustIrError::IncorrectAssociatedTypeParameterKind { identifier, .. } => {
+                Some(identifier.span)
+            }
+            RustIrError::IncorrectConstType { identifier, .. } => {
+                identifier.as_ref().map(|identifier| identifier.span)
+            }
+            RustIrError::DuplicateOrShadowedParameters(span)
+            | RustIrError::InvalidExternAbi(_, span)
+            | RustIrError::ConstLiteralOutOfRange { span, .. } => *span,
+        }
+    }
+
+    /// Sets the span of an error that doesn't know where it occurred to
+    /// `span`, the span of the type, where clause or item being lowered.
+    pub(crate) fn or_span(self, span: Span) -> Self {
+        match self {
+            RustIrError::DuplicateOrShadowedParameters(None) => {
+                RustIrError::DuplicateOrShadowedParameters(Some(span))
+            }
+            RustIrError::InvalidExternAbi(abi, None) => {
+                RustIrError::InvalidExternAbi(abi, Some(span))
+            }
+            RustIrError::ConstLiteralOutOfRange {
+                value,
+                ty,
+                span: None,
+            } => RustIrError::ConstLiteralOutOfRange {
+                value,
+                ty,
+                span: Some(span),
+            },
+            error => error,
+        }
+    }
 }
 
 impl std::fmt::Display for RustIrError {
@@ -117,7 +223,7 @@ impl std::fmt::Display for RustIrError {
                 "expected a struct, found `{}`, which is not a struct",
                 name
             ),
-            RustIrError::DuplicateOrShadowedParameters => {
+            RustIrError::DuplicateOrShadowedParameters(_) => {
                 write!(f, "duplicate or shadowed parameters")
             }
             RustIrError::AutoTraitAssociatedTypes(name) => {
@@ -142,6 +248,9 @@ impl std::fmt::Display for RustIrError {
             RustIrError::MissingAssociatedType(name) => {
                 write!(f, "no associated type `{}` defined in trait", name)
             }
+            RustIrError::MissingAssociatedConst(name) => {
+                write!(f, "no associated const `{}` defined in trait", name)
+            }
             RustIrError::IncorrectNumberOfVarianceParameters {
                 identifier,
                 expected,
@@ -196,12 +305,61 @@ impl std::fmt::Display for RustIrError {
                 "incorrect associated type parameter kind for `{}`: expected {}, found {}",
                 identifier, expected, actual
             ),
+            RustIrError::IncorrectConstType {
+                identifier,
+                expected,
+                actual,
+            } => {
+                write!(f, "incorrect const type")?;
+                if let Some(identifier) = identifier {
+                    write!(f, " for `{}`", identifier)?;
+                }
+                write!(
+                    f,
+                    ": expected {}, found {}",
+                    const_ty_name(expected),
+                    const_ty_name(actual)
+                )
+            }
+            RustIrError::ConstLiteralOutOfRange { value, ty, .. } => write!(
+                f,
+                "literal `{}` does not fit in type {}",
+                value,
+                const_ty_name(ty)
+            ),
             RustIrError::CannotApplyTypeParameter(name) => {
                 write!(f, "cannot apply type parameter `{}`", name)
             }
-            RustIrError::InvalidExternAbi(abi) => write!(f, "invalid extern ABI `{}`", abi),
+            RustIrError::InvalidExternAbi(abi, _) => write!(f, "invalid extern ABI `{}`", abi),
         }
     }
 }
 
 impl std::error::Error for RustIrError {}
+
+/// Spells out the type of a const the way it's written in a program.
+fn const_ty_name(ty: &chalk_ir::Ty<ChalkIr>) -> String {
+    use chalk_ir::{IntTy, Scalar, TyKind, UintTy};
+    let scalar = match ty.kind(&ChalkIr) {
+        TyKind::Scalar(scalar) => scalar,
+        _ => return format!("{:?}", ty),
+    };
+    match scalar {
+        Scalar::Bool => "bool",
+        Scalar::Char => "char",
+        Scalar::Int(IntTy::Isize) => "isize",
+        Scalar::Int(IntTy::I8) => "i8",
+        Scalar::Int(IntTy::I16) => "i16",
+        Scalar::Int(IntTy::I32) => "i32",
+        Scalar::Int(IntTy::I64) => "i64",
+        Scalar::Int(IntTy::I128) => "i128",
+        Scalar::Uint(UintTy::Usize) => "usize",
+        Scalar::Uint(UintTy::U8) => "u8",
+        Scalar::Uint(UintTy::U16) => "u16",
+        Scalar::Uint(UintTy::U32) => "u32",
+        Scalar::Uint(UintTy::U64) => "u64",
+        Scalar::Uint(UintTy::U128) => "u128",
+        Scalar::Float(_) => return format!("{:?}", ty),
+    }
+    .to_string()
+}
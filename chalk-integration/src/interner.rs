@@ -1,13 +1,14 @@
 use crate::tls;
 use chalk_ir::{
     interner::{HasInterner, Interner},
-    TyKind,
+    IntTy, Scalar, TyKind, UintTy,
 };
 use chalk_ir::{
-    AdtId, AliasTy, AssocTypeId, CanonicalVarKind, CanonicalVarKinds, ConstData, Constraint,
-    Constraints, FnDefId, Goals, InEnvironment, Lifetime, OpaqueTy, OpaqueTyId,
-    ProgramClauseImplication, ProgramClauses, ProjectionTy, QuantifiedWhereClauses,
-    SeparatorTraitRef, Substitution, TraitId, Ty, TyData, VariableKind, VariableKinds, Variances,
+    AdtId, AliasTy, AssocConstId, AssocTypeId, CanonicalVarKind, CanonicalVarKinds, ConstData,
+    ConstValue, Constraint, Constraints, FnDefId, Goals, InEnvironment, Lifetime, OpaqueTy,
+    OpaqueTyId, ProgramClauseImplication, ProgramClauses, ProjectionConst, ProjectionTy,
+    QuantifiedWhereClauses, SeparatorTraitRef, Substitution, TraitId, Ty, TyData, UnevaluatedConst,
+    VariableKind, VariableKinds, Variances,
 };
 use chalk_ir::{
     GenericArg, GenericArgData, Goal, GoalData, LifetimeData, ProgramClause, ProgramClauseData,
@@ -50,6 +51,123 @@ impl Debug for ChalkFnAbi {
     }
 }
 
+/// The value of a concrete const. Integers of all types share the `Int`
+/// variant; the type of the const tells them apart.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChalkConstScalar {
+    Bool(bool),
+    Char(char),
+    Int(i128),
+}
+
+impl ChalkConstScalar {
+    /// Whether this is a valid value for a const of type `ty`.
+    pub fn fits(self, ty: &Ty<ChalkIr>) -> bool {
+        let (min, max) = match (self, ty.kind(&ChalkIr)) {
+            (ChalkConstScalar::Bool(_), TyKind::Scalar(Scalar::Bool))
+            | (ChalkConstScalar::Char(_), TyKind::Scalar(Scalar::Char)) => return true,
+            (ChalkConstScalar::Int(_), TyKind::Scalar(Scalar::Int(int_ty))) => match int_ty {
+                IntTy::I8 => (i8::MIN as i128, i8::MAX as i128),
+                IntTy::I16 => (i16::MIN as i128, i16::MAX as i128),
+                IntTy::I32 => (i32::MIN as i128, i32::MAX as i128),
+                IntTy::Isize | IntTy::I64 => (i64::MIN as i128, i64::MAX as i128),
+                IntTy::I128 => (i128::MIN, i128::MAX),
+            },
+            (ChalkConstScalar::Int(_), TyKind::Scalar(Scalar::Uint(uint_ty))) => match uint_ty {
+                UintTy::U8 => (0, u8::MAX as i128),
+                UintTy::U16 => (0, u16::MAX as i128),
+                UintTy::U32 => (0, u32::MAX as i128),
+                UintTy::Usize | UintTy::U64 => (0, u64::MAX as i128),
+                UintTy::U128 => (0, i128::MAX),
+            },
+            _ => return false,
+        };
+        match self {
+            ChalkConstScalar::Int(value) => min <= value && value <= max,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Debug for ChalkConstScalar {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChalkConstScalar::Bool(value) => write!(fmt, "{}", value),
+            ChalkConstScalar::Char(value) => write!(fmt, "{:?}", value),
+            ChalkConstScalar::Int(value) => write!(fmt, "{}", value),
+        }
+    }
+}
+
+/// An unevaluated const expression like `N + 1`. Parameters like `N` are
+/// referred to by their index in the substitution of the
+/// `UnevaluatedConst`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ChalkConstExpr {
+    Param(usize),
+    Value(i128),
+    Add(Box<ChalkConstExpr>, Box<ChalkConstExpr>),
+    Sub(Box<ChalkConstExpr>, Box<ChalkConstExpr>),
+    Mul(Box<ChalkConstExpr>, Box<ChalkConstExpr>),
+}
+
+impl ChalkConstExpr {
+    /// Evaluates the expression to a value of type `ty`, given the values of
+    /// its parameters. Returns `None` if a parameter isn't a concrete const,
+    /// or if the result doesn't fit in `ty`.
+    pub fn evaluate(
+        &self,
+        ty: &Ty<ChalkIr>,
+        params: &[GenericArg<ChalkIr>],
+    ) -> Option<ChalkConstScalar> {
+        let value = self.evaluate_scalar(params)?;
+        if value.fits(ty) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn evaluate_scalar(&self, params: &[GenericArg<ChalkIr>]) -> Option<ChalkConstScalar> {
+        let int = |expr: &ChalkConstExpr| match expr.evaluate_scalar(params)? {
+            ChalkConstScalar::Int(value) => Some(value),
+            _ => None,
+        };
+        let value = match self {
+            ChalkConstExpr::Param(index) => {
+                return match params[*index].constant(&ChalkIr)?.data(&ChalkIr).value {
+                    ConstValue::Concrete(ref concrete) => Some(concrete.interned),
+                    _ => None,
+                }
+            }
+            ChalkConstExpr::Value(value) => *value,
+            ChalkConstExpr::Add(l, r) => int(l)?.checked_add(int(r)?)?,
+            ChalkConstExpr::Sub(l, r) => int(l)?.checked_sub(int(r)?)?,
+            ChalkConstExpr::Mul(l, r) => int(l)?.checked_mul(int(r)?)?,
+        };
+        Some(ChalkConstScalar::Int(value))
+    }
+
+    fn fmt_with_params(
+        &self,
+        params: &[GenericArg<ChalkIr>],
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let (l, op, r) = match self {
+            ChalkConstExpr::Param(index) => return write!(fmt, "{:?}", params[*index]),
+            ChalkConstExpr::Value(value) => return write!(fmt, "{}", value),
+            ChalkConstExpr::Add(l, r) => (l, "+", r),
+            ChalkConstExpr::Sub(l, r) => (l, "-", r),
+            ChalkConstExpr::Mul(l, r) => (l, "*", r),
+        };
+        write!(fmt, "(")?;
+        l.fmt_with_params(params, fmt)?;
+        write!(fmt, " {} ", op)?;
+        r.fmt_with_params(params, fmt)?;
+        write!(fmt, ")")
+    }
+}
+
 /// The default "interner" and the only interner used by chalk
 /// itself. In this interner, no interning actually occurs.
 #[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -59,7 +177,8 @@ impl Interner for ChalkIr {
     type InternedType = Arc<TyData<ChalkIr>>;
     type InternedLifetime = LifetimeData<ChalkIr>;
     type InternedConst = Arc<ConstData<ChalkIr>>;
-    type InternedConcreteConst = u32;
+    type InternedConcreteConst = ChalkConstScalar;
+    type InternedUnevaluatedConst = ChalkConstExpr;
     type InternedGenericArg = GenericArgData<ChalkIr>;
     type InternedGoal = Arc<GoalData<ChalkIr>>;
     type InternedGoals = Vec<Goal<ChalkIr>>;
@@ -97,6 +216,25 @@ impl Interner for ChalkIr {
         tls::with_current_program(|prog| Some(prog?.debug_assoc_type_id(id, fmt)))
     }
 
+    fn debug_assoc_const_id(
+        id: AssocConstId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        tls::with_current_program(|prog| Some(prog?.debug_assoc_const_id(id, fmt)))
+    }
+
+    fn debug_unevaluated_const(
+        unevaluated: &UnevaluatedConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        let params = unevaluated.substitution.as_slice(&ChalkIr);
+        Some(
+            write!(fmt, "{{")
+                .and_then(|()| unevaluated.interned.fmt_with_params(params, fmt))
+                .and_then(|()| write!(fmt, "}}")),
+        )
+    }
+
     fn debug_opaque_ty_id(
         id: OpaqueTyId<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
@@ -119,6 +257,13 @@ impl Interner for ChalkIr {
         tls::with_current_program(|prog| Some(prog?.debug_projection_ty(proj, fmt)))
     }
 
+    fn debug_projection_const(
+        proj: &ProjectionConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        tls::with_current_program(|prog| Some(prog?.debug_projection_const(proj, fmt)))
+    }
+
     fn debug_opaque_ty(
         opaque_ty: &OpaqueTy<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
@@ -261,7 +406,12 @@ impl Interner for ChalkIr {
         constant
     }
 
-    fn const_eq(&self, _ty: &Arc<TyData<ChalkIr>>, c1: &u32, c2: &u32) -> bool {
+    fn const_eq(
+        &self,
+        _ty: &Arc<TyData<ChalkIr>>,
+        c1: &ChalkConstScalar,
+        c2: &ChalkConstScalar,
+    ) -> bool {
         c1 == c2
     }
 
@@ -399,6 +549,10 @@ impl Interner for ChalkIr {
     fn variances_data<'a>(&self, variances: &'a Self::InternedVariances) -> &'a [Variance] {
         variances
     }
+
+    fn is_rust_abi(&self, abi: ChalkFnAbi) -> bool {
+        abi == ChalkFnAbi::Rust
+    }
 }
 
 impl HasInterner for ChalkIr {
@@ -7,6 +7,7 @@ pub mod interner;
 pub mod lowering;
 pub mod program;
 pub mod program_environment;
+pub mod program_sources;
 pub mod query;
 pub mod test_macros;
 pub mod tls;
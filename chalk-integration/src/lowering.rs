@@ -14,7 +14,7 @@ use string_cache::DefaultAtom as Atom;
 use tracing::debug;
 
 use crate::error::RustIrError;
-use crate::interner::{ChalkFnAbi, ChalkIr};
+use crate::interner::{ChalkConstExpr, ChalkConstScalar, ChalkFnAbi, ChalkIr};
 use crate::program::Program as LoweredProgram;
 use crate::{Identifier as Ident, TypeSort};
 use env::*;
@@ -39,14 +39,20 @@ impl Lower for Program {
 
     fn lower(&self) -> Self::Lowered {
         let mut lowerer = ProgramLowerer::default();
+        let (program, modules) = self.flatten();
 
         // Make a vector mapping each thing in `items` to an id,
         // based just on its position:
-        let raw_ids = self.items.iter().map(|_| lowerer.next_item_id()).collect();
+        let raw_ids = program
+            .items
+            .iter()
+            .map(|_| lowerer.next_item_id())
+            .collect();
 
-        lowerer.extract_associated_types(self, &raw_ids)?;
-        lowerer.extract_ids(self, &raw_ids)?;
-        lowerer.lower(self, &raw_ids)
+        lowerer.extract_associated_types(&program, &raw_ids)?;
+        lowerer.extract_ids(&program, &raw_ids)?;
+        lowerer.extract_associated_consts(&program, &raw_ids, &modules)?;
+        lowerer.lower(&program, &raw_ids, &modules)
     }
 }
 
@@ -128,7 +134,13 @@ impl Lower for VariableKind {
                 n,
             ),
             VariableKind::Lifetime(n) => (chalk_ir::VariableKind::Lifetime, n),
-            VariableKind::Const(ref n) => (chalk_ir::VariableKind::Const(get_type_of_u32()), n),
+            VariableKind::Const(ref n, ty) => (
+                chalk_ir::VariableKind::Const(match ty {
+                    Some(ty) => chalk_ir::TyKind::Scalar(ty.lower()).intern(&ChalkIr),
+                    None => get_type_of_u32(),
+                }),
+                n,
+            ),
         };
 
         chalk_ir::WithKind::new(kind, n.str.clone())
@@ -196,7 +208,9 @@ impl LowerWithEnv for QuantifiedWhereClause {
     /// `Implemented(T: Foo)` and `ProjectionEq(<T as Foo>::Item = U)`.
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let variable_kinds = self.variable_kinds.iter().map(|k| k.lower());
-        let binders = env.in_binders(variable_kinds, |env| Ok(self.where_clause.lower(env)?))?;
+        let binders = env
+            .in_binders(variable_kinds, |env| Ok(self.where_clause.lower(env)?))
+            .map_err(|e| e.or_span(self.span))?;
         Ok(binders.into_iter().collect())
     }
 }
@@ -261,11 +275,10 @@ impl LowerWithEnv for LeafGoal {
             LeafGoal::DomainGoal { goal } => {
                 chalk_ir::Goal::all(interner, goal.lower(env)?.into_iter().casted(interner))
             }
-            LeafGoal::UnifyGenericArgs { a, b } => chalk_ir::EqGoal {
-                a: a.lower(env)?.cast(interner),
-                b: b.lower(env)?.cast(interner),
+            LeafGoal::UnifyGenericArgs { a, b } => {
+                let (a, b) = lower_unified_args(a, b, env)?;
+                chalk_ir::EqGoal { a, b }.cast::<chalk_ir::Goal<ChalkIr>>(interner)
             }
-            .cast::<chalk_ir::Goal<ChalkIr>>(interner),
             LeafGoal::SubtypeGenericArgs { a, b } => chalk_ir::SubtypeGoal {
                 a: a.lower(env)?,
                 b: b.lower(env)?,
@@ -386,7 +399,7 @@ impl Lower for FnAbi {
         match self.0.as_ref() {
             "Rust" => Ok(ChalkFnAbi::Rust),
             "C" => Ok(ChalkFnAbi::C),
-            _ => Err(RustIrError::InvalidExternAbi(self.0.clone())),
+            _ => Err(RustIrError::InvalidExternAbi(self.0.clone(), None)),
         }
     }
 }
@@ -451,29 +464,25 @@ impl LowerWithEnv for TraitBound {
             Err(RustIrError::NotTrait(self.trait_name.clone()))?;
         }
 
-        let parameters = self
-            .args_no_self
-            .iter()
-            .map(|a| a.lower(env))
-            .collect::<LowerResult<Vec<_>>>()?;
-
-        if parameters.len() != k.binders.len(interner) {
+        if self.args_no_self.len() != k.binders.len(interner) {
             Err(RustIrError::IncorrectNumberOfTypeParameters {
                 identifier: self.trait_name.clone(),
                 expected: k.binders.len(interner),
-                actual: parameters.len(),
+                actual: self.args_no_self.len(),
             })?;
         }
 
-        for (binder, param) in k.binders.binders.iter(interner).zip(parameters.iter()) {
-            if binder.kind() != param.kind() {
-                Err(RustIrError::IncorrectTraitParameterKind {
-                    identifier: self.trait_name.clone(),
-                    expected: binder.kind(),
-                    actual: param.kind(),
-                })?;
-            }
-        }
+        let parameters = lower_generic_args(
+            &self.trait_name,
+            &self.args_no_self,
+            k.binders.binders.iter(interner),
+            env,
+            |identifier, expected, actual| RustIrError::IncorrectTraitParameterKind {
+                identifier,
+                expected,
+                actual,
+            },
+        )?;
 
         Ok(rust_ir::TraitBound {
             trait_id,
@@ -488,29 +497,26 @@ impl LowerWithEnv for AliasEqBound {
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let trait_bound = self.trait_bound.lower(env)?;
         let lookup = env.lookup_associated_ty(trait_bound.trait_id, &self.name)?;
-        let args: Vec<_> = self
-            .args
-            .iter()
-            .map(|a| a.lower(env))
-            .collect::<LowerResult<_>>()?;
 
-        if args.len() != lookup.addl_variable_kinds.len() {
+        if self.args.len() != lookup.addl_variable_kinds.len() {
             Err(RustIrError::IncorrectNumberOfAssociatedTypeParameters {
                 identifier: self.name.clone(),
                 expected: lookup.addl_variable_kinds.len(),
-                actual: args.len(),
+                actual: self.args.len(),
             })?;
         }
 
-        for (param, arg) in lookup.addl_variable_kinds.iter().zip(args.iter()) {
-            if param.kind() != arg.kind() {
-                Err(RustIrError::IncorrectAssociatedTypeParameterKind {
-                    identifier: self.name.clone(),
-                    expected: param.kind(),
-                    actual: arg.kind(),
-                })?;
-            }
-        }
+        let args = lower_generic_args(
+            &self.name,
+            &self.args,
+            lookup.addl_variable_kinds.iter(),
+            env,
+            |identifier, expected, actual| RustIrError::IncorrectAssociatedTypeParameterKind {
+                identifier,
+                expected,
+                actual,
+            },
+        )?;
 
         Ok(rust_ir::AliasEqBound {
             trait_bound,
@@ -625,10 +631,6 @@ impl LowerWithEnv for ProjectionTy {
             substitution: trait_substitution,
         } = trait_ref.lower(env)?;
         let lookup = env.lookup_associated_ty(trait_id, name)?;
-        let mut args: Vec<_> = args
-            .iter()
-            .map(|a| a.lower(env))
-            .collect::<LowerResult<_>>()?;
 
         if args.len() != lookup.addl_variable_kinds.len() {
             Err(RustIrError::IncorrectNumberOfAssociatedTypeParameters {
@@ -638,15 +640,17 @@ impl LowerWithEnv for ProjectionTy {
             })?;
         }
 
-        for (param, arg) in lookup.addl_variable_kinds.iter().zip(args.iter()) {
-            if param.kind() != arg.kind() {
-                Err(RustIrError::IncorrectAssociatedTypeParameterKind {
-                    identifier: self.name.clone(),
-                    expected: param.kind(),
-                    actual: arg.kind(),
-                })?;
-            }
-        }
+        let mut args = lower_generic_args(
+            name,
+            args,
+            lookup.addl_variable_kinds.iter(),
+            env,
+            |identifier, expected, actual| RustIrError::IncorrectAssociatedTypeParameterKind {
+                identifier,
+                expected,
+                actual,
+            },
+        )?;
 
         args.extend(trait_substitution.iter(interner).cloned());
 
@@ -663,7 +667,7 @@ impl LowerWithEnv for Ty {
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let interner = env.interner();
         Ok(match self {
-            Ty::Id { name } => {
+            Ty::Id { name, .. } => {
                 let parameter = env.lookup_generic_arg(&name)?;
                 parameter.ty(interner).map(|ty| ty.clone()).ok_or_else(|| {
                     RustIrError::IncorrectParameterKind {
@@ -676,34 +680,37 @@ impl LowerWithEnv for Ty {
             Ty::Dyn {
                 ref bounds,
                 ref lifetime,
+                span,
             } => chalk_ir::TyKind::Dyn(chalk_ir::DynTy {
-                bounds: env.in_binders(
-                    // FIXME: Figure out a proper name for this type parameter
-                    Some(chalk_ir::WithKind::new(
-                        chalk_ir::VariableKind::Ty(TyVariableKind::General),
-                        Atom::from(FIXME_SELF),
-                    )),
-                    |env| {
-                        Ok(QuantifiedWhereClauses::from_iter(
-                            interner,
-                            bounds.lower(env)?.iter().flat_map(|qil| {
-                                qil.into_where_clauses(
-                                    interner,
-                                    chalk_ir::TyKind::BoundVar(BoundVar::new(
-                                        DebruijnIndex::INNERMOST,
-                                        0,
-                                    ))
-                                    .intern(interner),
-                                )
-                            }),
-                        ))
-                    },
-                )?,
+                bounds: env
+                    .in_binders(
+                        // FIXME: Figure out a proper name for this type parameter
+                        Some(chalk_ir::WithKind::new(
+                            chalk_ir::VariableKind::Ty(TyVariableKind::General),
+                            Atom::from(FIXME_SELF),
+                        )),
+                        |env| {
+                            Ok(QuantifiedWhereClauses::from_iter(
+                                interner,
+                                bounds.lower(env)?.iter().flat_map(|qil| {
+                                    qil.into_where_clauses(
+                                        interner,
+                                        chalk_ir::TyKind::BoundVar(BoundVar::new(
+                                            DebruijnIndex::INNERMOST,
+                                            0,
+                                        ))
+                                        .intern(interner),
+                                    )
+                                }),
+                            ))
+                        },
+                    )
+                    .map_err(|e| e.or_span(*span))?,
                 lifetime: lifetime.lower(env)?,
             })
             .intern(interner),
 
-            Ty::Apply { name, ref args } => {
+            Ty::Apply { name, ref args, .. } => {
                 macro_rules! tykind {
                     ($k:expr, $tykind:ident, $id:expr) => {{
                         if $k.binders.len(interner) != args.len() {
@@ -714,25 +721,22 @@ impl LowerWithEnv for Ty {
                             })?;
                         }
 
-                        let substitution = chalk_ir::Substitution::from_fallible(
+                        let substitution = chalk_ir::Substitution::from_iter(
                             interner,
-                            args.iter().map(|t| Ok(t.lower(env)?)),
-                        )?;
-
-                        for (param, arg) in $k
-                            .binders
-                            .binders
-                            .iter(interner)
-                            .zip(substitution.iter(interner))
-                        {
-                            if param.kind() != arg.kind() {
-                                Err(RustIrError::IncorrectParameterKind {
-                                    identifier: name.clone(),
-                                    expected: param.kind(),
-                                    actual: arg.kind(),
-                                })?;
-                            }
-                        }
+                            lower_generic_args(
+                                name,
+                                args,
+                                $k.binders.binders.iter(interner),
+                                env,
+                                |identifier, expected, actual| {
+                                    RustIrError::IncorrectParameterKind {
+                                        identifier,
+                                        expected,
+                                        actual,
+                                    }
+                                },
+                            )?,
+                        );
                         chalk_ir::TyKind::$tykind($id, substitution).intern(interner)
                     }};
                 }
@@ -751,7 +755,7 @@ impl LowerWithEnv for Ty {
                 }
             }
 
-            Ty::Projection { ref proj } => {
+            Ty::Projection { ref proj, .. } => {
                 chalk_ir::TyKind::Alias(chalk_ir::AliasTy::Projection(proj.lower(env)?))
                     .intern(interner)
             }
@@ -760,10 +764,13 @@ impl LowerWithEnv for Ty {
                 lifetime_names,
                 types,
                 sig,
+                span,
             } => {
-                let quantified_env = env.introduce(lifetime_names.iter().map(|id| {
-                    chalk_ir::WithKind::new(chalk_ir::VariableKind::Lifetime, id.str.clone())
-                }))?;
+                let quantified_env = env
+                    .introduce(lifetime_names.iter().map(|id| {
+                        chalk_ir::WithKind::new(chalk_ir::VariableKind::Lifetime, id.str.clone())
+                    }))
+                    .map_err(|e| e.or_span(*span))?;
 
                 let mut lowered_tys = Vec::with_capacity(types.len());
                 for ty in types {
@@ -773,11 +780,11 @@ impl LowerWithEnv for Ty {
                 let function = chalk_ir::FnPointer {
                     num_binders: lifetime_names.len(),
                     substitution: chalk_ir::FnSubst(Substitution::from_iter(interner, lowered_tys)),
-                    sig: sig.lower()?,
+                    sig: sig.lower().map_err(|e| e.or_span(*span))?,
                 };
                 chalk_ir::TyKind::Function(function).intern(interner)
             }
-            Ty::Tuple { ref types } => chalk_ir::TyKind::Tuple(
+            Ty::Tuple { ref types, .. } => chalk_ir::TyKind::Tuple(
                 types.len(),
                 chalk_ir::Substitution::from_fallible(
                     interner,
@@ -786,15 +793,15 @@ impl LowerWithEnv for Ty {
             )
             .intern(interner),
 
-            Ty::Scalar { ty } => chalk_ir::TyKind::Scalar(ty.lower()).intern(interner),
+            Ty::Scalar { ty, .. } => chalk_ir::TyKind::Scalar(ty.lower()).intern(interner),
 
-            Ty::Array { ty, len } => {
+            Ty::Array { ty, len, .. } => {
                 chalk_ir::TyKind::Array(ty.lower(env)?, len.lower(env)?).intern(interner)
             }
 
-            Ty::Slice { ty } => chalk_ir::TyKind::Slice(ty.lower(env)?).intern(interner),
+            Ty::Slice { ty, .. } => chalk_ir::TyKind::Slice(ty.lower(env)?).intern(interner),
 
-            Ty::Raw { mutability, ty } => {
+            Ty::Raw { mutability, ty, .. } => {
                 chalk_ir::TyKind::Raw(mutability.lower(), ty.lower(env)?).intern(interner)
             }
 
@@ -802,12 +809,13 @@ impl LowerWithEnv for Ty {
                 mutability,
                 lifetime,
                 ty,
+                ..
             } => chalk_ir::TyKind::Ref(mutability.lower(), lifetime.lower(env)?, ty.lower(env)?)
                 .intern(interner),
 
-            Ty::Str => chalk_ir::TyKind::Str.intern(interner),
+            Ty::Str { .. } => chalk_ir::TyKind::Str.intern(interner),
 
-            Ty::Never => chalk_ir::TyKind::Never.intern(interner),
+            Ty::Never { .. } => chalk_ir::TyKind::Never.intern(interner),
         })
     }
 }
@@ -816,25 +824,265 @@ impl LowerWithEnv for Const {
     type Lowered = chalk_ir::Const<ChalkIr>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
-        let interner = env.interner();
-        match self {
-            Const::Id(name) => {
-                let parameter = env.lookup_generic_arg(name)?;
-                parameter
-                    .constant(interner)
-                    .ok_or_else(|| RustIrError::IncorrectParameterKind {
-                        identifier: name.clone(),
-                        expected: Kind::Const,
-                        actual: parameter.kind(),
-                    })
-                    .map(|c| c.clone())
+        lower_const(self, None, env)
+    }
+}
+
+/// Lowers `c`, which is expected to be of type `ty` if that's known from the
+/// context. Untyped integer literals and const expressions take on that type;
+/// consts that already have some other type are left for the caller to
+/// reject.
+fn lower_const(
+    c: &Const,
+    ty: Option<&chalk_ir::Ty<ChalkIr>>,
+    env: &Env,
+) -> LowerResult<chalk_ir::Const<ChalkIr>> {
+    let interner = env.interner();
+    match c {
+        Const::Id(name) => {
+            let parameter = env.lookup_generic_arg(name)?;
+            parameter
+                .constant(interner)
+                .ok_or_else(|| RustIrError::IncorrectParameterKind {
+                    identifier: name.clone(),
+                    expected: Kind::Const,
+                    actual: parameter.kind(),
+                })
+                .cloned()
+        }
+        Const::Value(literal) => {
+            let (ty, interned) = match *literal {
+                ConstLiteral::Bool(value) => (
+                    chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Bool).intern(interner),
+                    ChalkConstScalar::Bool(value),
+                ),
+                ConstLiteral::Char(value) => (
+                    chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Char).intern(interner),
+                    ChalkConstScalar::Char(value),
+                ),
+                ConstLiteral::Int(value, Some(suffix)) => (
+                    chalk_ir::TyKind::Scalar(suffix.lower()).intern(interner),
+                    ChalkConstScalar::Int(value),
+                ),
+                ConstLiteral::Int(value, None) => (
+                    ty.filter(|ty| is_integer(ty))
+                        .cloned()
+                        .unwrap_or_else(get_type_of_u32),
+                    ChalkConstScalar::Int(value),
+                ),
+            };
+            if let ChalkConstScalar::Int(value) = interned {
+                if !interned.fits(&ty) {
+                    Err(RustIrError::ConstLiteralOutOfRange {
+                        value,
+                        ty: ty.clone(),
+                        span: None,
+                    })?;
+                }
+            }
+            Ok(chalk_ir::ConstData {
+                ty,
+                value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst { interned }),
+            }
+            .intern(interner))
+        }
+        Const::Projection(projection) => projection.lower(env),
+        Const::Unevaluated(expr) => {
+            let mut params = vec![];
+            let interned = lower_const_expr(expr, env, &mut params)?;
+
+            // Without an expected type, the expression has the type of the
+            // consts it refers to, and otherwise that of an untyped literal.
+            let ty = ty
+                .cloned()
+                .or_else(|| {
+                    params
+                        .first()
+                        .map(|(_, param)| param.data(interner).ty.clone())
+                })
+                .unwrap_or_else(get_type_of_u32);
+            for (name, param) in &params {
+                let actual = &param.data(interner).ty;
+                if *actual != ty {
+                    Err(RustIrError::IncorrectConstType {
+                        identifier: Some(name.clone()),
+                        expected: ty.clone(),
+                        actual: actual.clone(),
+                    })?;
+                }
+            }
+
+            Ok(chalk_ir::ConstData {
+                ty,
+                value: chalk_ir::ConstValue::Unevaluated(chalk_ir::UnevaluatedConst {
+                    interned,
+                    substitution: Substitution::from_iter(
+                        interner,
+                        params.into_iter().map(|(_, param)| param),
+                    ),
+                }),
+            }
+            .intern(interner))
+        }
+    }
+}
+
+fn is_integer(ty: &chalk_ir::Ty<ChalkIr>) -> bool {
+    matches!(
+        ty.kind(&ChalkIr),
+        chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Int(_))
+            | chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Uint(_))
+    )
+}
+
+/// Lowers a const expression, collecting the consts it refers to into
+/// `params` so that they end up in the substitution of the unevaluated const.
+fn lower_const_expr(
+    expr: &ConstExpr,
+    env: &Env,
+    params: &mut Vec<(Identifier, chalk_ir::Const<ChalkIr>)>,
+) -> LowerResult<ChalkConstExpr> {
+    let mut lower_boxed = |expr: &ConstExpr| lower_const_expr(expr, env, params).map(Box::new);
+    Ok(match expr {
+        ConstExpr::Id(name) => {
+            let param = Const::Id(name.clone()).lower(env)?;
+            params.push((name.clone(), param));
+            ChalkConstExpr::Param(params.len() - 1)
+        }
+        ConstExpr::Value(value) => ChalkConstExpr::Value(*value),
+        ConstExpr::Add(l, r) => ChalkConstExpr::Add(lower_boxed(l)?, lower_boxed(r)?),
+        ConstExpr::Sub(l, r) => ChalkConstExpr::Sub(lower_boxed(l)?, lower_boxed(r)?),
+        ConstExpr::Mul(l, r) => ChalkConstExpr::Mul(lower_boxed(l)?, lower_boxed(r)?),
+    })
+}
+
+/// Lowers the two sides of a goal like `a = b`. An untyped integer literal on
+/// either side takes the type of the const on the other side, as in `N = 3`,
+/// and consts on both sides must have the same type.
+fn lower_unified_args(
+    a: &GenericArg,
+    b: &GenericArg,
+    env: &Env,
+) -> LowerResult<(chalk_ir::GenericArg<ChalkIr>, chalk_ir::GenericArg<ChalkIr>)> {
+    let interner = env.interner();
+    let is_untyped_literal = |arg: &GenericArg| {
+        matches!(
+            arg,
+            GenericArg::Const(Const::Value(ConstLiteral::Int(_, None)))
+        )
+    };
+    let name = |arg: &GenericArg| match arg {
+        GenericArg::Id(name) | GenericArg::Const(Const::Id(name)) => Some(name.clone()),
+        _ => None,
+    };
+
+    // Lower the side that has a type of its own first, so that its type can
+    // be given to the other side.
+    let swapped = is_untyped_literal(a);
+    let (first, second) = if swapped { (b, a) } else { (a, b) };
+    let first_lowered = first.lower(env)?;
+    let expected = first_lowered
+        .constant(interner)
+        .map(|c| c.data(interner).ty.clone());
+    let second_lowered = match (second, &expected) {
+        (GenericArg::Const(c), Some(ty)) => lower_const(c, Some(ty), env)?.cast(interner),
+        _ => second.lower(env)?,
+    };
+
+    if let (Some(expected), Some(c)) = (expected, second_lowered.constant(interner)) {
+        let actual = &c.data(interner).ty;
+        if *actual != expected {
+            Err(RustIrError::IncorrectConstType {
+                identifier: name(second),
+                expected,
+                actual: actual.clone(),
+            })?;
+        }
+    }
+
+    Ok(if swapped {
+        (second_lowered, first_lowered)
+    } else {
+        (first_lowered, second_lowered)
+    })
+}
+
+/// Lowers the generic arguments `args` given to `identifier`, whose
+/// parameters have the kinds `kinds`, reporting arguments of the wrong kind
+/// with `kind_error`. Untyped integer literals take the type of the const
+/// parameter they're given for; other consts must already have that type.
+fn lower_generic_args<'k>(
+    identifier: &Identifier,
+    args: &[GenericArg],
+    kinds: impl Iterator<Item = &'k chalk_ir::VariableKind<ChalkIr>>,
+    env: &Env,
+    kind_error: fn(Identifier, Kind, Kind) -> RustIrError,
+) -> LowerResult<Vec<chalk_ir::GenericArg<ChalkIr>>> {
+    let interner = env.interner();
+    args.iter()
+        .zip(kinds)
+        .map(|(arg, kind)| {
+            let arg = match (arg, kind) {
+                (GenericArg::Const(c), chalk_ir::VariableKind::Const(ty)) => {
+                    lower_const(c, Some(ty), env)?.cast(interner)
+                }
+                _ => arg.lower(env)?,
+            };
+            if kind.kind() != arg.kind() {
+                Err(kind_error(identifier.clone(), kind.kind(), arg.kind()))?;
             }
-            Const::Value(value) => Ok(chalk_ir::ConstData {
-                ty: get_type_of_u32(),
-                value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst { interned: *value }),
+            if let (chalk_ir::VariableKind::Const(ty), Some(c)) = (kind, arg.constant(interner)) {
+                let actual = &c.data(interner).ty;
+                if actual != ty {
+                    Err(RustIrError::IncorrectConstType {
+                        identifier: Some(identifier.clone()),
+                        expected: ty.clone(),
+                        actual: actual.clone(),
+                    })?;
+                }
             }
-            .intern(interner)),
+            Ok(arg)
+        })
+        .collect()
+}
+
+impl LowerWithEnv for AssocConstValue {
+    type Lowered = chalk_ir::Const<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let interner = env.interner();
+        let ty = self.ty.lower(env)?;
+        let value = lower_const(&self.value, Some(&ty), env)?;
+        let actual = &value.data(interner).ty;
+        if *actual != ty {
+            Err(RustIrError::IncorrectConstType {
+                identifier: Some(self.name.clone()),
+                expected: ty.clone(),
+                actual: actual.clone(),
+            })?;
         }
+        Ok(value)
+    }
+}
+
+impl LowerWithEnv for ProjectionConst {
+    type Lowered = chalk_ir::Const<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let interner = env.interner();
+        let chalk_ir::TraitRef {
+            trait_id,
+            substitution,
+        } = self.trait_ref.lower(env)?;
+        let lookup = env.lookup_associated_const(trait_id, &self.name)?;
+        Ok(chalk_ir::ConstData {
+            ty: lookup.ty.clone().substitute(interner, &substitution),
+            value: chalk_ir::ConstValue::Projection(chalk_ir::ProjectionConst {
+                associated_const_id: lookup.id,
+                substitution,
+            }),
+        }
+        .intern(interner))
     }
 }
 
@@ -884,18 +1132,27 @@ impl LowerWithEnv for Lifetime {
     }
 }
 
-impl LowerWithEnv for (&Impl, ImplId<ChalkIr>, &AssociatedTyValueIds) {
+impl LowerWithEnv
+    for (
+        &Impl,
+        ImplId<ChalkIr>,
+        &AssociatedTyValueIds,
+        &AssociatedConstValueIds,
+    )
+{
     type Lowered = rust_ir::ImplDatum<ChalkIr>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
-        let (impl_, impl_id, associated_ty_value_ids) = self;
+        let (impl_, impl_id, associated_ty_value_ids, associated_const_value_ids) = self;
 
         let polarity = impl_.polarity.lower();
         let binders = env.in_binders(impl_.all_parameters(), |env| {
             let trait_ref = impl_.trait_ref.lower(env)?;
             debug!(?trait_ref);
 
-            if !polarity.is_positive() && !impl_.assoc_ty_values.is_empty() {
+            if !polarity.is_positive()
+                && (!impl_.assoc_ty_values.is_empty() || !impl_.assoc_const_values.is_empty())
+            {
                 Err(RustIrError::NegativeImplAssociatedValues(
                     impl_.trait_ref.trait_name.clone(),
                 ))?;
@@ -920,11 +1177,18 @@ impl LowerWithEnv for (&Impl, ImplId<ChalkIr>, &AssociatedTyValueIds) {
 
         debug!(?associated_ty_value_ids);
 
+        let associated_const_value_ids = impl_
+            .assoc_const_values
+            .iter()
+            .map(|acv| associated_const_value_ids[&(*impl_id, acv.name.str.clone())])
+            .collect();
+
         Ok(rust_ir::ImplDatum {
             polarity,
             binders,
             impl_type: impl_.impl_type.lower(),
             associated_ty_value_ids,
+            associated_const_value_ids,
         })
     }
 }
@@ -998,11 +1262,22 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
             .map(|defn| env.lookup_associated_ty(*trait_id, &defn.name).unwrap().id)
             .collect();
 
+        let associated_const_ids: Vec<_> = trait_defn
+            .assoc_const_defns
+            .iter()
+            .map(|defn| {
+                env.lookup_associated_const(*trait_id, &defn.name)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+
         let trait_datum = rust_ir::TraitDatum {
             id: *trait_id,
             binders,
             flags: trait_defn.flags.lower(),
             associated_ty_ids,
+            associated_const_ids,
             well_known: trait_defn.well_known.map(|def| def.lower()),
         };
 
@@ -1031,6 +1306,18 @@ pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir
         })
         .collect();
 
+    let associated_const_lookups: BTreeMap<_, _> = program
+        .associated_const_data
+        .iter()
+        .map(|(&associated_const_id, datum)| {
+            let lookup = AssociatedConstLookup {
+                id: associated_const_id,
+                ty: datum.ty.clone(),
+            };
+            ((datum.trait_id, datum.name.clone()), lookup)
+        })
+        .collect();
+
     let auto_traits = program
         .trait_data
         .iter()
@@ -1051,9 +1338,11 @@ pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir
         trait_kinds: &program.trait_kinds,
         opaque_ty_kinds: &program.opaque_ty_kinds,
         associated_ty_lookups: &associated_ty_lookups,
+        associated_const_lookups: &associated_const_lookups,
         foreign_ty_ids: &program.foreign_ty_ids,
         parameter_map: BTreeMap::new(),
         auto_traits: &auto_traits,
+        module: &Atom::from(""),
     };
 
     goal.lower(&env)
@@ -1133,6 +1422,7 @@ impl Lower for WellKnownTrait {
             WellKnownTrait::Unpin => rust_ir::WellKnownTrait::Unpin,
             WellKnownTrait::CoerceUnsized => rust_ir::WellKnownTrait::CoerceUnsized,
             WellKnownTrait::DiscriminantKind => rust_ir::WellKnownTrait::DiscriminantKind,
+            WellKnownTrait::Generator => rust_ir::WellKnownTrait::Generator,
         }
     }
 }
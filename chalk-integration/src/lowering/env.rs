@@ -5,7 +5,7 @@ use chalk_ir::{
 };
 use chalk_ir::{cast::Cast, ForeignDefId, WithKind};
 use chalk_parse::ast::*;
-use chalk_solve::rust_ir::AssociatedTyValueId;
+use chalk_solve::rust_ir::{AssociatedConstValueId, AssociatedTyValueId};
 use std::collections::BTreeMap;
 
 use crate::error::RustIrError;
@@ -28,6 +28,10 @@ pub type GeneratorKinds = BTreeMap<chalk_ir::GeneratorId<ChalkIr>, TypeKind>;
 pub type AssociatedTyLookups = BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssociatedTyLookup>;
 pub type AssociatedTyValueIds =
     BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedTyValueId<ChalkIr>>;
+pub type AssociatedConstLookups =
+    BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssociatedConstLookup>;
+pub type AssociatedConstValueIds =
+    BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedConstValueId<ChalkIr>>;
 pub type ForeignIds = BTreeMap<Ident, chalk_ir::ForeignDefId<ChalkIr>>;
 
 pub type ParameterMap = BTreeMap<Ident, chalk_ir::WithKind<ChalkIr, BoundVar>>;
@@ -47,6 +51,7 @@ pub struct Env<'k> {
     pub opaque_ty_ids: &'k OpaqueTyIds,
     pub opaque_ty_kinds: &'k OpaqueTyVariableKinds,
     pub associated_ty_lookups: &'k AssociatedTyLookups,
+    pub associated_const_lookups: &'k AssociatedConstLookups,
     pub auto_traits: &'k AutoTraits,
     pub foreign_ty_ids: &'k ForeignIds,
     pub generator_ids: &'k GeneratorIds,
@@ -54,6 +59,9 @@ pub struct Env<'k> {
     /// GenericArg identifiers are used as keys, therefore
     /// all identifiers in an environment must be unique (no shadowing).
     pub parameter_map: ParameterMap,
+    /// The path of the module that names are looked up in, e.g. `a::b`,
+    /// which is empty at the top level.
+    pub module: &'k Ident,
 }
 
 /// Information about an associated type **declaration** (i.e., an
@@ -75,6 +83,24 @@ pub struct AssociatedTyLookup {
     pub addl_variable_kinds: Vec<chalk_ir::VariableKind<ChalkIr>>,
 }
 
+/// Information about an associated const **declaration** (i.e., an
+/// `AssociatedConstDatum`), used to lookup the id and the type of an
+/// associated const when lowering a projection like `<T as Foo>::N`.
+///
+/// ```ignore
+/// trait Foo {
+///     const N: u32; // <-- associated const declaration
+///           // ---
+///           // |
+///           // ty, bound by the trait parameters
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct AssociatedConstLookup {
+    pub id: chalk_ir::AssocConstId<ChalkIr>,
+    pub ty: chalk_ir::Binders<chalk_ir::Ty<ChalkIr>>,
+}
+
 pub enum TypeLookup<'k> {
     Parameter(&'k WithKind<ChalkIr, BoundVar>),
     Adt(AdtId<ChalkIr>),
@@ -150,26 +176,46 @@ impl Env<'_> {
         }
     }
 
+    /// The names that `name` may refer to, from the one in the current
+    /// module to the one at the top level: in module `a::b`, `Foo` may refer
+    /// to `a::b::Foo`, `a::Foo` or `Foo`. Paths starting with `crate::` are
+    /// always looked up from the top level.
+    fn qualified_names(&self, name: &Identifier) -> Vec<Ident> {
+        if let Some(path) = name.str.strip_prefix("crate::") {
+            return vec![Ident::from(path)];
+        }
+        let mut names = vec![];
+        let mut module: &str = self.module;
+        while !module.is_empty() {
+            names.push(Ident::from(format!("{}::{}", module, name.str)));
+            module = module.rfind("::").map_or("", |i| &module[..i]);
+        }
+        names.push(name.str.clone());
+        names
+    }
+
     pub fn lookup_type(&self, name: &Identifier) -> LowerResult<TypeLookup> {
         if let Some(id) = self.parameter_map.get(&name.str) {
-            Ok(TypeLookup::Parameter(id))
-        } else if let Some(id) = self.adt_ids.get(&name.str) {
-            Ok(TypeLookup::Adt(*id))
-        } else if let Some(id) = self.fn_def_ids.get(&name.str) {
-            Ok(TypeLookup::FnDef(*id))
-        } else if let Some(id) = self.closure_ids.get(&name.str) {
-            Ok(TypeLookup::Closure(*id))
-        } else if let Some(id) = self.opaque_ty_ids.get(&name.str) {
-            Ok(TypeLookup::Opaque(*id))
-        } else if let Some(id) = self.foreign_ty_ids.get(&name.str) {
-            Ok(TypeLookup::Foreign(*id))
-        } else if let Some(id) = self.trait_ids.get(&name.str) {
-            Ok(TypeLookup::Trait(*id))
-        } else if let Some(id) = self.generator_ids.get(&name.str) {
-            Ok(TypeLookup::Generator(*id))
-        } else {
-            Err(RustIrError::NotStruct(name.clone()))
+            return Ok(TypeLookup::Parameter(id));
+        }
+        for name in self.qualified_names(name) {
+            if let Some(id) = self.adt_ids.get(&name) {
+                return Ok(TypeLookup::Adt(*id));
+            } else if let Some(id) = self.fn_def_ids.get(&name) {
+                return Ok(TypeLookup::FnDef(*id));
+            } else if let Some(id) = self.closure_ids.get(&name) {
+                return Ok(TypeLookup::Closure(*id));
+            } else if let Some(id) = self.opaque_ty_ids.get(&name) {
+                return Ok(TypeLookup::Opaque(*id));
+            } else if let Some(id) = self.foreign_ty_ids.get(&name) {
+                return Ok(TypeLookup::Foreign(*id));
+            } else if let Some(id) = self.trait_ids.get(&name) {
+                return Ok(TypeLookup::Trait(*id));
+            } else if let Some(id) = self.generator_ids.get(&name) {
+                return Ok(TypeLookup::Generator(*id));
+            }
         }
+        Err(RustIrError::NotStruct(name.clone()))
     }
 
     pub fn auto_trait(&self, id: chalk_ir::TraitId<ChalkIr>) -> bool {
@@ -177,15 +223,17 @@ impl Env<'_> {
     }
 
     pub fn lookup_trait(&self, name: &Identifier) -> LowerResult<TraitId<ChalkIr>> {
-        if let Some(_) = self.parameter_map.get(&name.str) {
-            Err(RustIrError::NotTrait(name.clone()))
-        } else if let Some(_) = self.adt_ids.get(&name.str) {
-            Err(RustIrError::NotTrait(name.clone()))
-        } else if let Some(id) = self.trait_ids.get(&name.str) {
-            Ok(*id)
-        } else {
-            Err(RustIrError::InvalidTraitName(name.clone()))
+        if self.parameter_map.contains_key(&name.str) {
+            return Err(RustIrError::NotTrait(name.clone()));
         }
+        for qualified_name in self.qualified_names(name) {
+            if self.adt_ids.contains_key(&qualified_name) {
+                return Err(RustIrError::NotTrait(name.clone()));
+            } else if let Some(id) = self.trait_ids.get(&qualified_name) {
+                return Ok(*id);
+            }
+        }
+        Err(RustIrError::InvalidTraitName(name.clone()))
     }
 
     pub fn trait_kind(&self, id: chalk_ir::TraitId<ChalkIr>) -> &TypeKind {
@@ -222,6 +270,16 @@ impl Env<'_> {
             .ok_or(RustIrError::MissingAssociatedType(ident.clone()))
     }
 
+    pub fn lookup_associated_const(
+        &self,
+        trait_id: TraitId<ChalkIr>,
+        ident: &Identifier,
+    ) -> LowerResult<&AssociatedConstLookup> {
+        self.associated_const_lookups
+            .get(&(trait_id, ident.str.clone()))
+            .ok_or(RustIrError::MissingAssociatedConst(ident.clone()))
+    }
+
     /// Introduces new parameters, shifting the indices of existing
     /// parameters to accommodate them. The indices of the new binders
     /// will be assigned in order as they are iterated.
@@ -253,7 +311,7 @@ impl Env<'_> {
             .chain(binders)
             .collect();
         if parameter_map.len() != self.parameter_map.len() + len {
-            Err(RustIrError::DuplicateOrShadowedParameters)?;
+            Err(RustIrError::DuplicateOrShadowedParameters(None))?;
         }
         Ok(Env {
             parameter_map,
@@ -3,15 +3,17 @@ use crate::{tls, Identifier, TypeKind};
 use chalk_ir::{could_match::CouldMatch, UnificationDatabase};
 use chalk_ir::{debug::Angle, Variance};
 use chalk_ir::{
-    debug::SeparatorTraitRef, AdtId, AliasTy, AssocTypeId, Binders, CanonicalVarKinds, ClosureId,
-    FnDefId, ForeignDefId, GeneratorId, GenericArg, Goal, Goals, ImplId, IntTy, Lifetime, OpaqueTy,
-    OpaqueTyId, ProgramClause, ProgramClauseImplication, ProgramClauses, ProjectionTy, Scalar,
-    Substitution, TraitId, Ty, TyKind, UintTy, Variances,
+    debug::SeparatorTraitRef, AdtId, AliasTy, AssocConstId, AssocTypeId, Binders,
+    CanonicalVarKinds, ClosureId, ConcreteConst, FnDefId, ForeignDefId, GeneratorId, GenericArg,
+    Goal, Goals, ImplId, IntTy, Lifetime, OpaqueTy, OpaqueTyId, ProgramClause,
+    ProgramClauseImplication, ProgramClauses, ProjectionConst, ProjectionTy, Scalar, Substitution,
+    TraitId, Ty, TyKind, UintTy, UnevaluatedConst, Variances,
 };
 use chalk_solve::rust_ir::{
-    AdtDatum, AdtRepr, AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind,
-    FnDefDatum, FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum,
-    ImplType, OpaqueTyDatum, TraitDatum, WellKnownTrait,
+    AdtDatum, AdtRepr, AssociatedConstDatum, AssociatedConstValue, AssociatedConstValueId,
+    AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind, FnDefDatum,
+    FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum, ImplType,
+    OpaqueTyDatum, TraitDatum, WellKnownTrait,
 };
 use chalk_solve::split::Split;
 use chalk_solve::RustIrDatabase;
@@ -76,6 +78,10 @@ pub struct Program {
     pub associated_ty_values:
         BTreeMap<AssociatedTyValueId<ChalkIr>, Arc<AssociatedTyValue<ChalkIr>>>,
 
+    /// For each associated const value `const N: T = XXX` found in an impl:
+    pub associated_const_values:
+        BTreeMap<AssociatedConstValueId<ChalkIr>, Arc<AssociatedConstValue<ChalkIr>>>,
+
     // From opaque type name to item-id. Used during lowering only.
     pub opaque_ty_ids: BTreeMap<Identifier, OpaqueTyId<ChalkIr>>,
 
@@ -97,6 +103,9 @@ pub struct Program {
     /// For each associated ty declaration `type Foo` found in a trait:
     pub associated_ty_data: BTreeMap<AssocTypeId<ChalkIr>, Arc<AssociatedTyDatum<ChalkIr>>>,
 
+    /// For each associated const declaration `const N: T` found in a trait:
+    pub associated_const_data: BTreeMap<AssocConstId<ChalkIr>, Arc<AssociatedConstDatum<ChalkIr>>>,
+
     /// For each user-specified clause
     pub custom_clauses: Vec<ProgramClause<ChalkIr>>,
 
@@ -161,6 +170,20 @@ impl tls::DebugContext for Program {
         }
     }
 
+    fn debug_assoc_const_id(
+        &self,
+        assoc_const_id: AssocConstId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        if let Some(d) = self.associated_const_data.get(&assoc_const_id) {
+            write!(fmt, "({:?}::{})", d.trait_id, d.name)
+        } else {
+            fmt.debug_struct("InvalidAssocConstId")
+                .field("index", &assoc_const_id.0)
+                .finish()
+        }
+    }
+
     fn debug_opaque_ty_id(
         &self,
         opaque_ty_id: OpaqueTyId<ChalkIr>,
@@ -217,6 +240,25 @@ impl tls::DebugContext for Program {
         )
     }
 
+    fn debug_projection_const(
+        &self,
+        projection_const: &ProjectionConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let interner = self.interner();
+        let associated_const_data =
+            &self.associated_const_data[&projection_const.associated_const_id];
+        let trait_params = projection_const.substitution.as_slice(interner);
+        write!(
+            fmt,
+            "<{:?} as {:?}{:?}>::{}",
+            &trait_params[0],
+            associated_const_data.trait_id,
+            Angle(&trait_params[1..]),
+            associated_const_data.name,
+        )
+    }
+
     fn debug_opaque_ty(
         &self,
         opaque_ty: &OpaqueTy<ChalkIr>,
@@ -403,6 +445,20 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.associated_ty_values[&id].clone()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        self.associated_const_data[&id].clone()
+    }
+
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        self.associated_const_values[&id].clone()
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
         self.opaque_ty_data[&id].clone()
     }
@@ -531,8 +587,11 @@ impl RustIrDatabase<ChalkIr> for Program {
         &ChalkIr
     }
 
+    // Chalk doesn't know about methods, so `#[object_safe]` stands for the
+    // trait's methods being object safe; the rest is checked here.
     fn is_object_safe(&self, trait_id: TraitId<ChalkIr>) -> bool {
         self.object_safe_traits.contains(&trait_id)
+            && chalk_solve::object_safety::is_object_safe(self, trait_id)
     }
 
     // For all the closure functions: this is different than how rustc does it.
@@ -576,6 +635,25 @@ impl RustIrDatabase<ChalkIr> for Program {
         self
     }
 
+    // The default implementations of the `*_name` methods sanitize the names
+    // written by the `debug_*` methods, which would turn the qualified name
+    // `a::Foo` of an item in a module into `a__Foo`.
+    fn trait_name(&self, trait_id: TraitId<ChalkIr>) -> String {
+        self.trait_kinds[&trait_id].name.to_string()
+    }
+
+    fn adt_name(&self, adt_id: AdtId<ChalkIr>) -> String {
+        self.adt_kinds[&adt_id].name.to_string()
+    }
+
+    fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<ChalkIr>) -> String {
+        self.opaque_ty_kinds[&opaque_ty_id].name.to_string()
+    }
+
+    fn fn_def_name(&self, fn_def_id: FnDefId<ChalkIr>) -> String {
+        self.fn_def_kinds[&fn_def_id].name.to_string()
+    }
+
     // The default implementation for `RustIrDatabase::assoc_type_name` outputs
     // the name in the format `(Trait::AssocTypeName)`, which is reformatted to
     // `_Trait__AssocTypeName_`. This doesn't match the input names, which is
@@ -590,6 +668,22 @@ impl RustIrDatabase<ChalkIr> for Program {
             .to_string()
     }
 
+    // See `assoc_type_name` above.
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<ChalkIr>) -> String {
+        self.associated_const_data[&assoc_const_id].name.to_string()
+    }
+
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<ChalkIr>,
+        unevaluated: &UnevaluatedConst<ChalkIr>,
+    ) -> Option<ConcreteConst<ChalkIr>> {
+        let params = unevaluated.substitution.as_slice(self.interner());
+        Some(ConcreteConst {
+            interned: unevaluated.interned.evaluate(ty, params)?,
+        })
+    }
+
     // Mirrors current (07a63e6d1fabf3560e8e1e17c1d56b10a06152d9) implementation in rustc
     fn discriminant_type(&self, ty: Ty<ChalkIr>) -> Ty<ChalkIr> {
         let interner = self.interner();
@@ -0,0 +1,257 @@
+//! Resolves the `include` items of a program and adds its prelude.
+//!
+//! The text of every included file (and of the prelude) is appended to the
+//! program text, so the whole program can be parsed at once while the spans
+//! of the program's own items stay the same. `ProgramSources` remembers
+//! where each file starts, so that errors can point at the right file.
+
+use crate::error::ChalkError;
+use chalk_parse::ast::{Item, Program, Span, TraitDefn};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A prelude declaring the well-known traits, for use with
+/// `LoweringDatabase::set_prelude`.
+pub const STD_PRELUDE: &str = include_str!("prelude.chalk");
+
+/// The text of a program followed by the text of its prelude and of the
+/// files it includes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramSources {
+    text: String,
+
+    /// The files whose text was appended to the program text, in order.
+    files: Vec<SourceFile>,
+
+    /// The offset at which the text of the prelude starts, if there is one.
+    /// The files included by the prelude come after it.
+    prelude_start: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SourceFile {
+    /// The path of the file, or `prelude` for the prelude.
+    name: String,
+
+    /// The offset at which the file's text starts in `ProgramSources::text`.
+    start: usize,
+}
+
+impl ProgramSources {
+    /// Collects the files included by `program_text`, which was read from
+    /// `program_path` if it is set. Relative paths in the program text and
+    /// in the prelude are resolved against the directory of `program_path`
+    /// and the current directory respectively. Each file is included at
+    /// most once, and include cycles are reported as errors.
+    pub fn load(
+        program_text: &str,
+        program_path: Option<&Path>,
+        prelude: Option<&str>,
+    ) -> Result<Self, ChalkError> {
+        let mut loader = Loader {
+            sources: ProgramSources {
+                text: program_text.to_string(),
+                files: vec![],
+                prelude_start: None,
+            },
+            included: vec![],
+            stack: vec![],
+        };
+
+        let program_dir = match program_path {
+            Some(path) => {
+                let canonical = canonicalize(path)?;
+                loader.included.push(canonical.clone());
+                loader.stack.push((canonical, path.display().to_string()));
+                parent_dir(path)
+            }
+            None => PathBuf::new(),
+        };
+        loader.include_all(None, program_text, &program_dir)?;
+        loader.stack.clear();
+
+        if let Some(prelude) = prelude {
+            loader.append("prelude".to_string(), prelude);
+            loader.sources.prelude_start = loader.sources.files.last().map(|file| file.start);
+            loader.include_all(Some("prelude"), prelude, Path::new(""))?;
+        }
+
+        Ok(loader.sources)
+    }
+
+    /// The text to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Removes the items of the prelude (and of the files it includes) that
+    /// the program declares itself, i.e. those with the same name as an item
+    /// of the program, and traits with the same `#[lang]` attribute as a
+    /// trait of the program.
+    pub fn remove_declared_prelude_items(&self, program: &mut Program) {
+        let prelude_start = match self.prelude_start {
+            Some(start) => start,
+            None => return,
+        };
+        let in_prelude = |item: &Item| item.span().lo >= prelude_start;
+
+        let mut names = HashSet::new();
+        let mut well_known = vec![];
+        let (flattened, _) = program.flatten();
+        for item in flattened.items.iter().filter(|item| !in_prelude(item)) {
+            if let Some(name) = item.name() {
+                names.insert(name.str.clone());
+            }
+            if let Item::TraitDefn(TraitDefn {
+                well_known: Some(trait_),
+                ..
+            }) = item
+            {
+                well_known.push(*trait_);
+            }
+        }
+
+        program.items.retain(|item| {
+            if !in_prelude(item) {
+                return true;
+            }
+            let declared_name = match item.name() {
+                Some(name) => names.contains(&name.str),
+                None => false,
+            };
+            let declared_lang_item = match item {
+                Item::TraitDefn(TraitDefn {
+                    well_known: Some(trait_),
+                    ..
+                }) => well_known.contains(trait_),
+                _ => false,
+            };
+            !declared_name && !declared_lang_item
+        });
+    }
+
+    /// Shows where `span` is, see `Span::position_string`. Spans in the
+    /// prelude and in included files are shown relative to that file, along
+    /// with its name.
+    pub fn position_string(&self, span: Span) -> String {
+        let index = self.files.iter().rposition(|file| file.start <= span.lo);
+        match index {
+            None => {
+                let end = self.files.first().map_or(self.text.len(), |f| f.start);
+                span.position_string(&self.text[..end])
+            }
+            Some(index) => {
+                let file = &self.files[index];
+                let end = self
+                    .files
+                    .get(index + 1)
+                    .map_or(self.text.len(), |f| f.start);
+                let span = Span::new(span.lo - file.start, span.hi - file.start);
+                format!(
+                    "in `{}`:\n{}",
+                    file.name,
+                    span.position_string(&self.text[file.start..end])
+                )
+            }
+        }
+    }
+}
+
+struct Loader {
+    sources: ProgramSources,
+
+    /// The canonical paths of the files that were included so far.
+    included: Vec<PathBuf>,
+
+    /// The canonical paths and names of the files whose includes are being
+    /// resolved, outermost first.
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl Loader {
+    fn append(&mut self, name: String, text: &str) {
+        let sources = &mut self.sources;
+        sources.text.push('\n');
+        sources.files.push(SourceFile {
+            name,
+            start: sources.text.len(),
+        });
+        sources.text.push_str(text);
+    }
+
+    /// Includes the files included by the items of `text`, whose relative
+    /// paths are resolved against `dir`. `name` is the name of the file
+    /// `text` comes from, unless it is the program text.
+    fn include_all(
+        &mut self,
+        name: Option<&str>,
+        text: &str,
+        dir: &Path,
+    ) -> Result<(), ChalkError> {
+        let program = chalk_parse::parse_program(text).map_err(|e| match name {
+            Some(name) => format!("in `{}`:\n{}", name, e),
+            None => e.to_string(),
+        })?;
+        for item in &program.items {
+            match item {
+                Item::Include(include) => self.include(&dir.join(&include.path))?,
+                Item::Module(module) => check_no_includes(&module.items)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn include(&mut self, path: &Path) -> Result<(), ChalkError> {
+        let canonical = canonicalize(path)?;
+        let name = path.display().to_string();
+        if let Some(start) = self.stack.iter().position(|(file, _)| *file == canonical) {
+            let cycle: Vec<_> = self.stack[start..]
+                .iter()
+                .map(|(_, name)| name)
+                .chain(Some(&name))
+                .map(|name| format!("`{}`", name))
+                .collect();
+            Err(format!("include cycle: {}", cycle.join(" -> ")))?;
+        }
+        if self.included.contains(&canonical) {
+            return Ok(());
+        }
+
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("cannot read `{}`: {}", name, e))?;
+        self.included.push(canonical.clone());
+        self.append(name.clone(), &text);
+
+        self.stack.push((canonical, name.clone()));
+        self.include_all(Some(&name), &text, &parent_dir(path))?;
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// Files can only be included at the top level, as their items are added
+/// there.
+fn check_no_includes(items: &[Item]) -> Result<(), ChalkError> {
+    for item in items {
+        match item {
+            Item::Include(include) => Err(format!(
+                "`include \"{}\";` is not allowed in a module",
+                include.path
+            ))?,
+            Item::Module(module) => check_no_includes(&module.items)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, ChalkError> {
+    fs::canonicalize(path)
+        .map_err(|e| ChalkError::from(format!("cannot find `{}`: {}", path.display(), e)))
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map_or_else(PathBuf::new, Path::to_path_buf)
+}
@@ -6,13 +6,15 @@ use crate::interner::ChalkIr;
 use crate::lowering::Lower;
 use crate::program::Program;
 use crate::program_environment::ProgramEnvironment;
+use crate::program_sources::ProgramSources;
 use crate::tls;
-use crate::SolverChoice;
+use crate::{RawId, SolverChoice};
 use chalk_ir::{Substitution, TraitId};
+use chalk_parse::ast;
 use chalk_solve::clauses::builder::ClauseBuilder;
 use chalk_solve::clauses::program_clauses::ToProgramClauses;
 use chalk_solve::coherence::orphan;
-use chalk_solve::coherence::{CoherenceSolver, SpecializationPriorities};
+use chalk_solve::coherence::{CoherenceError, CoherenceSolver, SpecializationPriorities};
 use chalk_solve::wf;
 use chalk_solve::RustIrDatabase;
 use chalk_solve::Solver;
@@ -20,7 +22,9 @@ use salsa::Database;
 use std::clone::Clone;
 use std::cmp::{Eq, PartialEq};
 use std::collections::BTreeMap;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -34,6 +38,24 @@ pub trait LoweringDatabase:
     #[salsa::input]
     fn solver_choice(&self) -> SolverChoice;
 
+    /// The path of the file the program text was read from, against which
+    /// the paths of its `include` items are resolved. If it is not set, they
+    /// are resolved against the current directory.
+    #[salsa::input]
+    fn program_path(&self) -> Option<Arc<PathBuf>>;
+
+    /// Items that are added to every program, e.g. `STD_PRELUDE`.
+    #[salsa::input]
+    fn prelude(&self) -> Option<Arc<String>>;
+
+    /// The program text along with that of the prelude and the included
+    /// files. These are read again only when one of the inputs changes.
+    fn program_sources(&self) -> Result<Arc<ProgramSources>, ChalkError>;
+
+    /// The parsed program sources, which are also used to point errors at
+    /// the items they are about.
+    fn program_ast(&self) -> Result<Arc<ast::Program>, ChalkError>;
+
     fn program_ir(&self) -> Result<Arc<Program>, ChalkError>;
 
     /// Performs coherence check and computes which impls specialize
@@ -52,10 +74,10 @@ pub trait LoweringDatabase:
 
     /// Creates the solver we can use to solve goals. This solver
     /// stores intermediate, cached state, which is why it is behind a
-    /// mutex. Moreover, if the set of program clauses change, that
-    /// cached state becomes invalid, so the query is marked as
-    /// volatile, thus ensuring that the solver is recreated in every
-    /// revision (i.e., each time source program changes).
+    /// mutex. Moreover, if the program changes, that cached state
+    /// becomes invalid, so the query depends on the lowered program,
+    /// thus ensuring that the solver is recreated whenever the lowered
+    /// program changes (but not if e.g. only comments are edited).
     // HACK: salsa requires that queries return types that implement `Eq`
     fn solver(&self) -> ArcEq<Mutex<Box<dyn Solver<ChalkIr>>>>;
 }
@@ -122,9 +144,65 @@ impl<T> Clone for ArcEq<T> {
     }
 }
 
+fn program_sources(db: &dyn LoweringDatabase) -> Result<Arc<ProgramSources>, ChalkError> {
+    let sources = ProgramSources::load(
+        &db.program_text(),
+        db.program_path().as_deref().map(PathBuf::as_path),
+        db.prelude().as_deref().map(String::as_str),
+    )?;
+    Ok(Arc::new(sources))
+}
+
+fn program_ast(db: &dyn LoweringDatabase) -> Result<Arc<ast::Program>, ChalkError> {
+    let sources = db.program_sources()?;
+    let mut program = chalk_parse::parse_program(sources.text())?;
+    sources.remove_declared_prelude_items(&mut program);
+    Ok(Arc::new(program))
+}
+
 fn program_ir(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
-    let text = db.program_text();
-    Ok(Arc::new(chalk_parse::parse_program(&text)?.lower()?))
+    let program = db.program_ast()?.lower().map_err(|e| {
+        let span = e.span();
+        spanned_error(db, e, span)
+    })?;
+    Ok(Arc::new(program))
+}
+
+/// Creates an error pointing at the given spans of the program sources.
+fn spanned_error(
+    db: &dyn LoweringDatabase,
+    error: impl fmt::Display,
+    spans: impl IntoIterator<Item = ast::Span>,
+) -> ChalkError {
+    match db.program_sources() {
+        Ok(sources) => ChalkError::with_positions(
+            error,
+            spans.into_iter().map(|span| sources.position_string(span)),
+        ),
+        Err(e) => e,
+    }
+}
+
+/// Creates an error about the items with the given ids, pointing at their
+/// text.
+fn item_error(
+    db: &dyn LoweringDatabase,
+    error: impl fmt::Display,
+    ids: impl IntoIterator<Item = RawId>,
+) -> ChalkError {
+    match db.program_ast() {
+        // Items are numbered in the order they appear in the flattened
+        // program, see `Lower for Program`.
+        Ok(program) => {
+            let (program, _) = program.flatten();
+            let spans = ids
+                .into_iter()
+                .filter_map(|id| program.items.get(id.index as usize))
+                .map(ast::Item::span);
+            spanned_error(db, error, spans)
+        }
+        Err(e) => e,
+    }
 }
 
 fn orphan_check(db: &dyn LoweringDatabase) -> Result<(), ChalkError> {
@@ -134,7 +212,8 @@ fn orphan_check(db: &dyn LoweringDatabase) -> Result<(), ChalkError> {
         let local_impls = program.local_impl_ids();
         for impl_id in local_impls {
             let mut solver = db.solver_choice().into_solver();
-            orphan::perform_orphan_check::<ChalkIr>(db.upcast(), &mut *solver, impl_id)?;
+            orphan::perform_orphan_check::<ChalkIr>(db.upcast(), &mut *solver, impl_id)
+                .map_err(|e| item_error(db, e.display(db.upcast()), Some(impl_id.0)))?;
         }
         Ok(())
     })
@@ -153,7 +232,17 @@ fn coherence(
             .map(|&trait_id| {
                 let solver: CoherenceSolver<ChalkIr> =
                     CoherenceSolver::new(db.upcast(), &solver_builder, trait_id);
-                let priorities = solver.specialization_priorities()?;
+                let priorities = solver.specialization_priorities().map_err(|e| {
+                    let impl_ids = match &e {
+                        CoherenceError::OverlappingImpls { impls, .. } => vec![impls.0, impls.1],
+                        CoherenceError::FailedOrphanCheck(_) => vec![],
+                    };
+                    item_error(
+                        db,
+                        e.display(db.upcast()),
+                        impl_ids.into_iter().map(|impl_id| impl_id.0),
+                    )
+                })?;
                 Ok((trait_id, priorities))
             })
             .collect();
@@ -174,15 +263,21 @@ fn checked_program(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError
         let solver_builder = || solver_choice.into_solver();
         let solver: wf::WfSolver<ChalkIr> = wf::WfSolver::new(db.upcast(), &solver_builder);
         for &id in program.adt_data.keys() {
-            solver.verify_adt_decl(id)?;
+            solver
+                .verify_adt_decl(id)
+                .map_err(|e| item_error(db, e.display(db.upcast()), Some(id.0)))?;
         }
 
         for &opaque_ty_id in program.opaque_ty_data.keys() {
-            solver.verify_opaque_ty_decl(opaque_ty_id)?;
+            solver
+                .verify_opaque_ty_decl(opaque_ty_id)
+                .map_err(|e| item_error(db, e.display(db.upcast()), Some(opaque_ty_id.0)))?;
         }
 
         for &impl_id in program.impl_data.keys() {
-            solver.verify_trait_impl(impl_id)?;
+            solver
+                .verify_trait_impl(impl_id)
+                .map_err(|e| item_error(db, e.display(db.upcast()), Some(impl_id.0)))?;
         }
 
         Ok(())
@@ -242,6 +337,11 @@ fn environment(db: &dyn LoweringDatabase) -> Result<Arc<ProgramEnvironment>, Cha
                 .iter()
                 .map(|&atv_id| db.associated_ty_value(atv_id))
                 .for_each(|atv| atv.to_program_clauses(builder, &env));
+            datum
+                .associated_const_value_ids
+                .iter()
+                .map(|&acv_id| db.associated_const_value(acv_id))
+                .for_each(|acv| acv.to_program_clauses(builder, &env));
         }
     }
 
@@ -249,7 +349,9 @@ fn environment(db: &dyn LoweringDatabase) -> Result<Arc<ProgramEnvironment>, Cha
 }
 
 fn solver(db: &dyn LoweringDatabase) -> ArcEq<Mutex<Box<dyn Solver<ChalkIr>>>> {
-    db.salsa_runtime().report_untracked_read();
+    // Errors are reported by whoever uses the solver, we only need to
+    // record the dependency here.
+    let _ = db.program_ir();
     let choice = db.solver_choice();
     ArcEq::new(Mutex::new(choice.into_solver()))
 }
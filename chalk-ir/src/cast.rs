@@ -136,6 +136,18 @@ impl<I: Interner> CastTo<DomainGoal<I>> for Normalize<I> {
     }
 }
 
+impl<I: Interner> CastTo<DomainGoal<I>> for NormalizeConst<I> {
+    fn cast_to(self, _interner: &I) -> DomainGoal<I> {
+        DomainGoal::NormalizeConst(self)
+    }
+}
+
+impl<I: Interner> CastTo<DomainGoal<I>> for EvaluateConst<I> {
+    fn cast_to(self, _interner: &I) -> DomainGoal<I> {
+        DomainGoal::EvaluateConst(self)
+    }
+}
+
 impl<I: Interner> CastTo<DomainGoal<I>> for WellFormed<I> {
     fn cast_to(self, _interner: &I) -> DomainGoal<I> {
         DomainGoal::WellFormed(self)
@@ -23,6 +23,13 @@ impl<I: Interner> Debug for AssocTypeId<I> {
     }
 }
 
+impl<I: Interner> Debug for AssocConstId<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_assoc_const_id(*self, fmt)
+            .unwrap_or_else(|| write!(fmt, "AssocConstId({:?})", self.0))
+    }
+}
+
 impl<I: Interner> Debug for FnDefId<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
         I::debug_fn_def_id(*self, fmt).unwrap_or_else(|| write!(fmt, "FnDefId({:?})", self.0))
@@ -144,6 +151,25 @@ impl<I: Interner> Debug for ProjectionTy<I> {
     }
 }
 
+impl<I: Interner> Debug for ProjectionConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_projection_const(self, fmt).unwrap_or_else(|| {
+            write!(
+                fmt,
+                "({:?}){:?}",
+                self.associated_const_id, self.substitution
+            )
+        })
+    }
+}
+
+impl<I: Interner> Debug for UnevaluatedConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_unevaluated_const(self, fmt)
+            .unwrap_or_else(|| write!(fmt, "{{{:?}}}{:?}", self.interned, self.substitution))
+    }
+}
+
 impl<I: Interner> Debug for OpaqueTy<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         I::debug_opaque_ty(self, fmt).unwrap_or_else(|| {
@@ -359,6 +385,8 @@ impl<I: Interner> Debug for ConstData<I> {
             ConstValue::InferenceVar(var) => write!(fmt, "{:?}", var),
             ConstValue::Placeholder(index) => write!(fmt, "{:?}", index),
             ConstValue::Concrete(evaluated) => write!(fmt, "{:?}", evaluated),
+            ConstValue::Projection(projection) => write!(fmt, "{:?}", projection),
+            ConstValue::Unevaluated(unevaluated) => write!(fmt, "{:?}", unevaluated),
         }
     }
 }
@@ -766,6 +794,26 @@ impl<I: Interner> Debug for Normalize<I> {
     }
 }
 
+impl<I: Interner> Debug for NormalizeConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            fmt,
+            "NormalizeConst({:?} -> {:?})",
+            self.projection, self.value
+        )
+    }
+}
+
+impl<I: Interner> Debug for EvaluateConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            fmt,
+            "EvaluateConst({:?} -> {:?})",
+            self.unevaluated, self.value
+        )
+    }
+}
+
 impl<I: Interner> Debug for AliasEq<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         write!(fmt, "AliasEq({:?} = {:?})", self.alias, self.ty)
@@ -808,6 +856,8 @@ impl<I: Interner> Debug for DomainGoal<I> {
             DomainGoal::WellFormed(n) => write!(fmt, "{:?}", n),
             DomainGoal::FromEnv(n) => write!(fmt, "{:?}", n),
             DomainGoal::Normalize(n) => write!(fmt, "{:?}", n),
+            DomainGoal::NormalizeConst(n) => write!(fmt, "{:?}", n),
+            DomainGoal::EvaluateConst(n) => write!(fmt, "{:?}", n),
             DomainGoal::IsLocal(n) => write!(fmt, "IsLocal({:?})", n),
             DomainGoal::IsUpstream(n) => write!(fmt, "IsUpstream({:?})", n),
             DomainGoal::IsFullyVisible(n) => write!(fmt, "IsFullyVisible({:?})", n),
@@ -583,6 +583,22 @@ where
                 }),
             }
             .intern(folder.interner())),
+            ConstValue::Projection(projection) => Ok(ConstData {
+                ty: fold_ty()?,
+                value: ConstValue::Projection(projection.clone().fold_with(folder, outer_binder)?),
+            }
+            .intern(folder.interner())),
+            ConstValue::Unevaluated(unevaluated) => Ok(ConstData {
+                ty: fold_ty()?,
+                value: ConstValue::Unevaluated(UnevaluatedConst {
+                    interned: unevaluated.interned.clone(),
+                    substitution: unevaluated
+                        .substitution
+                        .clone()
+                        .fold_with(folder, outer_binder)?,
+                }),
+            }
+            .intern(folder.interner())),
         }
     }
 }
@@ -249,6 +249,7 @@ id_fold!(ImplId);
 id_fold!(AdtId);
 id_fold!(TraitId);
 id_fold!(AssocTypeId);
+id_fold!(AssocConstId);
 id_fold!(OpaqueTyId);
 id_fold!(FnDefId);
 id_fold!(ClosureId);
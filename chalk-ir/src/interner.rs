@@ -1,5 +1,6 @@
 //! Encapsulates the concrete representation of core types such as types and goals.
 use crate::AliasTy;
+use crate::AssocConstId;
 use crate::AssocTypeId;
 use crate::CanonicalVarKind;
 use crate::CanonicalVarKinds;
@@ -23,6 +24,7 @@ use crate::ProgramClause;
 use crate::ProgramClauseData;
 use crate::ProgramClauseImplication;
 use crate::ProgramClauses;
+use crate::ProjectionConst;
 use crate::ProjectionTy;
 use crate::QuantifiedWhereClause;
 use crate::QuantifiedWhereClauses;
@@ -31,6 +33,7 @@ use crate::Substitution;
 use crate::TraitId;
 use crate::Ty;
 use crate::TyData;
+use crate::UnevaluatedConst;
 use crate::VariableKind;
 use crate::VariableKinds;
 use crate::Variance;
@@ -96,6 +99,15 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
     /// evaluated consts.
     type InternedConcreteConst: Debug + Clone + Eq + Hash;
 
+    /// Representation of a const expression that hasn't been evaluated
+    /// yet, like `N + 1` or `size_of::<T>()`. We refer to it through
+    /// `UnevaluatedConst<Self>`, which pairs it with the substitution for
+    /// the generic parameters it mentions.
+    ///
+    /// Like `InternedConcreteConst`, chalk never creates these, it only
+    /// hands them back to `RustIrDatabase::try_evaluate_const`.
+    type InternedUnevaluatedConst: Debug + Clone + Eq + Hash;
+
     /// "Interned" representation of a "generic parameter", which can
     /// be either a type or a lifetime.  In normal user code,
     /// `Self::InternedGenericArg` is not referenced. Instead, we refer to
@@ -227,6 +239,16 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         None
     }
 
+    /// Prints the debug representation of an associated-const-id.
+    /// Returns `None` to fallback to the default debug output.
+    #[allow(unused_variables)]
+    fn debug_assoc_const_id(
+        const_id: AssocConstId<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
     /// Prints the debug representation of an opaque type.
     /// Returns `None` to fallback to the default debug output.
     #[allow(unused_variables)]
@@ -299,6 +321,26 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         None
     }
 
+    /// Prints the debug representation of a ProjectionConst.
+    /// Returns `None` to fallback to the default debug output.
+    #[allow(unused_variables)]
+    fn debug_projection_const(
+        projection_const: &ProjectionConst<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
+    /// Prints the debug representation of an UnevaluatedConst.
+    /// Returns `None` to fallback to the default debug output.
+    #[allow(unused_variables)]
+    fn debug_unevaluated_const(
+        unevaluated_const: &UnevaluatedConst<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
     /// Prints the debug representation of an OpaqueTy.
     /// Returns `None` to fallback to the default debug output.
     #[allow(unused_variables)]
@@ -652,6 +694,14 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
     /// Lookup the slice of `Variance` that was interned to
     /// create a `Variances`.
     fn variances_data<'a>(&self, variances: &'a Self::InternedVariances) -> &'a [Variance];
+
+    /// Returns true if `abi` is the Rust ABI. Only safe, non-variadic
+    /// functions using the Rust ABI implement the `Fn` family of traits.
+    /// By default, every ABI is treated as the Rust ABI.
+    #[allow(unused_variables)]
+    fn is_rust_abi(&self, abi: Self::FnAbi) -> bool {
+        true
+    }
 }
 
 /// Implemented by types that have an associated interner (which
@@ -41,6 +41,7 @@ pub struct NoSolution;
 
 /// Indicates that the complete set of program clauses for this goal
 /// cannot be enumerated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Floundered;
 
 macro_rules! impl_debugs {
@@ -383,6 +384,13 @@ pub struct ClauseId<I: Interner>(pub I::DefId);
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct AssocTypeId<I: Interner>(pub I::DefId);
 
+/// The id for the associated const member of a trait. The details of the
+/// const can be found by invoking the [`associated_const_data`] method.
+///
+/// [`associated_const_data`]: ../chalk_solve/trait.RustIrDatabase.html#tymethod.associated_const_data
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssocConstId<I: Interner>(pub I::DefId);
+
 /// Id for an opaque type.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OpaqueTyId<I: Interner>(pub I::DefId);
@@ -553,7 +561,8 @@ bitflags! {
         const HAS_TY_PROJECTION           = 1 << 7;
         /// Does the type contain an opaque type
         const HAS_TY_OPAQUE               = 1 << 8;
-        /// Does the type contain an unevaluated const projection
+        /// Does the type contain an associated const projection or an
+        /// unevaluated const
         const HAS_CT_PROJECTION           = 1 << 9;
         /// Does the type contain an error
         const HAS_ERROR                   = 1 << 10;
@@ -718,6 +727,14 @@ impl<I: Interner> TyKind<I> {
                         ConstValue::Placeholder(_) => {
                             TypeFlags::HAS_CT_PLACEHOLDER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
                         }
+                        ConstValue::Projection(ref projection) => {
+                            TypeFlags::HAS_CT_PROJECTION
+                                | projection.substitution.compute_flags(interner)
+                        }
+                        ConstValue::Unevaluated(ref unevaluated) => {
+                            TypeFlags::HAS_CT_PROJECTION
+                                | unevaluated.substitution.compute_flags(interner)
+                        }
                     }
             }
             TyKind::Placeholder(_) => TypeFlags::HAS_TY_PLACEHOLDER,
@@ -1180,6 +1197,10 @@ impl<I: Interner> Const<I> {
             ConstValue::InferenceVar(_) => false,
             ConstValue::Placeholder(_) => false,
             ConstValue::Concrete(_) => false,
+            ConstValue::Projection(projection) => projection.substitution.has_free_vars(interner),
+            ConstValue::Unevaluated(unevaluated) => {
+                unevaluated.substitution.has_free_vars(interner)
+            }
         }
     }
 }
@@ -1204,9 +1225,19 @@ pub enum ConstValue<I: Interner> {
     Placeholder(PlaceholderIndex),
     /// Concrete constant value.
     Concrete(ConcreteConst<I>),
+    /// An associated const projection like `<T as Trait>::N`.
+    Projection(ProjectionConst<I>),
+    /// A const expression like `N + 1` that is yet to be evaluated.
+    Unevaluated(UnevaluatedConst<I>),
 }
 
-impl<I: Interner> Copy for ConstValue<I> where I::InternedConcreteConst: Copy {}
+impl<I: Interner> Copy for ConstValue<I>
+where
+    I::InternedConcreteConst: Copy,
+    I::InternedUnevaluatedConst: Copy,
+    I::InternedSubstitution: Copy,
+{
+}
 
 impl<I: Interner> ConstData<I> {
     /// Wraps the constant data in a `Const`.
@@ -1230,6 +1261,25 @@ impl<I: Interner> ConcreteConst<I> {
     }
 }
 
+/// Constant expression that has not been evaluated yet, because it
+/// depends on generic parameters (e.g. `N + 1`). The expression itself is
+/// opaque to chalk, it can be evaluated with
+/// `RustIrDatabase::try_evaluate_const` once the parameters are known.
+#[derive(Clone, PartialEq, Eq, Hash, HasInterner)]
+pub struct UnevaluatedConst<I: Interner> {
+    /// The interned expression.
+    pub interned: I::InternedUnevaluatedConst,
+    /// The generic parameters mentioned by the expression.
+    pub substitution: Substitution<I>,
+}
+
+impl<I: Interner> Copy for UnevaluatedConst<I>
+where
+    I::InternedUnevaluatedConst: Copy,
+    I::InternedSubstitution: Copy,
+{
+}
+
 /// A Rust lifetime.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, HasInterner)]
 pub struct Lifetime<I: Interner> {
@@ -1514,6 +1564,16 @@ impl<I: Interner> GenericArg<I> {
                             | TypeFlags::STILL_FURTHER_SPECIALIZABLE
                     }
                     ConstValue::Concrete(_) => flags,
+                    ConstValue::Projection(ref projection) => {
+                        flags
+                            | TypeFlags::HAS_CT_PROJECTION
+                            | projection.substitution.compute_flags(interner)
+                    }
+                    ConstValue::Unevaluated(ref unevaluated) => {
+                        flags
+                            | TypeFlags::HAS_CT_PROJECTION
+                            | unevaluated.substitution.compute_flags(interner)
+                    }
                 }
             }
         }
@@ -1657,6 +1717,28 @@ impl<I: Interner> ProjectionTy<I> {
     }
 }
 
+/// A projection `<P0 as TraitName<P1..Pn>>::ASSOC_CONST`.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
+pub struct ProjectionConst<I: Interner> {
+    /// The id for the associated const member.
+    pub associated_const_id: AssocConstId<I>,
+    /// The substitution for the projection.
+    pub substitution: Substitution<I>,
+}
+
+impl<I: Interner> Copy for ProjectionConst<I> where I::InternedSubstitution: Copy {}
+
+impl<I: Interner> ProjectionConst<I> {
+    /// Gets the type parameters of the `Self` type in this projection.
+    pub fn self_type_parameter(&self, interner: &I) -> Ty<I> {
+        self.substitution
+            .iter(interner)
+            .find_map(move |p| p.ty(interner))
+            .unwrap()
+            .clone()
+    }
+}
+
 /// An opaque type `opaque type T<..>: Trait = HiddenTy`.
 #[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
 pub struct OpaqueTy<I: Interner> {
@@ -1850,6 +1932,13 @@ pub enum DomainGoal<I: Interner> {
     /// True if the alias type can be normalized to some other type
     Normalize(Normalize<I>),
 
+    /// True if the associated const projection can be normalized to some
+    /// other const
+    NormalizeConst(NormalizeConst<I>),
+
+    /// True if the unevaluated const evaluates to some other const
+    EvaluateConst(EvaluateConst<I>),
+
     /// True if a type is considered to have been "defined" by the current crate. This is true for
     /// a `struct Foo { }` but false for a `#[upstream] struct Foo { }`. However, for fundamental types
     /// like `Box<T>`, it is true if `T` is local.
@@ -1913,6 +2002,7 @@ where
     I::InternedSubstitution: Copy,
     I::InternedLifetime: Copy,
     I::InternedType: Copy,
+    I::InternedConst: Copy,
 {
 }
 
@@ -2032,6 +2122,37 @@ where
 {
 }
 
+/// Proves that the given associated const projection **normalizes** to the
+/// given const. A projection `<T as Trait>::N` normalizes to the const `C`
+/// if we can **match it to an impl** and that impl has a `const N: .. = V`
+/// where `C = V`.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, Zip)]
+#[allow(missing_docs)]
+pub struct NormalizeConst<I: Interner> {
+    pub projection: ProjectionConst<I>,
+    pub value: Const<I>,
+}
+
+impl<I: Interner> Copy for NormalizeConst<I>
+where
+    I::InternedSubstitution: Copy,
+    I::InternedConst: Copy,
+{
+}
+
+/// Proves that the given unevaluated const (a const whose value is a
+/// `ConstValue::Unevaluated`) **evaluates** to the given const. This can
+/// only be decided once the generic parameters of the unevaluated const
+/// are known, until then it is ambiguous.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, Zip)]
+#[allow(missing_docs)]
+pub struct EvaluateConst<I: Interner> {
+    pub unevaluated: Const<I>,
+    pub value: Const<I>,
+}
+
+impl<I: Interner> Copy for EvaluateConst<I> where I::InternedConst: Copy {}
+
 /// Proves **equality** between an alias and a type.
 #[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, Zip)]
 #[allow(missing_docs)]
@@ -2623,6 +2744,7 @@ where
     I::InternedLifetime: Copy,
     I::InternedGenericArg: Copy,
     I::InternedSubstitution: Copy,
+    I::InternedConst: Copy,
     I::InternedGoal: Copy,
     I::InternedGoals: Copy,
     I::InternedProgramClauses: Copy,
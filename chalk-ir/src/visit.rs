@@ -436,6 +436,10 @@ impl<I: Interner> SuperVisit<I> for Const<I> {
                 visitor.visit_free_placeholder(*universe, outer_binder)
             }
             ConstValue::Concrete(_) => ControlFlow::CONTINUE,
+            ConstValue::Projection(projection) => projection.visit_with(visitor, outer_binder),
+            ConstValue::Unevaluated(unevaluated) => {
+                unevaluated.substitution.visit_with(visitor, outer_binder)
+            }
         }
     }
 }
@@ -11,6 +11,35 @@ impl Span {
     pub fn new(lo: usize, hi: usize) -> Self {
         Span { lo, hi }
     }
+
+    /// The one-based line and column at which the span starts in `text`.
+    pub fn line_column(&self, text: &str) -> (usize, usize) {
+        let before = &text[..self.lo];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    /// Shows the line of `text` on which the span starts, with the span
+    /// underlined up to the end of that line.
+    pub fn position_string(&self, text: &str) -> String {
+        let (line, column) = self.line_column(text);
+        let line_start = text[..self.lo].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[self.lo..]
+            .find('\n')
+            .map_or(text.len(), |i| self.lo + i);
+        let line_text = &text[line_start..line_end];
+        let indent = line_text.len() - line_text.trim_start().len();
+        let prefix = format!("position (line {}, column {}): `", line, column);
+        let underlined = &text[self.lo..self.hi.min(line_end)];
+        format!(
+            "{}{}`\n{}{}\n",
+            prefix,
+            line_text.trim(),
+            " ".repeat(prefix.len() + text[line_start + indent..self.lo].chars().count()),
+            "^".repeat(underlined.chars().count().max(1))
+        )
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -18,6 +47,50 @@ pub struct Program {
     pub items: Vec<Item>,
 }
 
+impl Program {
+    /// Moves the items of modules to the top level, qualifying the names
+    /// they define with the path of their module. Returns the program along
+    /// with the path of the module each of its items is in, which is empty
+    /// for top-level items.
+    pub fn flatten(&self) -> (Program, Vec<Atom>) {
+        fn flatten_into(
+            items: &[Item],
+            module: &Atom,
+            program: &mut Program,
+            modules: &mut Vec<Atom>,
+        ) {
+            for item in items {
+                if let Item::Module(m) = item {
+                    flatten_into(&m.items, &qualify(module, &m.name).str, program, modules);
+                    continue;
+                }
+                let mut item = item.clone();
+                if let Some(name) = item.name_mut() {
+                    *name = qualify(module, name);
+                }
+                program.items.push(item);
+                modules.push(module.clone());
+            }
+        }
+
+        let mut program = Program { items: vec![] };
+        let mut modules = vec![];
+        flatten_into(&self.items, &Atom::from(""), &mut program, &mut modules);
+        (program, modules)
+    }
+}
+
+/// Prefixes `name` with the path of `module`.
+fn qualify(module: &Atom, name: &Identifier) -> Identifier {
+    if module.is_empty() {
+        return name.clone();
+    }
+    Identifier {
+        str: Atom::from(format!("{}::{}", module, name.str)),
+        span: name.span,
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Item {
     AdtDefn(AdtDefn),
@@ -29,10 +102,83 @@ pub enum Item {
     Impl(Impl),
     Clause(Clause),
     Foreign(ForeignDefn),
+    Include(Include),
+    Module(Module),
 }
 
+impl Item {
+    /// The span of the item's text.
+    pub fn span(&self) -> Span {
+        match self {
+            Item::AdtDefn(defn) => defn.span,
+            Item::FnDefn(defn) => defn.span,
+            Item::ClosureDefn(defn) => defn.span,
+            Item::TraitDefn(defn) => defn.span,
+            Item::OpaqueTyDefn(defn) => defn.span,
+            Item::GeneratorDefn(defn) => defn.span,
+            Item::Impl(defn) => defn.span,
+            Item::Clause(clause) => clause.span,
+            Item::Foreign(defn) => defn.span,
+            Item::Include(include) => include.span,
+            Item::Module(module) => module.span,
+        }
+    }
+
+    /// The name of the item, or `None` for impls, clauses and includes.
+    pub fn name(&self) -> Option<&Identifier> {
+        match self {
+            Item::AdtDefn(defn) => Some(&defn.name),
+            Item::FnDefn(defn) => Some(&defn.name),
+            Item::ClosureDefn(defn) => Some(&defn.name),
+            Item::TraitDefn(defn) => Some(&defn.name),
+            Item::OpaqueTyDefn(defn) => Some(&defn.name),
+            Item::GeneratorDefn(defn) => Some(&defn.name),
+            Item::Foreign(defn) => Some(&defn.name),
+            Item::Module(module) => Some(&module.name),
+            Item::Impl(_) | Item::Clause(_) | Item::Include(_) => None,
+        }
+    }
+
+    fn name_mut(&mut self) -> Option<&mut Identifier> {
+        match self {
+            Item::AdtDefn(defn) => Some(&mut defn.name),
+            Item::FnDefn(defn) => Some(&mut defn.name),
+            Item::ClosureDefn(defn) => Some(&mut defn.name),
+            Item::TraitDefn(defn) => Some(&mut defn.name),
+            Item::OpaqueTyDefn(defn) => Some(&mut defn.name),
+            Item::GeneratorDefn(defn) => Some(&mut defn.name),
+            Item::Foreign(defn) => Some(&mut defn.name),
+            Item::Module(module) => Some(&mut module.name),
+            Item::Impl(_) | Item::Clause(_) | Item::Include(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForeignDefn {
+    pub name: Identifier,
+    pub span: Span,
+}
+
+/// A `mod name { ... }` item. The names of the items in a module are
+/// qualified with the module's path, e.g. `a::Foo`; names used in the
+/// module are looked up in it first and then in its enclosing modules,
+/// unless they start with `crate::`.
+/// A module can be given in several blocks, which are merged.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Module {
+    pub name: Identifier,
+    pub items: Vec<Item>,
+    pub span: Span,
+}
+
+/// An `include "path";` item, which adds the items of another file to the
+/// program. The path is relative to the directory of the including file.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct ForeignDefn(pub Identifier);
+pub struct Include {
+    pub path: String,
+    pub span: Span,
+}
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AdtDefn {
@@ -43,6 +189,7 @@ pub struct AdtDefn {
     pub flags: AdtFlags,
     pub repr: AdtRepr,
     pub variances: Option<Vec<Variance>>,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -67,6 +214,7 @@ pub struct GeneratorDefn {
     pub return_ty: Ty,
     pub witness_types: Vec<Ty>,
     pub witness_lifetimes: Vec<Identifier>,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -114,6 +262,7 @@ pub struct FnDefn {
     pub return_type: Ty,
     pub sig: FnSig,
     pub variances: Option<Vec<Variance>>,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -124,6 +273,7 @@ pub struct ClosureDefn {
     pub argument_types: Vec<Ty>,
     pub return_type: Ty,
     pub upvars: Vec<Ty>,
+    pub span: Span,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -141,8 +291,10 @@ pub struct TraitDefn {
     pub variable_kinds: Vec<VariableKind>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub assoc_ty_defns: Vec<AssocTyDefn>,
+    pub assoc_const_defns: Vec<AssocConstDefn>,
     pub flags: TraitFlags,
     pub well_known: Option<WellKnownTrait>,
+    pub span: Span,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -158,6 +310,7 @@ pub enum WellKnownTrait {
     Unpin,
     CoerceUnsized,
     DiscriminantKind,
+    Generator,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -179,6 +332,12 @@ pub struct AssocTyDefn {
     pub where_clauses: Vec<QuantifiedWhereClause>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstDefn {
+    pub name: Identifier,
+    pub ty: Ty,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct OpaqueTyDefn {
     pub ty: Ty,
@@ -186,6 +345,7 @@ pub struct OpaqueTyDefn {
     pub name: Identifier,
     pub bounds: Vec<QuantifiedInlineBound>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -194,7 +354,9 @@ pub enum VariableKind {
     IntegerTy(Identifier),
     FloatTy(Identifier),
     Lifetime(Identifier),
-    Const(Identifier),
+    /// A const parameter, like `const N: usize`. Its type defaults to `u32`
+    /// if it isn't given.
+    Const(Identifier, Option<ScalarType>),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -208,7 +370,30 @@ pub enum GenericArg {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Const {
     Id(Identifier),
-    Value(u32),
+    Value(ConstLiteral),
+    Projection(ProjectionConst),
+    Unevaluated(ConstExpr),
+}
+
+/// A literal const value, like `true`, `'c'`, `-1` or `3usize`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConstLiteral {
+    Bool(bool),
+    Char(char),
+    /// An integer literal, with the type given by its suffix. Integer
+    /// literals without a suffix take the type of the const parameter they're
+    /// given for, if known, and are `u32` otherwise.
+    Int(i128, Option<ScalarType>),
+}
+
+/// A const expression like `N + 1`, which is left unevaluated in the IR.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ConstExpr {
+    Id(Identifier),
+    Value(i128),
+    Add(Box<ConstExpr>, Box<ConstExpr>),
+    Sub(Box<ConstExpr>, Box<ConstExpr>),
+    Mul(Box<ConstExpr>, Box<ConstExpr>),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -266,7 +451,9 @@ pub struct Impl {
     pub polarity: Polarity,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub assoc_ty_values: Vec<AssocTyValue>,
+    pub assoc_const_values: Vec<AssocConstValue>,
     pub impl_type: ImplType,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -283,51 +470,103 @@ pub struct AssocTyValue {
     pub default: bool,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstValue {
+    pub name: Identifier,
+    pub ty: Ty,
+    pub value: Const,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Ty {
     Id {
         name: Identifier,
+        span: Span,
     },
     Dyn {
         bounds: Vec<QuantifiedInlineBound>,
         lifetime: Lifetime,
+        span: Span,
     },
     Apply {
         name: Identifier,
         args: Vec<GenericArg>,
+        span: Span,
     },
     Projection {
         proj: ProjectionTy,
+        span: Span,
     },
     ForAll {
         lifetime_names: Vec<Identifier>,
         types: Vec<Box<Ty>>,
         sig: FnSig,
+        span: Span,
     },
     Tuple {
         types: Vec<Box<Ty>>,
+        span: Span,
     },
     Scalar {
         ty: ScalarType,
+        span: Span,
     },
     Slice {
         ty: Box<Ty>,
+        span: Span,
     },
     Array {
         ty: Box<Ty>,
         len: Const,
+        span: Span,
     },
     Raw {
         mutability: Mutability,
         ty: Box<Ty>,
+        span: Span,
     },
     Ref {
         mutability: Mutability,
         lifetime: Lifetime,
         ty: Box<Ty>,
+        span: Span,
+    },
+    Str {
+        span: Span,
     },
-    Str,
-    Never,
+    Never {
+        span: Span,
+    },
+}
+
+impl Ty {
+    /// The unit type `()` at `position`, used as the return type of
+    /// functions that don't specify one.
+    pub fn unit(position: usize) -> Ty {
+        Ty::Tuple {
+            types: vec![],
+            span: Span::new(position, position),
+        }
+    }
+
+    /// The span of the type's text.
+    pub fn span(&self) -> Span {
+        match *self {
+            Ty::Id { span, .. }
+            | Ty::Dyn { span, .. }
+            | Ty::Apply { span, .. }
+            | Ty::Projection { span, .. }
+            | Ty::ForAll { span, .. }
+            | Ty::Tuple { span, .. }
+            | Ty::Scalar { span, .. }
+            | Ty::Slice { span, .. }
+            | Ty::Array { span, .. }
+            | Ty::Raw { span, .. }
+            | Ty::Ref { span, .. }
+            | Ty::Str { span }
+            | Ty::Never { span } => span,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -398,6 +637,13 @@ pub struct ProjectionTy {
     pub args: Vec<GenericArg>,
 }
 
+/// A projection `<T as Trait>::N` of an associated const.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ProjectionConst {
+    pub trait_ref: TraitRef,
+    pub name: Identifier,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct TraitRef {
     pub trait_name: Identifier,
@@ -472,6 +718,7 @@ pub enum LeafGoal {
 pub struct QuantifiedWhereClause {
     pub variable_kinds: Vec<VariableKind>,
     pub where_clause: WhereClause,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -487,6 +734,7 @@ pub struct Clause {
     pub variable_kinds: Vec<VariableKind>,
     pub consequence: DomainGoal,
     pub conditions: Vec<Box<Goal>>,
+    pub span: Span,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
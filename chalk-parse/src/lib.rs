@@ -9,26 +9,100 @@ pub mod ast;
 lalrpop_mod!(pub parser);
 
 use lalrpop_util::ParseError;
+use std::fmt;
 use std::fmt::Write;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+/// A syntax error found while parsing a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// The offending token, if the error is about one.
+    pub span: Option<ast::Span>,
+}
+
+impl Diagnostic {
+    fn new<T: fmt::Display, E: fmt::Display>(error: ParseError<usize, T, E>) -> Self {
+        let span = match &error {
+            ParseError::InvalidToken { location } => Some(ast::Span::new(*location, location + 1)),
+            ParseError::UnrecognizedEOF { location, .. } => {
+                Some(ast::Span::new(*location, *location))
+            }
+            ParseError::UnrecognizedToken {
+                token: (lo, _, hi), ..
+            }
+            | ParseError::ExtraToken { token: (lo, _, hi) } => Some(ast::Span::new(*lo, *hi)),
+            ParseError::User { .. } => None,
+        };
+        Diagnostic {
+            message: error.to_string(),
+            span,
+        }
+    }
+
+    /// Formats the error along with the position it occurred at in `text`.
+    pub fn render(&self, text: &str) -> String {
+        match self.span {
+            Some(span) => format!(
+                "parse error: {}\n{}",
+                self.message,
+                span.position_string(text)
+            ),
+            None => format!("parse error: {}\n", self.message),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error: {}", self.message)
+    }
+}
+
+/// Parses a program, reporting all the syntax errors in it at once.
 pub fn parse_program(text: &str) -> Result<ast::Program> {
-    match parser::ProgramParser::new().parse(text) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(format!("parse error: {}", e))?,
+    let (program, diagnostics) = parse_program_with_recovery(text);
+    if diagnostics.is_empty() {
+        return Ok(program);
     }
+    let errors: String = diagnostics.iter().map(|d| d.render(text)).collect();
+    Err(errors.trim_end())?
+}
+
+/// Parses a program, skipping over malformed items. Returns the items that
+/// could be parsed along with the errors in the others, in the order in
+/// which they occur in `text`.
+pub fn parse_program_with_recovery(text: &str) -> (ast::Program, Vec<Diagnostic>) {
+    let mut errors = vec![];
+    let result = parser::ProgramParser::new().parse(&mut errors, text);
+    let mut diagnostics: Vec<_> = errors
+        .into_iter()
+        .map(|recovery| Diagnostic::new(recovery.error))
+        .collect();
+    let program = match result {
+        Ok(program) => program,
+        // Errors that can't be recovered from, e.g. invalid tokens, end
+        // the parse.
+        Err(e) => {
+            diagnostics.push(Diagnostic::new(e));
+            ast::Program { items: vec![] }
+        }
+    };
+    (program, diagnostics)
 }
 
 pub fn parse_ty(text: &str) -> Result<ast::Ty> {
-    match parser::TyParser::new().parse(text) {
+    let mut errors = vec![];
+    match parser::TyParser::new().parse(&mut errors, text) {
         Ok(v) => Ok(v),
         Err(e) => Err(format!("error parsing `{}`: {}", text, e))?,
     }
 }
 
 pub fn parse_goal(text: &str) -> Result<Box<ast::Goal>> {
-    match parser::GoalParser::new().parse(text) {
+    let mut errors = vec![];
+    match parser::GoalParser::new().parse(&mut errors, text) {
         Ok(v) => Ok(v),
         Err(e) => {
             let position_string = |start: usize, end: usize| {
@@ -63,6 +63,24 @@ pub(super) fn with_priorities<I: Interner>(
     }
 }
 
+/// Removes the low-priority solutions from `solutions` that are overridden by
+/// a high-priority solution for the same inputs (see `with_priorities`).
+pub(super) fn retain_highest_priority<I: Interner>(
+    interner: &I,
+    domain_goal: &DomainGoal<I>,
+    solutions: &mut Vec<(Solution<I>, ClausePriority)>,
+) {
+    let inputs_higher: Vec<_> = solutions
+        .iter()
+        .filter(|(_, prio)| *prio == ClausePriority::High)
+        .map(|(solution, _)| calculate_inputs(interner, domain_goal, solution))
+        .collect();
+    solutions.retain(|(solution, prio)| {
+        *prio == ClausePriority::High
+            || !inputs_higher.contains(&calculate_inputs(interner, domain_goal, solution))
+    });
+}
+
 fn calculate_inputs<I: Interner>(
     interner: &I,
     domain_goal: &DomainGoal<I>,
@@ -446,6 +446,13 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
         let mut progress = true;
 
         while progress {
+            // If we were asked to stop, leave the remaining obligations
+            // unsolved; the result will be ambiguous.
+            if !self.solver.should_continue() {
+                debug!("aborted, {} obligations left", self.obligations.len());
+                break;
+            }
+
             progress = false;
             debug!("start of round, {} obligations", self.obligations.len());
 
@@ -560,7 +567,12 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             // applying even *tentative* inference suggestions, so that we can
             // yield these upwards as our own suggestions. There are no
             // particular guarantees about *which* obligaiton we derive
-            // suggestions from.
+            // suggestions from. If we were asked to stop, some obligations
+            // may never have been proven, so we don't look for suggestions.
+
+            if !self.solver.should_continue() {
+                return Ok(Solution::Ambig(Guidance::Unknown));
+            }
 
             while let Some(obligation) = self.obligations.pop() {
                 if let Obligation::Prove(goal) = obligation {
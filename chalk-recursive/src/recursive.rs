@@ -5,9 +5,15 @@ use crate::stack::{Stack, StackDepth};
 use crate::{combine, Minimums, UCanonicalGoal};
 use chalk_ir::interner::Interner;
 use chalk_ir::Fallible;
-use chalk_ir::{Canonical, ConstrainedSubst, Constraints, Goal, InEnvironment, UCanonical};
-use chalk_solve::{coinductive_goal::IsCoinductive, RustIrDatabase, Solution};
+use chalk_ir::{
+    Canonical, ConstrainedSubst, Constraints, Goal, InEnvironment, Substitution, ToGenericArg,
+    UCanonical,
+};
+use chalk_solve::{
+    coinductive_goal::IsCoinductive, Guidance, RustIrDatabase, Solution, SubstitutionResult,
+};
 use rustc_hash::FxHashMap;
+use std::cell::Cell;
 use std::fmt;
 use tracing::debug;
 use tracing::{info, instrument};
@@ -38,6 +44,14 @@ struct RecursiveContext<I: Interner> {
 struct Solver<'me, I: Interner> {
     program: &'me dyn RustIrDatabase<I>,
     context: &'me mut RecursiveContext<I>,
+
+    /// Polled throughout solving; once it returns `false`, we stop doing any
+    /// further work and return an ambiguous solution.
+    should_continue: &'me dyn Fn() -> bool,
+
+    /// Set once `should_continue` has returned `false`. Results computed after
+    /// this point are incomplete, so they must not be cached.
+    aborted: bool,
 }
 
 pub struct RecursiveSolver<I: Interner> {
@@ -96,10 +110,13 @@ impl<I: Interner> RecursiveContext<I> {
     pub(crate) fn solver<'me>(
         &'me mut self,
         program: &'me dyn RustIrDatabase<I>,
+        should_continue: &'me dyn Fn() -> bool,
     ) -> Solver<'me, I> {
         Solver {
             program,
             context: self,
+            should_continue,
+            aborted: false,
         }
     }
 }
@@ -127,7 +144,42 @@ impl<'me, I: Interner> Solver<'me, I> {
         debug!("solve_root_goal(canonical_goal={:?})", canonical_goal);
         assert!(self.context.stack.is_empty());
         let minimums = &mut Minimums::new();
-        self.solve_goal(canonical_goal.clone(), minimums)
+        let result = self.solve_goal(canonical_goal.clone(), minimums);
+        if !self.aborted {
+            return result;
+        }
+
+        // The search was cut short, so we cannot claim to know the answer
+        // definitively. Whatever we did learn is only offered as a suggestion.
+        let interner = self.program.interner();
+        info!("solve_root_goal: aborted, partial result = {:?}", result);
+        let guidance = match result.map(Solution::into_guidance) {
+            Ok(Guidance::Definite(subst)) | Ok(Guidance::Suggested(subst))
+                if !subst.value.is_identity_subst(interner) =>
+            {
+                Guidance::Suggested(subst)
+            }
+            _ => Guidance::Unknown,
+        };
+        Ok(Solution::Ambig(guidance))
+    }
+
+    /// Solves a canonical goal, returning each distinct answer rather than
+    /// combining them into a single solution. The root goal (and each
+    /// refinement of it) is solved through `solve_goal`, so it takes part in
+    /// cycle detection and caching as usual; see `solve_answers`.
+    pub(crate) fn solve_root_goal_multiple(
+        &mut self,
+        canonical_goal: &UCanonicalGoal<I>,
+        f: &mut dyn FnMut(Solution<I>) -> bool,
+    ) -> bool {
+        debug!(
+            "solve_root_goal_multiple(canonical_goal={:?})",
+            canonical_goal
+        );
+        assert!(self.context.stack.is_empty());
+        let minimums = &mut Minimums::new();
+        self.solve_answers(canonical_goal, minimums, f)
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -155,8 +207,9 @@ impl<'me, I: Interner> Solver<'me, I> {
                 current_answer, minimums
             );
 
-            if !self.context.stack[depth].read_and_reset_cycle_flag() {
-                // None of our subgoals depended on us directly.
+            if !self.context.stack[depth].read_and_reset_cycle_flag() || self.aborted {
+                // None of our subgoals depended on us directly, or we were
+                // asked to stop, so there is no point in iterating further.
                 // We can return.
                 self.context.search_graph[dfn].solution = current_answer;
                 self.context.search_graph[dfn].solution_priority = current_prio;
@@ -219,6 +272,12 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
             return value.clone();
         }
 
+        // If we were asked to stop, don't do any more work.
+        if !self.should_continue() {
+            debug!("solve_reduced_goal: aborted");
+            return Ok(Solution::Ambig(Guidance::Unknown));
+        }
+
         // Next, check if the goal is in the search tree already.
         if let Some(dfn) = self.context.search_graph.lookup(&goal) {
             // Check if this table is still on the stack.
@@ -274,7 +333,10 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
             // cache now. This is a sort of hack to alleviate the
             // worst of the repeated work that we do during tabling.
             if subgoal_minimums.positive >= dfn {
-                if self.context.caching_enabled {
+                if self.aborted {
+                    debug!("solve_reduced_goal: SCC head encountered, rolling back as aborted");
+                    self.context.search_graph.rollback_to(dfn);
+                } else if self.context.caching_enabled {
                     self.context
                         .search_graph
                         .move_to_cache(dfn, &mut self.context.cache);
@@ -303,6 +365,13 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
     fn max_size(&self) -> usize {
         self.context.max_size
     }
+
+    fn should_continue(&mut self) -> bool {
+        if !self.aborted && !(self.should_continue)() {
+            self.aborted = true;
+        }
+        !self.aborted
+    }
 }
 
 impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
@@ -311,28 +380,74 @@ impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
     ) -> Option<chalk_solve::Solution<I>> {
-        self.ctx.solver(program).solve_root_goal(goal).ok()
+        self.ctx
+            .solver(program, &|| true)
+            .solve_root_goal(goal)
+            .ok()
     }
 
     fn solve_limited(
         &mut self,
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
-        _should_continue: &dyn std::ops::Fn() -> bool,
+        should_continue: &dyn std::ops::Fn() -> bool,
     ) -> Option<chalk_solve::Solution<I>> {
-        // TODO support should_continue in recursive solver
-        self.ctx.solver(program).solve_root_goal(goal).ok()
+        self.ctx
+            .solver(program, should_continue)
+            .solve_root_goal(goal)
+            .ok()
     }
 
     fn solve_multiple(
         &mut self,
-        _program: &dyn RustIrDatabase<I>,
-        _goal: &UCanonical<InEnvironment<Goal<I>>>,
-        _f: &mut dyn FnMut(
-            chalk_solve::SubstitutionResult<Canonical<ConstrainedSubst<I>>>,
-            bool,
-        ) -> bool,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<I>>>, bool) -> bool,
     ) -> bool {
-        unimplemented!("Recursive solver doesn't support multiple answers")
+        let interner = program.interner();
+        // `f` is only told whether there is a next answer, so each answer is
+        // held back until the next one has been found. Once `f` returns
+        // `false`, `should_continue` stops the solver from doing any more work.
+        let stopped = Cell::new(false);
+        let should_continue = || !stopped.get();
+        let mut pending = None;
+        self.ctx
+            .solver(program, &should_continue)
+            .solve_root_goal_multiple(goal, &mut |solution| {
+                let subst = match solution {
+                    Solution::Unique(subst) => SubstitutionResult::Definite(subst),
+                    solution => SubstitutionResult::Ambiguous(
+                        solution.constrained_subst(interner).unwrap_or_else(|| {
+                            // No inference guidance: every variable may be
+                            // anything.
+                            let binders = goal.canonical.binders.clone();
+                            let subst = Substitution::from_iter(
+                                interner,
+                                binders
+                                    .iter(interner)
+                                    .enumerate()
+                                    .map(|(i, var)| (i, &var.kind).to_generic_arg(interner)),
+                            );
+                            Canonical {
+                                binders,
+                                value: ConstrainedSubst {
+                                    subst,
+                                    constraints: Constraints::empty(interner),
+                                },
+                            }
+                        }),
+                    ),
+                };
+                if let Some(previous) = pending.replace(subst) {
+                    if !f(previous, true) {
+                        stopped.set(true);
+                    }
+                }
+                !stopped.get()
+            });
+        match pending {
+            Some(last) if !stopped.get() => f(last, false),
+            _ => !stopped.get(),
+        }
     }
 }
@@ -1,19 +1,25 @@
 use super::combine;
 use super::fulfill::Fulfill;
 use crate::{Minimums, UCanonicalGoal};
-use chalk_ir::could_match::CouldMatch;
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::{
-    Canonical, ClausePriority, DomainGoal, Fallible, Floundered, Goal, GoalData, InEnvironment,
-    NoSolution, ProgramClause, ProgramClauseData, Substitution, UCanonical,
+    Canonical, ClausePriority, ConstrainedSubst, Constraint, Constraints, DomainGoal, Environment,
+    Fallible, Floundered, GenericArg, Goal, GoalData, InEnvironment, NoSolution, ProgramClause,
+    ProgramClauseData, QuantifierKind, Substitution, UCanonical, UniverseMap, Variance,
 };
-use chalk_solve::clauses::program_clauses_that_could_match;
+use chalk_solve::clauses::program_clauses_for_goal_in_env;
 use chalk_solve::debug_span;
-use chalk_solve::infer::InferenceTable;
+use chalk_solve::infer::ucanonicalize::UniverseMapExt;
+use chalk_solve::infer::{InferenceTable, ParameterEnaVariableExt};
 use chalk_solve::{Guidance, RustIrDatabase, Solution};
 use tracing::{debug, instrument};
 
+/// How many times `solve_answers` splits an ambiguous goal into the ways
+/// one of its conjuncts can be proven, before it settles for reporting the
+/// remaining ambiguity as an answer of its own.
+const ANSWER_REFINEMENT_DEPTH: usize = 10;
+
 pub(super) trait SolveDatabase<I: Interner>: Sized {
     fn solve_goal(
         &mut self,
@@ -23,6 +29,10 @@ pub(super) trait SolveDatabase<I: Interner>: Sized {
 
     fn max_size(&self) -> usize;
 
+    /// Returns `false` if solving should be stopped as soon as possible; the
+    /// results computed from then on are ambiguous.
+    fn should_continue(&mut self) -> bool;
+
     fn interner(&self) -> &I;
 
     fn db(&self) -> &dyn RustIrDatabase<I>;
@@ -91,6 +101,45 @@ pub(super) trait SolveIteration<I: Interner>: SolveDatabase<I> {
             }
         }
     }
+    /// Like `solve_goal`, but returns each distinct answer to the goal rather
+    /// than one combined solution.
+    ///
+    /// The goal is first solved as a whole. If that is ambiguous, we flatten
+    /// it into its conjuncts and look for a domain goal among them that the
+    /// program clauses can prove in different ways. Each such way refines the
+    /// inference variables, and the refined goal is again solved as a whole
+    /// (and possibly split further). Ways that don't tell us anything about
+    /// the variables are reported as ambiguous answers of their own.
+    ///
+    /// Each distinct answer is passed to `f` as soon as it is found; once `f`
+    /// returns `false`, no further answers are computed.
+    #[instrument(level = "debug", skip(self, minimums, f))]
+    fn solve_answers(
+        &mut self,
+        canonical_goal: &UCanonicalGoal<I>,
+        minimums: &mut Minimums,
+        f: &mut dyn FnMut(Solution<I>) -> bool,
+    ) -> bool {
+        let (infer, subst, InEnvironment { environment, goal }) =
+            self.new_inference_table(canonical_goal);
+
+        let mut distinct: Vec<Solution<I>> = vec![];
+        self.collect_answers(
+            infer,
+            &subst,
+            &environment,
+            goal,
+            ANSWER_REFINEMENT_DEPTH,
+            minimums,
+            &mut |answer| {
+                if distinct.contains(&answer) {
+                    return true;
+                }
+                distinct.push(answer.clone());
+                f(answer)
+            },
+        )
+    }
 }
 
 impl<S, I> SolveIteration<I> for S
@@ -100,6 +149,15 @@ where
 {
 }
 
+/// The result of proving one conjunct of a goal with a single program
+/// clause; see `SolveIterationHelpers::refine_goal`.
+enum Refinement<I: Interner> {
+    /// The clause determined (some of) the goal's inference variables.
+    Refined(InferenceTable<I>),
+    /// The clause applies, but tells us nothing about the variables.
+    Ambiguous,
+}
+
 /// Helper methods for `solve_iteration`, private to this module.
 trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
     #[instrument(level = "debug", skip(self, minimums))]
@@ -123,49 +181,32 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
         canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
         minimums: &mut Minimums,
     ) -> (Fallible<Solution<I>>, ClausePriority) {
-        let mut clauses = vec![];
-
-        let db = self.db();
-        let could_match = |c: &ProgramClause<I>| {
-            c.could_match(
-                db.interner(),
-                db.unification_database(),
-                &canonical_goal.canonical.value.goal,
-            )
-        };
-        clauses.extend(db.custom_clauses().into_iter().filter(could_match));
-        match program_clauses_that_could_match(db, canonical_goal) {
-            Ok(goal_clauses) => clauses.extend(goal_clauses.into_iter().filter(could_match)),
+        let (infer, subst, goal, clauses) = match self.clauses_for_goal(canonical_goal) {
+            Ok(v) => v,
             Err(Floundered) => {
                 return (Ok(Solution::Ambig(Guidance::Unknown)), ClausePriority::High);
             }
-        }
-
-        let (infer, subst, goal) = self.new_inference_table(&canonical_goal);
-        clauses.extend(
-            db.program_clauses_for_env(&goal.environment)
-                .iter(db.interner())
-                .cloned()
-                .filter(could_match),
-        );
+        };
 
         let mut cur_solution = None;
         for program_clause in clauses {
             debug_span!("solve_from_clauses", clause = ?program_clause);
 
+            // If we were asked to stop, don't try any more clauses
+            if !self.should_continue() {
+                debug!("aborted");
+                return cur_solution.map_or(
+                    (Ok(Solution::Ambig(Guidance::Unknown)), ClausePriority::High),
+                    |(s, p)| (Ok(s), p),
+                );
+            }
+
             // If we have a completely ambiguous answer, it's not going to get better, so stop
             if cur_solution == Some((Solution::Ambig(Guidance::Unknown), ClausePriority::High)) {
                 return (Ok(Solution::Ambig(Guidance::Unknown)), ClausePriority::High);
             }
 
-            let ProgramClauseData(implication) = program_clause.data(self.interner());
-            let infer = infer.clone();
-            let subst = subst.clone();
-            let goal = goal.clone();
-            let res = match Fulfill::new_with_clause(self, infer, subst, goal, &implication) {
-                Ok(fulfill) => (fulfill.solve(minimums), implication.skip_binders().priority),
-                Err(e) => (Err(e), ClausePriority::High),
-            };
+            let res = self.solve_from_clause(&infer, &subst, &goal, &program_clause, minimums);
 
             if let (Ok(solution), priority) = res {
                 debug!(?solution, ?priority, "Ok");
@@ -187,6 +228,284 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
         cur_solution.map_or((Err(NoSolution), ClausePriority::High), |(s, p)| (Ok(s), p))
     }
 
+    /// Like `solve_from_clauses`, but rather than combining the solutions we
+    /// get from each clause, returns each of them separately. Solutions that
+    /// are overridden by a higher-priority solution are dropped.
+    fn solve_from_clauses_multiple(
+        &mut self,
+        canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
+        minimums: &mut Minimums,
+    ) -> Vec<(Solution<I>, ClausePriority)> {
+        let (infer, subst, goal, clauses) = match self.clauses_for_goal(canonical_goal) {
+            Ok(v) => v,
+            Err(Floundered) => {
+                return vec![(Solution::Ambig(Guidance::Unknown), ClausePriority::High)];
+            }
+        };
+
+        let mut solutions = vec![];
+        for program_clause in clauses {
+            debug_span!("solve_from_clauses_multiple", clause = ?program_clause);
+
+            if let (Ok(solution), priority) =
+                self.solve_from_clause(&infer, &subst, &goal, &program_clause, minimums)
+            {
+                debug!(?solution, ?priority, "Ok");
+                solutions.push((solution, priority));
+            } else {
+                debug!("Error");
+            }
+        }
+
+        combine::retain_highest_priority(
+            self.interner(),
+            &canonical_goal.canonical.value.goal,
+            &mut solutions,
+        );
+        solutions
+    }
+
+    /// Collects all the clauses that could be used to prove `canonical_goal`,
+    /// along with the inference table in which they should be applied.
+    fn clauses_for_goal(
+        &self,
+        canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
+    ) -> Result<
+        (
+            InferenceTable<I>,
+            Substitution<I>,
+            InEnvironment<DomainGoal<I>>,
+            Vec<ProgramClause<I>>,
+        ),
+        Floundered,
+    > {
+        let (infer, subst, goal) = self.new_inference_table(&canonical_goal);
+        let clauses =
+            program_clauses_for_goal_in_env(self.db(), canonical_goal, &goal.environment)?;
+        Ok((infer, subst, goal, clauses))
+    }
+
+    /// Attempts to solve `goal` by applying a single program clause.
+    fn solve_from_clause(
+        &mut self,
+        infer: &InferenceTable<I>,
+        subst: &Substitution<I>,
+        goal: &InEnvironment<DomainGoal<I>>,
+        program_clause: &ProgramClause<I>,
+        minimums: &mut Minimums,
+    ) -> (Fallible<Solution<I>>, ClausePriority) {
+        let ProgramClauseData(implication) = program_clause.data(self.interner());
+        let infer = infer.clone();
+        let subst = subst.clone();
+        let goal = goal.clone();
+        match Fulfill::new_with_clause(self, infer, subst, goal, &implication) {
+            Ok(fulfill) => (fulfill.solve(minimums), implication.skip_binders().priority),
+            Err(e) => (Err(e), ClausePriority::High),
+        }
+    }
+
+    /// Solves `goal` (whose free inference variables live in `infer`) and
+    /// passes its answers to `f`, expressed in terms of the root goal's
+    /// variables `subst`. Returns `false` if `f` asked to stop. See
+    /// `solve_answers`.
+    fn collect_answers(
+        &mut self,
+        mut infer: InferenceTable<I>,
+        subst: &Substitution<I>,
+        environment: &Environment<I>,
+        goal: Goal<I>,
+        depth: usize,
+        minimums: &mut Minimums,
+        f: &mut dyn FnMut(Solution<I>) -> bool,
+    ) -> bool {
+        let interner = *self.interner();
+        let interner = &interner;
+        let canonicalized =
+            infer.canonicalize(interner, InEnvironment::new(environment, goal.clone()));
+        let u_canonicalized = InferenceTable::u_canonicalize(interner, &canonicalized.quantified);
+        let solution = match self.solve_goal(u_canonicalized.quantified, minimums) {
+            Ok(solution) => solution,
+            Err(NoSolution) => return true,
+        };
+        debug!(?goal, ?solution);
+
+        if solution.is_ambig() && depth > 0 && self.should_continue() {
+            if let Some((goal, refinements)) =
+                self.refine_goal(infer.clone(), environment, goal, minimums)
+            {
+                for refinement in refinements {
+                    let keep_going = match refinement {
+                        Refinement::Refined(infer) => self.collect_answers(
+                            infer,
+                            subst,
+                            environment,
+                            goal.clone(),
+                            depth - 1,
+                            minimums,
+                            f,
+                        ),
+                        Refinement::Ambiguous => f(Solution::Ambig(Guidance::Unknown)),
+                    };
+                    if !keep_going {
+                        return false;
+                    }
+                }
+                return true;
+            }
+        }
+
+        let free_vars: Vec<_> = canonicalized
+            .free_vars
+            .into_iter()
+            .map(|free_var| free_var.to_generic_arg(interner))
+            .collect();
+        let answer = match solution.constrained_subst(interner) {
+            Some(constrained_subst) => {
+                let constraints = match Self::apply_answer(
+                    interner,
+                    self.db(),
+                    &mut infer,
+                    &free_vars,
+                    &u_canonicalized.universes,
+                    constrained_subst,
+                ) {
+                    Ok(constraints) => constraints,
+                    Err(NoSolution) => return true,
+                };
+                match solution {
+                    Solution::Unique(_) => Solution::Unique(
+                        infer
+                            .canonicalize(
+                                interner,
+                                ConstrainedSubst {
+                                    subst: subst.clone(),
+                                    constraints: Constraints::from_iter(interner, constraints),
+                                },
+                            )
+                            .quantified,
+                    ),
+                    Solution::Ambig(Guidance::Definite(_)) => Solution::Ambig(Guidance::Definite(
+                        infer.canonicalize(interner, subst.clone()).quantified,
+                    )),
+                    _ => Solution::Ambig(Guidance::Suggested(
+                        infer.canonicalize(interner, subst.clone()).quantified,
+                    )),
+                }
+            }
+            None => solution,
+        };
+        f(answer)
+    }
+
+    /// Flattens `goal` into its conjuncts and looks for the first domain goal
+    /// among them whose program clauses refine its variables. Returns the
+    /// flattened goal, along with how each clause that applies refines it.
+    /// Returns `None` if no conjunct can be refined this way.
+    fn refine_goal(
+        &mut self,
+        mut infer: InferenceTable<I>,
+        environment: &Environment<I>,
+        goal: Goal<I>,
+        minimums: &mut Minimums,
+    ) -> Option<(Goal<I>, Vec<Refinement<I>>)> {
+        let interner = *self.interner();
+        let interner = &interner;
+
+        let mut conjuncts = vec![];
+        let mut pending = vec![goal];
+        while let Some(goal) = pending.pop() {
+            match goal.data(interner) {
+                GoalData::All(goals) => pending.extend(goals.iter(interner).rev().cloned()),
+                GoalData::Quantified(QuantifierKind::Exists, subgoal) => {
+                    pending.push(infer.instantiate_binders_existentially(interner, subgoal.clone()))
+                }
+                _ => conjuncts.push(goal),
+            }
+        }
+
+        for conjunct in &conjuncts {
+            let domain_goal = match conjunct.data(interner) {
+                GoalData::DomainGoal(domain_goal) => domain_goal,
+                _ => continue,
+            };
+            let canonicalized = infer.canonicalize(
+                interner,
+                InEnvironment::new(environment, domain_goal.clone()),
+            );
+            let u_canonicalized =
+                InferenceTable::u_canonicalize(interner, &canonicalized.quantified);
+            let free_vars: Vec<_> = canonicalized
+                .free_vars
+                .into_iter()
+                .map(|free_var| free_var.to_generic_arg(interner))
+                .collect();
+
+            let mut refinements = vec![];
+            for (solution, _) in
+                self.solve_from_clauses_multiple(&u_canonicalized.quantified, minimums)
+            {
+                let constrained_subst = match solution.definite_subst(interner) {
+                    Some(subst) if !subst.value.subst.is_identity_subst(interner) => subst,
+                    _ => {
+                        refinements.push(Refinement::Ambiguous);
+                        continue;
+                    }
+                };
+                let mut infer = infer.clone();
+                if Self::apply_answer(
+                    interner,
+                    self.db(),
+                    &mut infer,
+                    &free_vars,
+                    &u_canonicalized.universes,
+                    constrained_subst,
+                )
+                .is_ok()
+                {
+                    refinements.push(Refinement::Refined(infer));
+                }
+            }
+
+            if refinements
+                .iter()
+                .any(|refinement| matches!(refinement, Refinement::Refined(_)))
+            {
+                let goal = Goal::all(interner, conjuncts.iter().cloned());
+                return Some((goal, refinements));
+            }
+        }
+        None
+    }
+
+    /// Unifies the variables `free_vars` of a goal with the values an answer
+    /// to that goal gives them, returning the answer's lifetime constraints.
+    fn apply_answer(
+        interner: &I,
+        db: &dyn RustIrDatabase<I>,
+        infer: &mut InferenceTable<I>,
+        free_vars: &[GenericArg<I>],
+        universes: &UniverseMap,
+        answer: Canonical<ConstrainedSubst<I>>,
+    ) -> Fallible<Vec<InEnvironment<Constraint<I>>>> {
+        let answer = universes.map_from_canonical(interner, &answer);
+        let ConstrainedSubst { subst, constraints } = infer.instantiate_canonical(interner, answer);
+
+        // As in `Fulfill::apply_solution`, this only substitutes unconstrained
+        // variables, so the empty environment will do.
+        let empty_env = &Environment::new(interner);
+        for (i, free_var) in free_vars.iter().enumerate() {
+            infer.relate(
+                interner,
+                db.unification_database(),
+                empty_env,
+                Variance::Invariant,
+                free_var,
+                subst.at(interner, i),
+            )?;
+        }
+        Ok(constraints.as_slice(interner).to_vec())
+    }
+
     fn new_inference_table<T: Fold<I, Result = T> + HasInterner<Interner = I> + Clone>(
         &self,
         ucanonical_goal: &UCanonical<InEnvironment<T>>,
@@ -8,6 +8,7 @@ use crate::RustIrDatabase;
 use chalk_ir::cast::{Cast, Caster};
 use chalk_ir::could_match::CouldMatch;
 use chalk_ir::interner::Interner;
+use chalk_ir::visit::VisitExt;
 use chalk_ir::*;
 use rustc_hash::FxHashSet;
 use std::iter;
@@ -19,7 +20,7 @@ mod dyn_ty;
 mod env_elaborator;
 mod generalize;
 pub mod program_clauses;
-mod super_traits;
+pub(crate) mod super_traits;
 
 // yields the types "contained" in `app_ty`
 fn constituent_types<I: Interner>(db: &dyn RustIrDatabase<I>, ty: &TyKind<I>) -> Vec<Ty<I>> {
@@ -213,6 +214,7 @@ pub fn push_auto_trait_impls<I: Interner>(
 }
 
 /// Leak auto traits for opaque types, just like `push_auto_trait_impls` does for structs.
+/// The hidden type is only visible under `Reveal`, so the auto trait only leaks there.
 ///
 /// For example, given the following program:
 ///
@@ -225,7 +227,7 @@ pub fn push_auto_trait_impls<I: Interner>(
 /// Checking the goal `Foo: Send` would generate the following:
 ///
 /// ```notrust
-/// Foo: Send :- Bar: Send
+/// Foo: Send :- Bar: Send, Reveal
 /// ```
 #[instrument(level = "debug", skip(builder))]
 pub fn push_auto_trait_impls_opaque<I: Interner>(
@@ -257,13 +259,17 @@ pub fn push_auto_trait_impls_opaque<I: Interner>(
             substitution: Substitution::from1(interner, self_ty),
         };
 
-        // OpaqueType<...>: MyAutoTrait :- HiddenType: MyAutoTrait
+        // HiddenType: MyAutoTrait
+        let hidden_ty_auto_trait: DomainGoal<I> = TraitRef {
+            trait_id: auto_trait_id,
+            substitution: Substitution::from1(interner, hidden_ty.clone()),
+        }
+        .cast(interner);
+
+        // OpaqueType<...>: MyAutoTrait :- HiddenType: MyAutoTrait, Reveal
         builder.push_clause(
             auto_trait_ref,
-            std::iter::once(TraitRef {
-                trait_id: auto_trait_id,
-                substitution: Substitution::from1(interner, hidden_ty.clone()),
-            }),
+            iter::once(hidden_ty_auto_trait).chain(iter::once(DomainGoal::Reveal)),
         );
     });
 }
@@ -357,6 +363,20 @@ pub fn push_auto_trait_impls_generator_witness<I: Interner>(
 pub fn program_clauses_for_goal<'db, I: Interner>(
     db: &'db dyn RustIrDatabase<I>,
     goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
+) -> Result<Vec<ProgramClause<I>>, Floundered> {
+    program_clauses_for_goal_in_env(db, goal, &goal.canonical.value.environment)
+}
+
+/// Like `program_clauses_for_goal`, but takes the clauses for the
+/// environment from `environment` rather than from the canonical `goal`.
+/// Solvers that have instantiated `goal` in an inference table pass its
+/// instantiated environment here, so that the environment clauses refer to
+/// the same inference variables as the goal.
+#[instrument(level = "debug", skip(db))]
+pub fn program_clauses_for_goal_in_env<'db, I: Interner>(
+    db: &'db dyn RustIrDatabase<I>,
+    goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
+    environment: &Environment<I>,
 ) -> Result<Vec<ProgramClause<I>>, Floundered> {
     let interner = db.interner();
 
@@ -367,7 +387,7 @@ pub fn program_clauses_for_goal<'db, I: Interner>(
     let clauses: Vec<ProgramClause<I>> = custom_clauses
         .chain(clauses_that_could_match)
         .chain(
-            db.program_clauses_for_env(&goal.canonical.value.environment)
+            db.program_clauses_for_env(environment)
                 .iter(interner)
                 .cloned(),
         )
@@ -644,6 +664,70 @@ pub fn program_clauses_that_could_match<I: Interner>(
             }
             AliasTy::Opaque(_) => (),
         },
+        DomainGoal::NormalizeConst(NormalizeConst {
+            projection,
+            value: _,
+        }) => {
+            // Like `Normalize` goals, these derive from the
+            // `AssociatedConstValue` datums found in impls.
+            let trait_ref = db.trait_ref_from_projection_const(projection);
+            let trait_datum = db.trait_datum(trait_ref.trait_id);
+
+            let self_ty = trait_ref.self_type_parameter(interner);
+            if let TyKind::InferenceVar(_, _) = self_ty.kind(interner) {
+                panic!("Inference vars not allowed when getting program clauses");
+            }
+
+            // Flounder if the self-type is unknown and the trait is non-enumerable.
+            if self_ty.is_general_var(interner, binders) && trait_datum.is_non_enumerable_trait() {
+                return Err(Floundered);
+            }
+
+            push_program_clauses_for_associated_const_values_in_impls_of(
+                builder,
+                environment,
+                &trait_ref,
+                binders,
+            );
+        }
+        DomainGoal::EvaluateConst(EvaluateConst {
+            unevaluated,
+            value: _,
+        }) => {
+            // An unevaluated const can only be evaluated once its const
+            // parameters are concrete, so flounder (making the goal
+            // ambiguous) while they are still inference variables or
+            // placeholders.
+            if unevaluated.has_free_vars(interner) {
+                return Err(Floundered);
+            }
+
+            let ConstData { ty, value } = unevaluated.data(interner);
+            if let ConstValue::Unevaluated(unevaluated_const) = value {
+                let all_concrete = unevaluated_const
+                    .substitution
+                    .iter(interner)
+                    .filter_map(|arg| arg.constant(interner))
+                    .all(|c| matches!(c.data(interner).value, ConstValue::Concrete(_)));
+                if !all_concrete {
+                    return Err(Floundered);
+                }
+
+                // Once the parameters are known, failing to evaluate the
+                // const is a definite error, so there's no value for it to
+                // be equal to.
+                if let Some(evaluated) = db.try_evaluate_const(ty, unevaluated_const) {
+                    builder.push_fact(EvaluateConst {
+                        unevaluated: unevaluated.clone(),
+                        value: ConstData {
+                            ty: ty.clone(),
+                            value: ConstValue::Concrete(evaluated),
+                        }
+                        .intern(interner),
+                    });
+                }
+            }
+        }
         DomainGoal::Compatible | DomainGoal::Reveal => (),
     };
 
@@ -748,6 +832,35 @@ fn push_program_clauses_for_associated_type_values_in_impls_of<I: Interner>(
     }
 }
 
+/// Generate program clauses from the associated-const values found in impls
+/// of the trait of `trait_ref`, like `push_program_clauses_for_associated_type_values_in_impls_of`
+/// does for associated types.
+#[instrument(level = "debug", skip(builder))]
+fn push_program_clauses_for_associated_const_values_in_impls_of<I: Interner>(
+    builder: &mut ClauseBuilder<'_, I>,
+    environment: &Environment<I>,
+    trait_ref: &TraitRef<I>,
+    binders: &CanonicalVarKinds<I>,
+) {
+    let interner = builder.interner();
+    for impl_id in builder.db.impls_for_trait(
+        trait_ref.trait_id,
+        trait_ref.substitution.as_slice(interner),
+        binders,
+    ) {
+        let impl_datum = builder.db.impl_datum(impl_id);
+        if !impl_datum.is_positive() {
+            continue;
+        }
+
+        for &acv_id in &impl_datum.associated_const_value_ids {
+            let acv = builder.db.associated_const_value(acv_id);
+            debug!(?acv_id, ?acv);
+            acv.to_program_clauses(builder, environment);
+        }
+    }
+}
+
 fn push_alias_implemented_clause<I: Interner>(
     builder: &mut ClauseBuilder<'_, I>,
     trait_ref: TraitRef<I>,
@@ -3,9 +3,11 @@ use crate::{CanonicalVarKinds, Interner, RustIrDatabase, TraitRef, WellKnownTrai
 use chalk_ir::{Floundered, Substitution, Ty};
 
 mod clone;
+mod coerce_unsized;
 mod copy;
 mod discriminant_kind;
 mod fn_family;
+mod generator;
 mod sized;
 mod unsize;
 
@@ -42,10 +44,16 @@ pub fn add_builtin_program_clauses<I: Interner>(
             WellKnownTrait::Unsize => {
                 unsize::add_unsize_program_clauses(db, builder, trait_ref, ty)
             }
+            WellKnownTrait::CoerceUnsized => {
+                coerce_unsized::add_coerce_unsized_program_clauses(db, builder, trait_ref, ty)?;
+            }
             // DiscriminantKind is automatically implemented for all types
             WellKnownTrait::DiscriminantKind => builder.push_fact(trait_ref),
+            WellKnownTrait::Generator => {
+                generator::add_generator_program_clauses(db, builder, self_ty)?;
+            }
             // There are no builtin impls provided for the following traits:
-            WellKnownTrait::Unpin | WellKnownTrait::Drop | WellKnownTrait::CoerceUnsized => (),
+            WellKnownTrait::Unpin | WellKnownTrait::Drop => (),
         }
         Ok(())
     })
@@ -73,6 +81,14 @@ pub fn add_builtin_assoc_program_clauses<I: Interner>(
         WellKnownTrait::DiscriminantKind => {
             discriminant_kind::add_discriminant_clauses(db, builder, self_ty)
         }
+        WellKnownTrait::Generator => {
+            let generalized = generalize::Generalize::apply(db.interner(), self_ty);
+
+            builder.push_binders(generalized, |builder, self_ty| {
+                generator::add_generator_program_clauses(db, builder, self_ty)?;
+                Ok(())
+            })
+        }
         _ => Ok(()),
     }
 }
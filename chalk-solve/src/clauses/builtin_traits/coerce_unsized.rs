@@ -0,0 +1,265 @@
+use std::iter;
+
+use crate::clauses::ClauseBuilder;
+use crate::rust_ir::AdtKind;
+use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
+use chalk_ir::{
+    cast::Cast, AdtId, Floundered, Goal, Lifetime, LifetimeOutlives, Mutability, Substitution,
+    TraitId, Ty, TyKind, TyVariableKind, WhereClause,
+};
+
+/// Pushes a clause of the form
+/// `forall<U> { Implemented(Source: CoerceUnsized<Target<U>>) :- Implemented(Pointee: Unsize<U>), Extra }`,
+/// where `Pointee` is the type `source_ty` points to and `Target<U>` is the
+/// pointer type built by `make_target`.
+fn push_pointer_coercion_clause<I: Interner>(
+    builder: &mut ClauseBuilder<'_, I>,
+    coerce_unsized_trait_id: TraitId<I>,
+    unsize_trait_id: TraitId<I>,
+    source_ty: &Ty<I>,
+    source_pointee: &Ty<I>,
+    make_target: impl FnOnce(Ty<I>) -> TyKind<I>,
+    extra_condition: Option<Goal<I>>,
+) {
+    builder.push_bound_ty(|builder, target_pointee| {
+        let interner = builder.interner();
+        let target_ty = make_target(target_pointee.clone()).intern(interner);
+
+        // Pointee: Unsize<U>
+        let unsize_goal: Goal<I> = TraitRef {
+            trait_id: unsize_trait_id,
+            substitution: Substitution::from_iter(
+                interner,
+                [source_pointee.clone(), target_pointee].iter().cloned(),
+            ),
+        }
+        .cast(interner);
+
+        builder.push_clause(
+            TraitRef {
+                trait_id: coerce_unsized_trait_id,
+                substitution: Substitution::from_iter(
+                    interner,
+                    [source_ty.clone(), target_ty].iter().cloned(),
+                ),
+            },
+            iter::once(unsize_goal).chain(extra_condition),
+        );
+    });
+}
+
+/// Like `push_pointer_coercion_clause`, but for coercions to a shared
+/// reference `&'b U`: the source lifetime `'a` is required to outlive `'b`.
+fn push_shared_ref_coercion_clause<I: Interner>(
+    builder: &mut ClauseBuilder<'_, I>,
+    coerce_unsized_trait_id: TraitId<I>,
+    unsize_trait_id: TraitId<I>,
+    source_ty: &Ty<I>,
+    source_pointee: &Ty<I>,
+    source_lifetime: &Lifetime<I>,
+) {
+    builder.push_bound_lifetime(|builder, target_lifetime| {
+        let interner = builder.interner();
+
+        // 'a: 'b
+        let lifetime_outlives_goal: Goal<I> = WhereClause::LifetimeOutlives(LifetimeOutlives {
+            a: source_lifetime.clone(),
+            b: target_lifetime.clone(),
+        })
+        .cast(interner);
+
+        push_pointer_coercion_clause(
+            builder,
+            coerce_unsized_trait_id,
+            unsize_trait_id,
+            source_ty,
+            source_pointee,
+            |target_pointee| TyKind::Ref(Mutability::Not, target_lifetime, target_pointee),
+            Some(lifetime_outlives_goal),
+        );
+    });
+}
+
+/// Pushes the clauses for coercions between instances of the struct
+/// `source_ty` that differ in a single type parameter. For each type parameter
+/// `T` that the type of exactly one field `f` depends on (`PhantomData` fields
+/// don't count), we push
+/// `forall<U> { Implemented(S<.., T, ..>: CoerceUnsized<S<.., U, ..>>) :- Implemented(F<T>: CoerceUnsized<F<U>>) }`,
+/// where `F<T>` is the type of `f`.
+fn push_struct_coercion_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    coerce_unsized_trait_id: TraitId<I>,
+    source_ty: &Ty<I>,
+    adt_id: AdtId<I>,
+    source_substitution: &Substitution<I>,
+) {
+    let interner = db.interner();
+    let adt_datum = db.adt_datum(adt_id);
+    if adt_datum.kind != AdtKind::Struct {
+        return;
+    }
+    let fields = adt_datum
+        .binders
+        .map_ref(|bound| bound.variants.last().map_or(vec![], |v| v.fields.clone()));
+    let source_fields = fields.clone().substitute(interner, source_substitution);
+    let is_phantom_data = |ty: &Ty<I>| match ty.kind(interner) {
+        TyKind::Adt(id, _) => db.adt_datum(*id).flags.phantom_data,
+        _ => false,
+    };
+
+    for (index, param) in source_substitution.iter(interner).enumerate() {
+        if param.ty(interner).is_none() {
+            continue;
+        }
+        builder.push_bound_ty(|builder, target_param| {
+            let target_substitution = Substitution::from_iter(
+                interner,
+                source_substitution
+                    .iter(interner)
+                    .enumerate()
+                    .map(|(i, param)| {
+                        if i == index {
+                            target_param.clone().cast(interner)
+                        } else {
+                            param.clone()
+                        }
+                    }),
+            );
+            let target_fields = fields.clone().substitute(interner, &target_substitution);
+
+            // Only one field may change
+            let mut changed_fields = source_fields.iter().cloned().zip(target_fields).filter(
+                |(source_field, target_field)| {
+                    source_field != target_field && !is_phantom_data(source_field)
+                },
+            );
+            let (source_field, target_field) = match (changed_fields.next(), changed_fields.next())
+            {
+                (Some(changed_field), None) => changed_field,
+                _ => return,
+            };
+
+            let target_ty = TyKind::Adt(adt_id, target_substitution).intern(interner);
+            builder.push_clause(
+                TraitRef {
+                    trait_id: coerce_unsized_trait_id,
+                    substitution: Substitution::from_iter(
+                        interner,
+                        [source_ty.clone(), target_ty].iter().cloned(),
+                    ),
+                },
+                iter::once(TraitRef {
+                    trait_id: coerce_unsized_trait_id,
+                    substitution: Substitution::from_iter(
+                        interner,
+                        [source_field, target_field].iter().cloned(),
+                    ),
+                }),
+            );
+        });
+    }
+}
+
+/// Adds the built-in `CoerceUnsized` impls that libcore provides for
+/// references and raw pointers:
+///
+/// ```notrust
+/// &'a mut T -> &'a mut U, &'b U (where 'a: 'b), *mut U, *const U
+/// &'a T     -> &'b U (where 'a: 'b), *const U
+/// *mut T    -> *mut U, *const U
+/// *const T  -> *const U
+/// ```
+///
+/// Each of these requires `T: Unsize<U>`. Structs coerce when exactly one of
+/// their fields does, see `push_struct_coercion_clauses`; user impls for
+/// structs are checked against the same rule by the WF rules.
+pub fn add_coerce_unsized_program_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_ref: TraitRef<I>,
+    ty: TyKind<I>,
+) -> Result<(), Floundered> {
+    let interner = db.interner();
+
+    let unsize_trait_id = match db.well_known_trait_id(WellKnownTrait::Unsize) {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let coerce_unsized_trait_id = trait_ref.trait_id;
+    let source_ty = trait_ref.self_type_parameter(interner);
+
+    let push_clause = |builder: &mut ClauseBuilder<'_, I>,
+                       source_pointee: &Ty<I>,
+                       make_target: fn(Ty<I>) -> TyKind<I>| {
+        push_pointer_coercion_clause(
+            builder,
+            coerce_unsized_trait_id,
+            unsize_trait_id,
+            &source_ty,
+            source_pointee,
+            make_target,
+            None,
+        )
+    };
+
+    match ty {
+        TyKind::Ref(Mutability::Mut, ref lifetime, ref pointee) => {
+            push_pointer_coercion_clause(
+                builder,
+                coerce_unsized_trait_id,
+                unsize_trait_id,
+                &source_ty,
+                pointee,
+                |u| TyKind::Ref(Mutability::Mut, lifetime.clone(), u),
+                None,
+            );
+            push_shared_ref_coercion_clause(
+                builder,
+                coerce_unsized_trait_id,
+                unsize_trait_id,
+                &source_ty,
+                pointee,
+                lifetime,
+            );
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Mut, u));
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Not, u));
+        }
+        TyKind::Ref(Mutability::Not, ref lifetime, ref pointee) => {
+            push_shared_ref_coercion_clause(
+                builder,
+                coerce_unsized_trait_id,
+                unsize_trait_id,
+                &source_ty,
+                pointee,
+                lifetime,
+            );
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Not, u));
+        }
+        TyKind::Raw(Mutability::Mut, ref pointee) => {
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Mut, u));
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Not, u));
+        }
+        TyKind::Raw(Mutability::Not, ref pointee) => {
+            push_clause(builder, pointee, |u| TyKind::Raw(Mutability::Not, u));
+        }
+
+        TyKind::Adt(adt_id, ref substitution) => push_struct_coercion_clauses(
+            db,
+            builder,
+            coerce_unsized_trait_id,
+            &source_ty,
+            adt_id,
+            substitution,
+        ),
+
+        // Don't know enough
+        TyKind::InferenceVar(_, TyVariableKind::General) | TyKind::BoundVar(_) => {
+            return Err(Floundered)
+        }
+
+        // Other types only implement `CoerceUnsized` through user impls
+        _ => {}
+    }
+    Ok(())
+}
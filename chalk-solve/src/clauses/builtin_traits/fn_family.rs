@@ -3,8 +3,8 @@ use crate::rust_ir::{ClosureKind, FnDefInputsAndOutputDatum, WellKnownTrait};
 use crate::{Interner, RustIrDatabase, TraitRef};
 use chalk_ir::cast::Cast;
 use chalk_ir::{
-    AliasTy, Binders, Floundered, Normalize, ProjectionTy, Safety, Substitution, TraitId, Ty,
-    TyKind,
+    AliasTy, Binders, Floundered, FnSig, Normalize, ProjectionTy, Safety, Substitution, TraitId,
+    Ty, TyKind,
 };
 
 fn push_clauses<I: Interner>(
@@ -72,11 +72,20 @@ fn push_clauses_for_apply<I: Interner>(
     });
 }
 
+/// Returns true if functions with the given signature implement the
+/// `Fn` traits: they must be safe, non-variadic and use the Rust ABI.
+fn fn_sig_implements_fn_traits<I: Interner>(interner: &I, sig: &FnSig<I>) -> bool {
+    sig.safety == Safety::Safe && !sig.variadic && interner.is_rust_abi(sig.abi)
+}
+
 /// Handles clauses for FnOnce/FnMut/Fn.
 /// If `self_ty` is a function, we push a clause of the form
 /// `fn(A1, A2, ..., AN) -> O: FnTrait<(A1, A2, ..., AN)>`, where `FnTrait`
 /// is the trait corresponding to `trait_id` (FnOnce/FnMut/Fn)
 ///
+/// If `self_ty` is a function definition, its argument and return types
+/// are taken from the `FnDefDatum`, instantiated with the substitution of `self_ty`.
+///
 /// If `trait_id` is `FnOnce`, we also push a clause for the output type of the form:
 /// `Normalize(<fn(A) -> B as FnOnce<(A,)>>::Output -> B)`
 /// We do not add the usual `Implemented(fn(A) -> b as FnOnce<(A,)>` clause
@@ -93,7 +102,7 @@ pub fn add_fn_trait_program_clauses<I: Interner>(
     match self_ty.kind(interner) {
         TyKind::FnDef(fn_def_id, substitution) => {
             let fn_def_datum = builder.db.fn_def_datum(*fn_def_id);
-            if fn_def_datum.sig.safety == Safety::Safe && !fn_def_datum.sig.variadic {
+            if fn_sig_implements_fn_traits(interner, &fn_def_datum.sig) {
                 let bound = fn_def_datum
                     .binders
                     .clone()
@@ -133,7 +142,7 @@ pub fn add_fn_trait_program_clauses<I: Interner>(
             );
             Ok(())
         }
-        TyKind::Function(fn_val) if fn_val.sig.safety == Safety::Safe && !fn_val.sig.variadic => {
+        TyKind::Function(fn_val) if fn_sig_implements_fn_traits(interner, &fn_val.sig) => {
             let bound_ref = fn_val.clone().into_binders(interner);
             builder.push_binders(bound_ref, |builder, orig_sub| {
                 // The last parameter represents the function return type
@@ -1,15 +1,16 @@
 use std::collections::HashSet;
 use std::iter;
 
-use crate::clauses::ClauseBuilder;
+use crate::clauses::{super_traits::super_traits, ClauseBuilder};
 use crate::rust_ir::AdtKind;
 use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
 use chalk_ir::{
     cast::Cast,
     interner::HasInterner,
     visit::{ControlFlow, SuperVisit, Visit, Visitor},
-    Binders, Const, ConstValue, DebruijnIndex, DomainGoal, DynTy, EqGoal, Goal, LifetimeOutlives,
-    QuantifiedWhereClauses, Substitution, TraitId, Ty, TyKind, TypeOutlives, WhereClause,
+    AliasEq, AliasTy, Binders, Const, ConstValue, DebruijnIndex, DomainGoal, DynTy, EqGoal, Goal,
+    LifetimeOutlives, QuantifiedWhereClause, QuantifiedWhereClauses, Substitution, TraitId, Ty,
+    TyKind, TypeOutlives, WhereClause,
 };
 
 struct UnsizeParameterCollector<'a, I: Interner> {
@@ -160,6 +161,48 @@ fn auto_trait_ids<'a, I: Interner>(
         .filter(move |&id| db.trait_datum(id).is_auto_trait())
 }
 
+/// Given the bounds of a trait object `dyn Trait`, returns the bound
+/// `Self: SuperTrait<..>` that `dyn Trait` can be upcast to, where `SuperTrait`
+/// is the trait with id `super_trait_id`.
+///
+/// Returns `None` if `SuperTrait` is not a supertrait of the principal trait,
+/// or if it is reachable with different generic arguments (e.g. `trait Trait:
+/// SuperTrait<u8> + SuperTrait<u16>`), in which case the upcast is ambiguous.
+fn upcast_principal<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    bounds: &Binders<QuantifiedWhereClauses<I>>,
+    principal_id: TraitId<I>,
+    super_trait_id: TraitId<I>,
+) -> Option<QuantifiedWhereClause<I>> {
+    let interner = db.interner();
+
+    let principal = bounds
+        .skip_binders()
+        .iter(interner)
+        .find(|bound| bound.trait_id() == Some(principal_id))?;
+    let principal_trait_ref = match principal.skip_binders() {
+        WhereClause::Implemented(trait_ref) => trait_ref,
+        _ => return None,
+    };
+
+    let mut candidates = super_traits(db, principal_id)
+        .substitute(interner, &principal_trait_ref.substitution)
+        .into_iter()
+        .filter(|super_trait_ref| super_trait_ref.skip_binders().trait_id == super_trait_id);
+
+    let candidate = candidates.next()?;
+    if candidates.any(|other| other != candidate) {
+        return None;
+    }
+
+    // `candidate` lives inside the binders of the principal bound; combine them
+    Some(
+        Binders::new(principal.binders.clone(), candidate)
+            .fuse_binders(interner)
+            .map(WhereClause::Implemented),
+    )
+}
+
 pub fn add_unsize_program_clauses<I: Interner>(
     db: &dyn RustIrDatabase<I>,
     builder: &mut ClauseBuilder<'_, I>,
@@ -205,15 +248,38 @@ pub fn add_unsize_program_clauses<I: Interner>(
             let auto_trait_ids_a: Vec<_> = auto_trait_ids(db, bounds_a).collect();
             let auto_trait_ids_b: Vec<_> = auto_trait_ids(db, bounds_b).collect();
 
-            let may_apply = principal_a == principal_b
-                && auto_trait_ids_b
-                    .iter()
-                    .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
+            let may_apply = auto_trait_ids_b
+                .iter()
+                .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
 
             if !may_apply {
                 return;
             }
 
+            // dyn Trait -> dyn SuperTrait
+            //
+            // If the principal traits differ, the target principal has to be a supertrait
+            // of the source principal. Only the projection bounds on the target's
+            // traits are kept in that case.
+            let upcast = if principal_a == principal_b {
+                None
+            } else {
+                let (principal_a, principal_b) = match (principal_a, principal_b) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return,
+                };
+                let upcast_bound = match upcast_principal(db, bounds_a, principal_a, principal_b) {
+                    Some(bound) => bound,
+                    None => return,
+                };
+                let target_trait_ids: HashSet<_> = super_traits(db, principal_b)
+                    .skip_binders()
+                    .iter()
+                    .map(|trait_ref| trait_ref.skip_binders().trait_id)
+                    .collect();
+                Some((principal_a, upcast_bound, target_trait_ids))
+            };
+
             // COMMENT FROM RUSTC:
             // ------------------
             // Require that the traits involved in this upcast are **equal**;
@@ -232,7 +298,8 @@ pub fn add_unsize_program_clauses<I: Interner>(
             // ------------------
 
             // Construct a new trait object type by taking the source ty,
-            // filtering out auto traits of source that are not present in target
+            // filtering out auto traits of source that are not present in target,
+            // replacing the principal with the upcast one (if any)
             // and changing source lifetime to target lifetime.
             //
             // In order for the coercion to be valid, this new type
@@ -241,16 +308,36 @@ pub fn add_unsize_program_clauses<I: Interner>(
                 bounds: bounds_a.map_ref(|bounds| {
                     QuantifiedWhereClauses::from_iter(
                         interner,
-                        bounds.iter(interner).filter(|bound| {
+                        bounds.iter(interner).filter_map(|bound| {
+                            if let Some((principal_a, upcast_bound, target_trait_ids)) = &upcast {
+                                if bound.trait_id() == Some(*principal_a) {
+                                    return Some(upcast_bound.clone());
+                                }
+                                if let WhereClause::AliasEq(AliasEq {
+                                    alias: AliasTy::Projection(projection),
+                                    ..
+                                }) = bound.skip_binders()
+                                {
+                                    let assoc_trait_id =
+                                        db.associated_ty_data(projection.associated_ty_id).trait_id;
+                                    if !target_trait_ids.contains(&assoc_trait_id) {
+                                        return None;
+                                    }
+                                }
+                            }
+
                             let trait_id = match bound.trait_id() {
                                 Some(id) => id,
-                                None => return true,
+                                None => return Some(bound.clone()),
                             };
 
-                            if auto_trait_ids_a.iter().all(|&id_a| id_a != trait_id) {
-                                return true;
+                            if auto_trait_ids_a.iter().all(|&id_a| id_a != trait_id)
+                                || auto_trait_ids_b.iter().any(|&id_b| id_b == trait_id)
+                            {
+                                Some(bound.clone())
+                            } else {
+                                None
                             }
-                            auto_trait_ids_b.iter().any(|&id_b| id_b == trait_id)
                         }),
                     )
                 }),
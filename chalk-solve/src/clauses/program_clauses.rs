@@ -134,23 +134,81 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
     }
 }
 
+impl<I: Interner> ToProgramClauses<I> for AssociatedConstValue<I> {
+    /// Given the following trait:
+    ///
+    /// ```notrust
+    /// trait Foo {
+    ///     const N: u32;
+    /// }
+    /// ```
+    ///
+    /// Then for the following impl:
+    /// ```notrust
+    /// impl<T> Foo for Vec<T> where T: Clone {
+    ///     const N: u32 = 3;
+    /// }
+    /// ```
+    ///
+    /// we generate:
+    ///
+    /// ```notrust
+    /// -- Rule NormalizeConst-From-Impl
+    /// forall<T> {
+    ///     NormalizeConst(<Vec<T> as Foo>::N -> 3) :-
+    ///         Implemented(T: Clone).
+    /// }
+    /// ```
+    fn to_program_clauses(
+        &self,
+        builder: &mut ClauseBuilder<'_, I>,
+        _environment: &Environment<I>,
+    ) {
+        let impl_datum = builder.db.impl_datum(self.impl_id);
+
+        builder.push_binders(self.value.clone(), |builder, value| {
+            let interner = builder.interner();
+            let impl_params = builder.placeholders_in_scope().to_vec();
+            let ImplDatumBound {
+                trait_ref,
+                where_clauses,
+            } = impl_datum
+                .binders
+                .clone()
+                .substitute(interner, &impl_params);
+
+            builder.push_clause(
+                NormalizeConst {
+                    projection: ProjectionConst {
+                        associated_const_id: self.associated_const_id,
+                        substitution: trait_ref.substitution,
+                    },
+                    value,
+                },
+                where_clauses,
+            );
+        });
+    }
+}
+
 impl<I: Interner> ToProgramClauses<I> for OpaqueTyDatum<I> {
     /// Given `opaque type T<U>: A + B = HiddenTy where U: C;`, we generate:
     ///
     /// ```notrust
     /// AliasEq(T<U> = HiddenTy) :- Reveal.
-    /// AliasEq(T<U> = !T<U>).
+    /// AliasEq(T<U> = !T<U>).         // low priority if `Reveal` is in the environment
     /// WF(T<U>) :- WF(U: C).
     /// Implemented(!T<U>: A).
     /// Implemented(!T<U>: B).
     /// ```
     /// where `!T<..>` is the placeholder for the unnormalized type `T<..>`.
     #[instrument(level = "debug", skip(builder))]
-    fn to_program_clauses(
-        &self,
-        builder: &mut ClauseBuilder<'_, I>,
-        _environment: &Environment<I>,
-    ) {
+    fn to_program_clauses(&self, builder: &mut ClauseBuilder<'_, I>, environment: &Environment<I>) {
+        let interner = builder.interner();
+        let revealed = environment
+            .clauses
+            .iter(interner)
+            .any(|clause| clause.data(interner).0.skip_binders().consequence == DomainGoal::Reveal);
         builder.push_binders(self.bound.clone(), |builder, opaque_ty_bound| {
             let interner = builder.interner();
             let substitution = builder.substitution_in_scope();
@@ -175,13 +233,25 @@ impl<I: Interner> ToProgramClauses<I> for OpaqueTyDatum<I> {
             );
 
             // AliasEq(T<..> = !T<..>).
-            builder.push_fact(DomainGoal::Holds(
-                AliasEq {
-                    alias,
-                    ty: alias_placeholder_ty.clone(),
-                }
-                .cast(interner),
-            ));
+            //
+            // Under `Reveal`, this is a fallback rule, like the one for projections:
+            // the hidden type takes precedence over the placeholder.
+            let priority = if revealed {
+                ClausePriority::Low
+            } else {
+                ClausePriority::High
+            };
+            builder.push_fact_with_priority(
+                DomainGoal::Holds(
+                    AliasEq {
+                        alias,
+                        ty: alias_placeholder_ty.clone(),
+                    }
+                    .cast(interner),
+                ),
+                None,
+                priority,
+            );
 
             // WF(!T<..>) :- WF(WC).
             builder.push_binders(opaque_ty_bound.where_clauses, |builder, where_clauses| {
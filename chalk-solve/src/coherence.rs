@@ -1,9 +1,10 @@
 use petgraph::prelude::*;
 
+use crate::display::{display_impl_header, display_trait_ref};
 use crate::solve::Solver;
 use crate::RustIrDatabase;
 use chalk_ir::interner::Interner;
-use chalk_ir::{self, ImplId, TraitId};
+use chalk_ir::{self, Canonical, ImplId, TraitId, TraitRef};
 use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
@@ -19,15 +20,45 @@ pub struct CoherenceSolver<'a, I: Interner> {
 
 #[derive(Debug)]
 pub enum CoherenceError<I: Interner> {
-    OverlappingImpls(TraitId<I>),
+    /// Two impls of the trait `trait_id` overlap and neither specializes
+    /// the other. `witness` is a trait reference that both impls apply to,
+    /// if one could be found; it may contain bound variables for types that
+    /// are left unconstrained.
+    OverlappingImpls {
+        trait_id: TraitId<I>,
+        impls: (ImplId<I>, ImplId<I>),
+        witness: Option<Canonical<TraitRef<I>>>,
+        kind: OverlapKind,
+    },
     FailedOrphanCheck(TraitId<I>),
 }
 
+/// Whether two impls overlap with the impls that exist now or only could
+/// overlap once another crate adds impls.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OverlapKind {
+    /// The impls overlap given the impls known in this crate.
+    Local,
+    /// The impls don't overlap given the impls known in this crate, but an
+    /// upstream or downstream crate could add impls that make them overlap.
+    OtherCrate,
+}
+
+impl<I: Interner> CoherenceError<I> {
+    /// Displays the error along with the overlapping impls and the witness
+    /// of their overlap, using `db` for the names of the items they refer to.
+    pub fn display<'a>(&'a self, db: &'a dyn RustIrDatabase<I>) -> CoherenceErrorDisplay<'a, I> {
+        CoherenceErrorDisplay { error: self, db }
+    }
+}
+
+/// Only displays which trait the error is about; see
+/// `CoherenceError::display` for the details.
 impl<I: Interner> fmt::Display for CoherenceError<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CoherenceError::OverlappingImpls(id) => {
-                write!(f, "overlapping impls of trait `{:?}`", id)
+            CoherenceError::OverlappingImpls { trait_id, .. } => {
+                write!(f, "overlapping impls of trait `{:?}`", trait_id)
             }
             CoherenceError::FailedOrphanCheck(id) => {
                 write!(f, "impl for trait `{:?}` violates the orphan rules", id)
@@ -36,6 +67,60 @@ impl<I: Interner> fmt::Display for CoherenceError<I> {
     }
 }
 
+pub struct CoherenceErrorDisplay<'a, I: Interner> {
+    error: &'a CoherenceError<I>,
+    db: &'a dyn RustIrDatabase<I>,
+}
+
+impl<'a, I: Interner> fmt::Display for CoherenceErrorDisplay<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let CoherenceErrorDisplay { error, db } = self;
+        match error {
+            CoherenceError::OverlappingImpls {
+                trait_id,
+                impls,
+                witness,
+                kind,
+            } => {
+                write!(
+                    f,
+                    "overlapping impls of trait `{}`, `{}` and `{}`",
+                    db.trait_name(*trait_id),
+                    display_impl_header(*db, impls.0),
+                    display_impl_header(*db, impls.1)
+                )?;
+                // The variables of the witness are the types that are left
+                // unconstrained, which are displayed as `_`.
+                let witness = witness
+                    .as_ref()
+                    .map(|witness| display_trait_ref(*db, &witness.value));
+                match (kind, witness) {
+                    (OverlapKind::Local, Some(witness)) => {
+                        write!(f, ": both impls apply to `{}`", witness)
+                    }
+                    (OverlapKind::Local, None) => Ok(()),
+                    (OverlapKind::OtherCrate, Some(witness)) => write!(
+                        f,
+                        ": an upstream or downstream crate could add impls \
+                         that make both impls apply to `{}`",
+                        witness
+                    ),
+                    (OverlapKind::OtherCrate, None) => write!(
+                        f,
+                        ": an upstream or downstream crate could add impls \
+                         that make them overlap"
+                    ),
+                }
+            }
+            CoherenceError::FailedOrphanCheck(id) => write!(
+                f,
+                "impl for trait `{}` violates the orphan rules",
+                db.trait_name(*id)
+            ),
+        }
+    }
+}
+
 impl<I: Interner> std::error::Error for CoherenceError<I> {}
 
 /// Stores the specialization priorities for a set of impls.
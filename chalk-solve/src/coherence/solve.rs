@@ -1,8 +1,8 @@
-use crate::coherence::{CoherenceError, CoherenceSolver};
+use crate::coherence::{CoherenceError, CoherenceSolver, OverlapKind};
 use crate::debug_span;
 use crate::ext::*;
 use crate::rust_ir::*;
-use crate::{goal_builder::GoalBuilder, Solution};
+use crate::{goal_builder::GoalBuilder, Guidance, Solution};
 use chalk_ir::cast::*;
 use chalk_ir::fold::shift::Shift;
 use chalk_ir::interner::Interner;
@@ -40,7 +40,7 @@ impl<I: Interner> CoherenceSolver<'_, I> {
                     (true, false) => record_specialization(l_id, r_id),
                     (false, true) => record_specialization(r_id, l_id),
                     (_, _) => {
-                        return Err(CoherenceError::OverlappingImpls(self.trait_id));
+                        return Err(self.overlap_error(l_id, r_id));
                     }
                 }
             }
@@ -86,8 +86,37 @@ impl<I: Interner> CoherenceSolver<'_, I> {
     fn disjoint(&self, lhs: &ImplDatum<I>, rhs: &ImplDatum<I>) -> bool {
         let interner = self.db.interner();
 
-        let (lhs_binders, lhs_bound) = lhs.binders.as_ref().into();
-        let (rhs_binders, rhs_bound) = rhs.binders.as_ref().into();
+        let (lhs_binders, _) = lhs.binders.as_ref().into();
+        let (rhs_binders, _) = rhs.binders.as_ref().into();
+
+        let goal = Box::new(Self::overlap_goal(interner, lhs, rhs))
+            .quantify(interner, QuantifierKind::Exists, lhs_binders)
+            .quantify(interner, QuantifierKind::Exists, rhs_binders)
+            .compatible(interner)
+            .negate(interner);
+
+        let canonical_goal = &goal.into_closed_goal(interner);
+        let mut fresh_solver = (self.solver_builder)();
+        let solution = fresh_solver.solve(self.db, canonical_goal);
+        let result = match solution {
+            // Goal was proven with a unique solution, so no impl was found that causes these two
+            // to overlap
+            Some(Solution::Unique(_)) => true,
+            // Goal was ambiguous, so there *may* be overlap
+            Some(Solution::Ambig(_)) |
+            // Goal cannot be proven, so there is some impl that causes overlap
+            None => false,
+        };
+        debug!("overlaps: result = {:?}", result);
+        result
+    }
+
+    // Creates the goal `T0 = U0, ..., Tm = Um, WC_lhs, WC_rhs` that holds for the parameters
+    // of two impls that both apply to the same types. The goal is under two binders, with the
+    // parameters of `lhs` in the innermost one; see `disjoint` for details.
+    fn overlap_goal(interner: &I, lhs: &ImplDatum<I>, rhs: &ImplDatum<I>) -> Goal<I> {
+        let lhs_bound = lhs.binders.skip_binders();
+        let rhs_bound = rhs.binders.skip_binders();
 
         // Upshift the rhs variables in params to account for the joined binders
         let lhs_params = lhs_bound
@@ -121,28 +150,120 @@ impl<I: Interner> CoherenceSolver<'_, I> {
             .chain(rhs_where_clauses)
             .map(|wc| wc.cast(interner));
 
-        // Join all the goals we've created together with And, then quantify them
-        // over the joined binders. This is our query.
-        let goal = Box::new(Goal::all(interner, params_goals.chain(wc_goals)))
-            .quantify(interner, QuantifierKind::Exists, lhs_binders)
-            .quantify(interner, QuantifierKind::Exists, rhs_binders)
-            .compatible(interner)
-            .negate(interner);
+        // Join all the goals we've created together with And
+        Goal::all(interner, params_goals.chain(wc_goals))
+    }
 
-        let canonical_goal = &goal.into_closed_goal(interner);
-        let mut fresh_solver = (self.solver_builder)();
-        let solution = fresh_solver.solve(self.db, canonical_goal);
-        let result = match solution {
-            // Goal was proven with a unique solution, so no impl was found that causes these two
-            // to overlap
-            Some(Solution::Unique(_)) => true,
-            // Goal was ambiguous, so there *may* be overlap
-            Some(Solution::Ambig(_)) |
-            // Goal cannot be proven, so there is some impl that causes overlap
-            None => false,
+    // Creates the error for two impls that are not disjoint. We look for the types both impls
+    // apply to by solving the overlap goal (see `disjoint`) with the impl parameters as the
+    // variables of the canonical goal, first with the impls known in this crate, and then in
+    // the `compatible` modality, where other crates may add impls:
+    //
+    //  Impls:
+    //      impl<T> Foo for T { }   // rhs
+    //      impl Foo for i32 { }    // lhs
+    //  Generates:
+    //      for<?T> { T = i32 }
+    //  which gives the witness `i32: Foo`.
+    fn overlap_error(&self, lhs_id: ImplId<I>, rhs_id: ImplId<I>) -> CoherenceError<I> {
+        let interner = self.db.interner();
+        let lhs = &self.db.impl_datum(lhs_id);
+        let rhs = &self.db.impl_datum(rhs_id);
+
+        let (kind, solution) = match self.solve_overlap_goal(lhs, rhs, OverlapKind::Local) {
+            Some(solution) => (OverlapKind::Local, Some(solution)),
+            None => (
+                OverlapKind::OtherCrate,
+                self.solve_overlap_goal(lhs, rhs, OverlapKind::OtherCrate),
+            ),
         };
-        debug!("overlaps: result = {:?}", result);
-        result
+        debug!(
+            "overlap_error: kind = {:?}, solution = {:?}",
+            kind, solution
+        );
+
+        let subst = match solution {
+            Some(Solution::Unique(constrained_subst)) => {
+                Some(constrained_subst.map(interner, |c| c.subst))
+            }
+            Some(Solution::Ambig(Guidance::Definite(subst)))
+            | Some(Solution::Ambig(Guidance::Suggested(subst))) => Some(subst),
+            Some(Solution::Ambig(Guidance::Unknown)) | None => None,
+        };
+
+        // The canonical variables are the parameters of `rhs` followed by those of `lhs`
+        let rhs_len = rhs.binders.len(interner);
+        let witness = subst.map(|subst| {
+            subst.map(interner, |subst| {
+                lhs.binders
+                    .map_ref(|bound| bound.trait_ref.clone())
+                    .substitute(interner, &subst.as_slice(interner)[rhs_len..])
+            })
+        });
+
+        CoherenceError::OverlappingImpls {
+            trait_id: self.trait_id,
+            impls: (lhs_id, rhs_id),
+            witness,
+            kind,
+        }
+    }
+
+    fn solve_overlap_goal(
+        &self,
+        lhs: &ImplDatum<I>,
+        rhs: &ImplDatum<I>,
+        kind: OverlapKind,
+    ) -> Option<Solution<I>> {
+        let interner = self.db.interner();
+
+        let (lhs_binders, _) = lhs.binders.as_ref().into();
+        let (rhs_binders, _) = rhs.binders.as_ref().into();
+        let goal = Binders::new(
+            rhs_binders.clone(),
+            Binders::new(lhs_binders.clone(), Self::overlap_goal(interner, lhs, rhs)),
+        )
+        .fuse_binders(interner);
+
+        // `compatible { G }` introduces a downstream type in a new universe. Unlike in
+        // `disjoint`, the impl parameters here are outside of that universe, so we
+        // introduce it ourselves and put the parameters in it.
+        let (universe, environment) = match kind {
+            OverlapKind::Local => (UniverseIndex::ROOT, Environment::new(interner)),
+            OverlapKind::OtherCrate => {
+                let universe = UniverseIndex::ROOT.next();
+                let downstream_ty = PlaceholderIndex {
+                    ui: universe,
+                    idx: 0,
+                }
+                .to_ty(interner);
+                let environment = Environment::new(interner).add_clauses(
+                    interner,
+                    vec![
+                        DomainGoal::Compatible.cast(interner),
+                        DomainGoal::DownstreamType(downstream_ty).cast(interner),
+                    ],
+                );
+                (universe, environment)
+            }
+        };
+
+        let (binders, goal) = goal.into();
+        let canonical_goal = UCanonical {
+            canonical: Canonical {
+                binders: CanonicalVarKinds::from_iter(
+                    interner,
+                    binders
+                        .iter(interner)
+                        .map(|kind| WithKind::new(kind.clone(), universe)),
+                ),
+                value: InEnvironment::new(&environment, goal),
+            },
+            universes: universe.counter + 1,
+        };
+
+        let mut fresh_solver = (self.solver_builder)();
+        fresh_solver.solve(self.db, &canonical_goal)
     }
 
     // Creates a goal which, if provable, means "more special" impl specializes the "less special" one.
@@ -14,6 +14,7 @@ use crate::{logging_db::RecordedItemId, split::Split, RustIrDatabase};
 mod utils;
 
 mod bounds;
+mod goals;
 mod identifiers;
 mod items;
 mod render_trait;
@@ -21,6 +22,7 @@ mod state;
 mod stub;
 mod ty;
 
+use self::items::ImplHeader;
 use self::render_trait::*;
 pub use self::state::*;
 pub use self::utils::sanitize_debug_name;
@@ -102,6 +104,66 @@ where
     Ok(())
 }
 
+/// Displays `v` using `db` for the names of the items it refers to.
+fn display_with_db<'a, I: Interner, T: RenderAsRust<I>>(
+    db: &'a dyn RustIrDatabase<I>,
+    v: &'a T,
+) -> impl Display + 'a {
+    as_display(move |f| v.fmt(&InternalWriterState::for_db(db), f))
+}
+
+/// Displays a goal in the syntax of `.chalk` goals, e.g. for diagnostics.
+///
+/// Bound variables which aren't bound within the goal itself are displayed
+/// as `_`.
+pub fn display_goal<'a, I: Interner>(
+    db: &'a dyn RustIrDatabase<I>,
+    goal: &'a Goal<I>,
+) -> impl Display + 'a {
+    display_with_db(db, goal)
+}
+
+/// Displays a program clause, e.g. `forall<_1_0> { Vec<_1_0>: Clone :- _1_0: Clone }`.
+pub fn display_program_clause<'a, I: Interner>(
+    db: &'a dyn RustIrDatabase<I>,
+    clause: &'a ProgramClause<I>,
+) -> impl Display + 'a {
+    display_with_db(db, clause)
+}
+
+/// Displays a trait reference as a where clause, e.g. `Vec<_>: Clone`.
+pub fn display_trait_ref<'a, I: Interner>(
+    db: &'a dyn RustIrDatabase<I>,
+    trait_ref: &'a TraitRef<I>,
+) -> impl Display + 'a {
+    display_with_db(db, trait_ref)
+}
+
+/// Displays an impl without its body on a single line, e.g.
+/// `impl<_1_0> Clone for Vec<_1_0> where _1_0: Clone`.
+pub fn display_impl_header<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impl_id: ImplId<I>,
+) -> impl Display + '_ {
+    as_display(move |f| {
+        let s = &InternalWriterState::for_db(db);
+        let impl_datum = db.impl_datum(impl_id);
+        write!(f, "{}", ImplHeader(&impl_datum).display(s))?;
+        let s = &s.add_debrujin_index(None);
+        write_joined_non_empty_list!(
+            f,
+            " where {}",
+            impl_datum
+                .binders
+                .skip_binders()
+                .where_clauses
+                .iter()
+                .map(|where_clause| where_clause.display(s)),
+            ", "
+        )
+    })
+}
+
 /// Displays a set of bounds, all targeting `Self`, as just the trait names,
 /// separated by `+`.
 ///
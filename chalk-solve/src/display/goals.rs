@@ -0,0 +1,139 @@
+//! Writer logic for goals and program clauses.
+//!
+//! These are written in the syntax of `.chalk` goals, e.g.
+//! `forall<T> { if (T: Clone) { Vec<T>: Clone } }`.
+use std::fmt::{Formatter, Result};
+
+use chalk_ir::{interner::Interner, *};
+use itertools::Itertools;
+
+use super::{render_trait::RenderAsRust, state::InternalWriterState};
+
+impl<I: Interner> RenderAsRust<I> for DomainGoal<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        match self {
+            DomainGoal::Holds(where_clause) => where_clause.fmt(s, f),
+            DomainGoal::WellFormed(WellFormed::Trait(trait_ref)) => {
+                write!(f, "WellFormed({})", trait_ref.display(s))
+            }
+            DomainGoal::WellFormed(WellFormed::Ty(ty)) => {
+                write!(f, "WellFormed({})", ty.display(s))
+            }
+            DomainGoal::FromEnv(FromEnv::Trait(trait_ref)) => {
+                write!(f, "FromEnv({})", trait_ref.display(s))
+            }
+            DomainGoal::FromEnv(FromEnv::Ty(ty)) => write!(f, "FromEnv({})", ty.display(s)),
+            DomainGoal::Normalize(Normalize { alias, ty }) => {
+                write!(f, "Normalize({} -> {})", alias.display(s), ty.display(s))
+            }
+            DomainGoal::NormalizeConst(NormalizeConst { projection, value }) => write!(
+                f,
+                "NormalizeConst({} -> {})",
+                projection.display(s),
+                value.display(s)
+            ),
+            DomainGoal::EvaluateConst(EvaluateConst { unevaluated, value }) => write!(
+                f,
+                "EvaluateConst({} -> {})",
+                unevaluated.display(s),
+                value.display(s)
+            ),
+            DomainGoal::IsLocal(ty) => write!(f, "IsLocal({})", ty.display(s)),
+            DomainGoal::IsUpstream(ty) => write!(f, "IsUpstream({})", ty.display(s)),
+            DomainGoal::IsFullyVisible(ty) => write!(f, "IsFullyVisible({})", ty.display(s)),
+            DomainGoal::LocalImplAllowed(trait_ref) => {
+                write!(f, "LocalImplAllowed({})", trait_ref.display(s))
+            }
+            DomainGoal::Compatible => write!(f, "Compatible"),
+            DomainGoal::DownstreamType(ty) => write!(f, "DownstreamType({})", ty.display(s)),
+            DomainGoal::Reveal => write!(f, "Reveal"),
+            DomainGoal::ObjectSafe(trait_id) => write!(f, "ObjectSafe({})", trait_id.display(s)),
+        }
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for Goal<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        match self.data(interner) {
+            GoalData::Quantified(kind, subgoal) => {
+                let s = &s.add_debrujin_index(None);
+                // Quantifiers without variables are introduced when
+                // lowering clauses; they don't add anything.
+                if subgoal.binders.is_empty(interner) {
+                    return subgoal.skip_binders().fmt(s, f);
+                }
+                let quantifier = match kind {
+                    QuantifierKind::ForAll => "forall",
+                    QuantifierKind::Exists => "exists",
+                };
+                write!(
+                    f,
+                    "{}<{}> {{ {} }}",
+                    quantifier,
+                    s.binder_var_display(&subgoal.binders).format(", "),
+                    subgoal.skip_binders().display(s)
+                )?;
+                Ok(())
+            }
+            GoalData::Implies(clauses, subgoal) => write!(
+                f,
+                "if ({}) {{ {} }}",
+                clauses
+                    .iter(interner)
+                    .map(|clause| clause.display(s))
+                    .format("; "),
+                subgoal.display(s)
+            ),
+            GoalData::All(goals) => write!(
+                f,
+                "{}",
+                goals
+                    .iter(interner)
+                    .map(|goal| goal.display(s))
+                    .format(", ")
+            ),
+            GoalData::Not(subgoal) => write!(f, "not {{ {} }}", subgoal.display(s)),
+            GoalData::EqGoal(EqGoal { a, b }) => write!(f, "{} = {}", a.display(s), b.display(s)),
+            GoalData::SubtypeGoal(SubtypeGoal { a, b }) => {
+                write!(f, "Subtype({}, {})", a.display(s), b.display(s))
+            }
+            GoalData::DomainGoal(domain_goal) => domain_goal.fmt(s, f),
+            GoalData::CannotProve => write!(f, "CannotProve"),
+        }
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for ProgramClause<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        let ProgramClauseData(implication) = self.data(interner);
+        let s = &s.add_debrujin_index(None);
+        if implication.binders.is_empty(interner) {
+            return implication.skip_binders().fmt(s, f);
+        }
+        write!(
+            f,
+            "forall<{}> {{ {} }}",
+            s.binder_var_display(&implication.binders).format(", "),
+            implication.skip_binders().display(s)
+        )?;
+        Ok(())
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for ProgramClauseImplication<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        // Foo: Bar :- Baz: Bar, Quux: Bar
+        write!(f, "{}", self.consequence.display(s))?;
+        write_joined_non_empty_list!(
+            f,
+            " :- {}",
+            self.conditions
+                .iter(interner)
+                .map(|condition| condition.display(s)),
+            ", "
+        )
+    }
+}
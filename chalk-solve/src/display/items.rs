@@ -203,6 +203,7 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
                 WellKnownTrait::Unpin => "unpin",
                 WellKnownTrait::CoerceUnsized => "coerce_unsized",
                 WellKnownTrait::DiscriminantKind => "discriminant_kind",
+                WellKnownTrait::Generator => "generator",
             };
             writeln!(f, "#[lang({})]", name)?;
         }
@@ -223,41 +224,35 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         // body
         write!(f, "{{")?;
         let s = &s.add_indent();
-        write_joined_non_empty_list!(
-            f,
-            "\n{}\n",
-            self.associated_ty_ids.iter().map(|assoc_ty_id| {
-                let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
-                format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
-            }),
-            "\n"
-        )?;
+        let assoc_tys = self.associated_ty_ids.iter().map(|assoc_ty_id| {
+            let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
+            format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
+        });
+        let assoc_consts = self.associated_const_ids.iter().map(|assoc_const_id| {
+            let assoc_const_data = s.db().associated_const_data(*assoc_const_id);
+            format!("{}{}", s.indent(), (*assoc_const_data).display(s))
+        });
+        write_joined_non_empty_list!(f, "\n{}\n", assoc_tys.chain(assoc_consts), "\n")?;
         write!(f, "}}")?;
         Ok(())
     }
 }
 
-impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
+/// The header of an impl, i.e. the impl without its where clauses and body.
+pub(in crate::display) struct ImplHeader<'a, I: Interner>(pub &'a ImplDatum<I>);
+
+impl<I: Interner> RenderAsRust<I> for ImplHeader<'_, I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
         let interner = s.db().interner();
 
         let s = &s.add_debrujin_index(None);
-        let binders = s.binder_var_display(&self.binders.binders);
-        let value = self.binders.skip_binders();
-
-        // annotations
-        // #[upstream]
-        // ^^^^^^^^^^^
-        // impl<T> Foo<T> for Bar<T> where T: Baz { }
-        if self.impl_type == ImplType::External {
-            writeln!(f, "#[upstream]")?;
-        }
+        let binders = s.binder_var_display(&self.0.binders.binders);
+        let trait_ref = &self.0.binders.skip_binders().trait_ref;
 
         // impl keyword
         // impl<T> Foo<T> for Bar<T> where T: Baz { }
         // ^^^^
         write!(f, "impl")?;
-        let trait_ref = &value.trait_ref;
 
         // generic binders
         // impl<T> Foo<T> for Bar<T> where T: Baz
@@ -276,10 +271,30 @@ impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
         write!(
             f,
             " {}{} for {}",
-            self.polarity.display(s),
+            self.0.polarity.display(s),
             full_trait_name,
             trait_ref.self_type_parameter(interner).display(s)
-        )?;
+        )
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // annotations
+        // #[upstream]
+        // ^^^^^^^^^^^
+        // impl<T> Foo<T> for Bar<T> where T: Baz { }
+        if self.impl_type == ImplType::External {
+            writeln!(f, "#[upstream]")?;
+        }
+
+        // header
+        // impl<T> Foo<T> for Bar<T> where T: Baz { }
+        // ^^^^^^^^^^^^^^^^^^^^^^^^^
+        write!(f, "{}", ImplHeader(self).display(s))?;
+
+        let s = &s.add_debrujin_index(None);
+        let value = self.binders.skip_binders();
 
         // where clauses
         // impl<T> Foo<T> for Bar<T> where T: Baz { }
@@ -303,7 +318,21 @@ impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
                     .display(s)
                     .to_string()
             });
-            write_joined_non_empty_list!(f, "\n{}\n", assoc_ty_values, "\n")?;
+            let assoc_const_values =
+                self.associated_const_value_ids
+                    .iter()
+                    .map(|assoc_const_value| {
+                        s.db()
+                            .associated_const_value(*assoc_const_value)
+                            .display(s)
+                            .to_string()
+                    });
+            write_joined_non_empty_list!(
+                f,
+                "\n{}\n",
+                assoc_ty_values.chain(assoc_const_values),
+                "\n"
+            )?;
         }
         write!(f, "}}")?;
         Ok(())
@@ -447,6 +476,36 @@ impl<I: Interner> RenderAsRust<I> for AssociatedTyValue<I> {
     }
 }
 
+/// Rendered within the trait's `InternalWriterState`, as the type of the
+/// const is bound by the trait's parameters.
+impl<I: Interner> RenderAsRust<I> for AssociatedConstDatum<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "const {}: {};",
+            self.id.display(s),
+            self.ty.skip_binders().display(s)
+        )
+    }
+}
+
+/// Rendered within the impl's `InternalWriterState`, as the value is bound by
+/// the impl's parameters.
+impl<I: Interner> RenderAsRust<I> for AssociatedConstValue<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        let value = self.value.skip_binders();
+        write!(
+            f,
+            "{}const {}: {} = {};",
+            s.indent(),
+            self.associated_const_id.display(s),
+            value.data(interner).ty.display(s),
+            value.display(s)
+        )
+    }
+}
+
 impl<I: Interner> RenderAsRust<I> for FnDefDatum<I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &mut Formatter<'_>) -> Result {
         let s = &s.add_debrujin_index(None);
@@ -10,6 +10,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use crate::display::render_trait::RenderAsRust;
 use crate::RustIrDatabase;
 use chalk_ir::{interner::Interner, *};
 use itertools::Itertools;
@@ -193,9 +194,22 @@ impl<'a, I: Interner> InternalWriterState<'a, I> {
         DB: RustIrDatabase<I>,
         P: Borrow<DB>,
     {
+        Self::with_persistent_state(
+            persistent_state.wrap_db_ref(|db| db.borrow() as &dyn RustIrDatabase<I>),
+        )
+    }
+
+    /// Creates the state for a single write call which doesn't share any
+    /// names with other writes.
+    pub(super) fn for_db(db: &'a dyn RustIrDatabase<I>) -> Self {
+        Self::with_persistent_state(WriterState::new(db))
+    }
+
+    fn with_persistent_state(
+        persistent_state: WriterState<I, dyn RustIrDatabase<I> + 'a, &'a dyn RustIrDatabase<I>>,
+    ) -> Self {
         InternalWriterState {
-            persistent_state: persistent_state
-                .wrap_db_ref(|db| db.borrow() as &dyn RustIrDatabase<I>),
+            persistent_state,
             indent_level: 0,
             debrujin_indices_deep: 0,
             remapping: Rc::new(BTreeMap::new()),
@@ -316,8 +330,16 @@ impl<'a, I: Interner> InternalWriterState<'a, I> {
         self.invert_debrujin_idx(0, idx)
     }
 
+    /// Displays a bound variable by the name of its binder. Variables which
+    /// aren't bound within the value being written, such as the variables
+    /// of a canonical value, have no such name and are written as `_`.
     pub(super) fn display_bound_var(&self, b: &BoundVar) -> impl Display {
-        self.apply_mappings(self.indices_for_bound_var(b))
+        let var = self.indices_for_bound_var(b);
+        if var.inverted_debrujin_idx <= 0 {
+            "_".to_owned()
+        } else {
+            self.apply_mappings(var).to_string()
+        }
     }
 
     pub(super) fn name_for_introduced_bound_var(&self, idx: IndexWithinBinding) -> impl Display {
@@ -344,7 +366,9 @@ impl<'a, I: Interner> InternalWriterState<'a, I> {
             .map(move |(parameter, var)| match parameter {
                 VariableKind::Ty(_) => format!("{}", self.apply_mappings(var)),
                 VariableKind::Lifetime => format!("'{}", self.apply_mappings(var)),
-                VariableKind::Const(_ty) => format!("const {}", self.apply_mappings(var)),
+                VariableKind::Const(ty) => {
+                    format!("const {}: {}", self.apply_mappings(var), ty.display(self))
+                }
             })
     }
 }
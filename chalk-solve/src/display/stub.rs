@@ -11,8 +11,8 @@ use crate::{
     RustIrDatabase,
 };
 use chalk_ir::{
-    interner::Interner, Binders, CanonicalVarKinds, GeneratorId, Substitution, Ty,
-    UnificationDatabase, VariableKinds, Variances,
+    interner::Interner, Binders, CanonicalVarKinds, ConcreteConst, GeneratorId, Substitution, Ty,
+    UnevaluatedConst, UnificationDatabase, VariableKinds, Variances,
 };
 
 #[derive(Debug)]
@@ -56,6 +56,13 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         Arc::new(v)
     }
 
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> std::sync::Arc<crate::rust_ir::AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+
     fn trait_datum(
         &self,
         trait_id: chalk_ir::TraitId<I>,
@@ -119,6 +126,13 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         unreachable!("associated type values should never be stubbed")
     }
 
+    fn associated_const_value(
+        &self,
+        _id: crate::rust_ir::AssociatedConstValueId<I>,
+    ) -> std::sync::Arc<crate::rust_ir::AssociatedConstValue<I>> {
+        unreachable!("associated const values should never be stubbed")
+    }
+
     fn opaque_ty_data(
         &self,
         id: chalk_ir::OpaqueTyId<I>,
@@ -250,6 +264,10 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         self.db.assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: chalk_ir::AssocConstId<I>) -> String {
+        self.db.assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: chalk_ir::OpaqueTyId<I>) -> String {
         self.db.opaque_type_name(opaque_ty_id)
     }
@@ -261,4 +279,12 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
     fn discriminant_type(&self, ty: Ty<I>) -> Ty<I> {
         self.db.discriminant_type(ty)
     }
+
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<I>,
+        unevaluated: &UnevaluatedConst<I>,
+    ) -> Option<ConcreteConst<I>> {
+        self.db.try_evaluate_const(ty, unevaluated)
+    }
 }
@@ -160,6 +160,27 @@ impl<I: Interner> RenderAsRust<I> for ProjectionTy<I> {
     }
 }
 
+impl<I: Interner> RenderAsRust<I> for ProjectionConst<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // <X as Y<A1, A2, A3>>::Z
+        //
+        // Associated consts have no parameters of their own, so the
+        // parameters are X, A1, A2, A3.
+        let trait_id = s
+            .db()
+            .associated_const_data(self.associated_const_id)
+            .trait_id;
+        let trait_params = self.substitution.as_slice(s.db().interner());
+        write!(
+            f,
+            "<{} as {}>::{}",
+            trait_params[0].display(s),
+            display_type_with_generics(s, trait_id, &trait_params[1..]),
+            self.associated_const_id.display(s),
+        )
+    }
+}
+
 impl<I: Interner> RenderAsRust<I> for OpaqueTy<I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
         let interner = s.db().interner();
@@ -262,6 +283,10 @@ impl<I: Interner> RenderAsRust<I> for ConstValue<I> {
             ConstValue::InferenceVar(_) => write!(f, "_"),
             ConstValue::Placeholder(_) => write!(f, "<const placeholder>"),
             ConstValue::Concrete(value) => write!(f, "{:?}", value.interned),
+            ConstValue::Projection(projection) => write!(f, "{{{}}}", projection.display(s)),
+            // The expression is opaque to us, so we have nothing to render
+            // it with.
+            ConstValue::Unevaluated(_) => write!(f, "<unevaluated const>"),
         }
     }
 }
@@ -53,6 +53,10 @@ where
 pub trait GoalExt<I: Interner> {
     fn into_peeled_goal(self, interner: &I) -> UCanonical<InEnvironment<Goal<I>>>;
     fn into_closed_goal(self, interner: &I) -> UCanonical<InEnvironment<Goal<I>>>;
+    fn into_first_domain_goal(
+        self,
+        interner: &I,
+    ) -> Option<UCanonical<InEnvironment<DomainGoal<I>>>>;
 }
 
 impl<I: Interner> GoalExt<I> for Goal<I> {
@@ -110,4 +114,50 @@ impl<I: Interner> GoalExt<I> for Goal<I> {
         let canonical_goal = infer.canonicalize(interner, env_goal).quantified;
         InferenceTable::u_canonicalize(interner, &canonical_goal).quantified
     }
+
+    /// Returns a canonical form of the first domain goal that would be
+    /// proven when proving this goal, in the environment it would be
+    /// proven in. Like in `into_peeled_goal`, the `forall<>` and `exists<>`
+    /// quantifiers around it are converted into free universal or
+    /// existential variables. Returns `None` if the goal contains no
+    /// domain goals. Useful for REPLs and tests but not much else.
+    fn into_first_domain_goal(
+        self,
+        interner: &I,
+    ) -> Option<UCanonical<InEnvironment<DomainGoal<I>>>> {
+        let mut infer = InferenceTable::new();
+        let env_goal = first_domain_goal(interner, &mut infer, &Environment::new(interner), &self)?;
+        let canonical_goal = infer.canonicalize(interner, env_goal).quantified;
+        Some(InferenceTable::u_canonicalize(interner, &canonical_goal).quantified)
+    }
+}
+
+fn first_domain_goal<I: Interner>(
+    interner: &I,
+    infer: &mut InferenceTable<I>,
+    environment: &Environment<I>,
+    goal: &Goal<I>,
+) -> Option<InEnvironment<DomainGoal<I>>> {
+    match goal.data(interner) {
+        GoalData::Quantified(QuantifierKind::ForAll, subgoal) => {
+            let subgoal = infer.instantiate_binders_universally(interner, subgoal.clone());
+            first_domain_goal(interner, infer, environment, &subgoal)
+        }
+        GoalData::Quantified(QuantifierKind::Exists, subgoal) => {
+            let subgoal = infer.instantiate_binders_existentially(interner, subgoal.clone());
+            first_domain_goal(interner, infer, environment, &subgoal)
+        }
+        GoalData::Implies(wc, subgoal) => {
+            let new_environment = environment.add_clauses(interner, wc.iter(interner).cloned());
+            first_domain_goal(interner, infer, &new_environment, subgoal)
+        }
+        GoalData::All(goals) => goals
+            .iter(interner)
+            .find_map(|goal| first_domain_goal(interner, infer, environment, goal)),
+        GoalData::Not(subgoal) => first_domain_goal(interner, infer, environment, subgoal),
+        GoalData::DomainGoal(domain_goal) => {
+            Some(InEnvironment::new(environment, domain_goal.clone()))
+        }
+        GoalData::EqGoal(_) | GoalData::SubtypeGoal(_) | GoalData::CannotProve => None,
+    }
 }
@@ -1098,13 +1098,17 @@ impl<'t, I: Interner> Unifier<'t, I> {
 
             // Unifying an inference variables with a non-inference variable.
             (&ConstValue::InferenceVar(var), &ConstValue::Concrete(_))
-            | (&ConstValue::InferenceVar(var), &ConstValue::Placeholder(_)) => {
+            | (&ConstValue::InferenceVar(var), &ConstValue::Placeholder(_))
+            | (&ConstValue::InferenceVar(var), &ConstValue::Projection(_))
+            | (&ConstValue::InferenceVar(var), &ConstValue::Unevaluated(_)) => {
                 debug!(?var, ty=?b, "unify_var_ty");
                 self.unify_var_const(var, b)
             }
 
             (&ConstValue::Concrete(_), &ConstValue::InferenceVar(var))
-            | (&ConstValue::Placeholder(_), &ConstValue::InferenceVar(var)) => {
+            | (&ConstValue::Placeholder(_), &ConstValue::InferenceVar(var))
+            | (&ConstValue::Projection(_), &ConstValue::InferenceVar(var))
+            | (&ConstValue::Unevaluated(_), &ConstValue::InferenceVar(var)) => {
                 debug!(?var, ty=?a, "unify_var_ty");
                 self.unify_var_const(var, a)
             }
@@ -1124,6 +1128,62 @@ impl<'t, I: Interner> Unifier<'t, I> {
             (&ConstValue::Concrete(_), &ConstValue::Placeholder(_))
             | (&ConstValue::Placeholder(_), &ConstValue::Concrete(_)) => Err(NoSolution),
 
+            // Projections of the same associated const are first related
+            // structurally, so that projections which can't be normalized
+            // (e.g. `<T as Trait>::N` for a placeholder `T`) are still equal
+            // to themselves. Projections with different parameters may still
+            // normalize to the same value, so those fall through to a
+            // `NormalizeConst` goal below.
+            (ConstValue::Projection(p1), ConstValue::Projection(p2))
+                if p1.associated_const_id == p2.associated_const_id
+                    && self.try_zip_substs(
+                        p1.substitution.as_slice(interner),
+                        p2.substitution.as_slice(interner),
+                    ) =>
+            {
+                Ok(())
+            }
+
+            (ConstValue::Projection(projection), _) => {
+                self.relate_projection_const(projection, b);
+                Ok(())
+            }
+
+            (_, ConstValue::Projection(projection)) => {
+                self.relate_projection_const(projection, a);
+                Ok(())
+            }
+
+            // Expressions with different parameters may still evaluate to
+            // the same value (like `{N * 0}` and `{M * 0}`), so unevaluated
+            // consts are generally related by an `EvaluateConst` goal below.
+            // Those that can never be evaluated are related structurally
+            // instead, so that e.g. `{N + 1}` in an impl header matches
+            // `{N + 1}` for a placeholder `N`.
+            (ConstValue::Unevaluated(u1), ConstValue::Unevaluated(u2))
+                if u1.interned == u2.interned
+                    && (u1 == u2 || !(self.may_be_evaluated(u1) && self.may_be_evaluated(u2)))
+                    && self.try_zip_substs(
+                        u1.substitution.as_slice(interner),
+                        u2.substitution.as_slice(interner),
+                    ) =>
+            {
+                Ok(())
+            }
+
+            // We can't tell whether an unevaluated const is equal to some
+            // other const before evaluating it, which may have to wait until
+            // its parameters are known, so defer to an `EvaluateConst` goal.
+            (ConstValue::Unevaluated(_), _) => {
+                self.relate_unevaluated_const(a, b);
+                Ok(())
+            }
+
+            (_, ConstValue::Unevaluated(_)) => {
+                self.relate_unevaluated_const(b, a);
+                Ok(())
+            }
+
             (ConstValue::BoundVar(_), _) | (_, ConstValue::BoundVar(_)) => panic!(
                 "unification encountered bound variable: a={:?} b={:?}",
                 a, b
@@ -1131,6 +1191,84 @@ impl<'t, I: Interner> Unifier<'t, I> {
         }
     }
 
+    /// Relates two substitutions invariantly, returning whether that
+    /// succeeded. On failure, any inference done and goals created along the
+    /// way are undone, so that the caller can relate them some other way.
+    fn try_zip_substs(&mut self, a: &[GenericArg<I>], b: &[GenericArg<I>]) -> bool {
+        let snapshot = self.table.snapshot();
+        let goals_len = self.goals.len();
+        match self.zip_substs(Variance::Invariant, None, a, b) {
+            Ok(()) => {
+                self.table.commit(snapshot);
+                true
+            }
+            Err(NoSolution) => {
+                self.table.rollback_to(snapshot);
+                self.goals.truncate(goals_len);
+                false
+            }
+        }
+    }
+
+    /// Whether the const parameters of `unevaluated` are concrete, or are
+    /// inference variables that may still become concrete, so that it can
+    /// eventually be evaluated.
+    fn may_be_evaluated(&mut self, unevaluated: &UnevaluatedConst<I>) -> bool {
+        let interner = self.interner;
+        unevaluated
+            .substitution
+            .iter(interner)
+            .filter_map(|arg| arg.constant(interner))
+            .all(|c| {
+                let c = self
+                    .table
+                    .normalize_const_shallow(interner, c)
+                    .unwrap_or_else(|| c.clone());
+                matches!(
+                    c.data(interner).value,
+                    ConstValue::Concrete(_) | ConstValue::InferenceVar(_)
+                )
+            })
+    }
+
+    /// Relate an associated const projection like `<T as Trait>::N` with
+    /// some other const `value` by creating a goal like
+    ///
+    /// ```notrust
+    /// NormalizeConst(<T as Trait>::N -> value)
+    /// ```
+    #[instrument(level = "debug", skip(self))]
+    fn relate_projection_const(&mut self, projection: &ProjectionConst<I>, value: &Const<I>) {
+        let interner = self.interner;
+        self.goals.push(InEnvironment::new(
+            self.environment,
+            NormalizeConst {
+                projection: projection.clone(),
+                value: value.clone(),
+            }
+            .cast(interner),
+        ));
+    }
+
+    /// Relate an unevaluated const like `N + 1` with some other const
+    /// `value` by creating a goal like
+    ///
+    /// ```notrust
+    /// EvaluateConst(N + 1 -> value)
+    /// ```
+    #[instrument(level = "debug", skip(self))]
+    fn relate_unevaluated_const(&mut self, unevaluated: &Const<I>, value: &Const<I>) {
+        let interner = self.interner;
+        self.goals.push(InEnvironment::new(
+            self.environment,
+            EvaluateConst {
+                unevaluated: unevaluated.clone(),
+                value: value.clone(),
+            }
+            .cast(interner),
+        ));
+    }
+
     #[instrument(level = "debug", skip(self))]
     fn unify_var_const(&mut self, var: InferenceVar, c: &Const<I>) -> Fallible<()> {
         let interner = self.interner;
@@ -17,6 +17,7 @@ pub mod goal_builder;
 pub mod infer;
 pub mod logging;
 pub mod logging_db;
+pub mod object_safety;
 pub mod rust_ir;
 pub mod solve;
 pub mod split;
@@ -49,6 +50,9 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the datum for the associated type with the given id.
     fn associated_ty_data(&self, ty: AssocTypeId<I>) -> Arc<AssociatedTyDatum<I>>;
 
+    /// Returns the datum for the associated const with the given id.
+    fn associated_const_data(&self, id: AssocConstId<I>) -> Arc<AssociatedConstDatum<I>>;
+
     /// Returns the datum for the definition with the given id.
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>>;
 
@@ -76,6 +80,10 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the `AssociatedTyValue` with the given id.
     fn associated_ty_value(&self, id: AssociatedTyValueId<I>) -> Arc<AssociatedTyValue<I>>;
 
+    /// Returns the `AssociatedConstValue` with the given id.
+    fn associated_const_value(&self, id: AssociatedConstValueId<I>)
+        -> Arc<AssociatedConstValue<I>>;
+
     /// Returns the `OpaqueTyDatum` with the given id.
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>>;
 
@@ -125,7 +133,9 @@ pub trait RustIrDatabase<I: Interner>: Debug {
 
     fn interner(&self) -> &I;
 
-    /// Check if a trait is object safe
+    /// Check if a trait is object safe. Hosts that don't track object
+    /// safety themselves can implement this with
+    /// [`object_safety::is_object_safe`].
     fn is_object_safe(&self, trait_id: TraitId<I>) -> bool;
 
     /// Gets the `ClosureKind` for a given closure and substitution.
@@ -181,6 +191,12 @@ pub trait RustIrDatabase<I: Interner>: Debug {
         sanitize_debug_name(|f| I::debug_assoc_type_id(assoc_ty_id, f))
     }
 
+    /// Retrieves the name of an associated const. No uniqueness guarantees, but must
+    /// a valid Rust identifier.
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        sanitize_debug_name(|f| I::debug_assoc_const_id(assoc_const_id, f))
+    }
+
     /// Retrieves the name of an opaque type. No uniqueness guarantees, but must
     /// a valid Rust identifier.
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
@@ -195,10 +211,23 @@ pub trait RustIrDatabase<I: Interner>: Debug {
 
     // Retrieves the discriminant type for a type (mirror of rustc `TyS::discriminant_ty`)
     fn discriminant_type(&self, ty: Ty<I>) -> Ty<I>;
+
+    /// Evaluates an unevaluated const of type `ty`, whose const parameters
+    /// are all concrete. Returns `None` if evaluating it fails, e.g. because
+    /// it overflows, in which case it isn't equal to any const.
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<I>,
+        unevaluated: &UnevaluatedConst<I>,
+    ) -> Option<ConcreteConst<I>>;
 }
 
 pub use clauses::program_clauses_for_env;
 
+pub use solve::explain::UnprovableGoal;
+pub use solve::proof_tree::ProofTree;
+#[cfg(feature = "serde")]
+pub use solve::serialize::Serializable;
 pub use solve::Guidance;
 pub use solve::Solution;
 pub use solve::Solver;
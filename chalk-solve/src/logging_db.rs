@@ -128,6 +128,15 @@ where
         ty_datum
     }
 
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> Arc<crate::rust_ir::AssociatedConstDatum<I>> {
+        let const_datum = self.ws.db().associated_const_data(id);
+        self.record(const_datum.trait_id);
+        const_datum
+    }
+
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>> {
         self.record(trait_id);
         self.ws.db().trait_datum(trait_id)
@@ -175,6 +184,15 @@ where
         value
     }
 
+    fn associated_const_value(
+        &self,
+        id: crate::rust_ir::AssociatedConstValueId<I>,
+    ) -> Arc<crate::rust_ir::AssociatedConstValue<I>> {
+        let value = self.ws.db().associated_const_value(id);
+        self.record(value.impl_id);
+        value
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>> {
         self.record(id);
         self.ws.db().opaque_ty_data(id)
@@ -237,6 +255,10 @@ where
         self.ws.db().assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        self.ws.db().assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
         self.ws.db().opaque_type_name(opaque_ty_id)
     }
@@ -287,6 +309,14 @@ where
         self.ws.db().discriminant_type(ty)
     }
 
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<I>,
+        unevaluated: &UnevaluatedConst<I>,
+    ) -> Option<ConcreteConst<I>> {
+        self.ws.db().try_evaluate_const(ty, unevaluated)
+    }
+
     fn unification_database(&self) -> &dyn UnificationDatabase<I> {
         self
     }
@@ -395,6 +425,13 @@ where
         self.db.associated_ty_data(ty)
     }
 
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> Arc<crate::rust_ir::AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>> {
         self.db.trait_datum(trait_id)
     }
@@ -430,6 +467,13 @@ where
         self.db.associated_ty_value(id)
     }
 
+    fn associated_const_value(
+        &self,
+        id: crate::rust_ir::AssociatedConstValueId<I>,
+    ) -> Arc<crate::rust_ir::AssociatedConstValue<I>> {
+        self.db.associated_const_value(id)
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>> {
         self.db.opaque_ty_data(id)
     }
@@ -493,6 +537,10 @@ where
         self.db.assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        self.db.assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
         self.db.opaque_type_name(opaque_ty_id)
     }
@@ -533,6 +581,14 @@ where
     fn discriminant_type(&self, ty: Ty<I>) -> Ty<I> {
         self.db.discriminant_type(ty)
     }
+
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<I>,
+        unevaluated: &UnevaluatedConst<I>,
+    ) -> Option<ConcreteConst<I>> {
+        self.db.try_evaluate_const(ty, unevaluated)
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
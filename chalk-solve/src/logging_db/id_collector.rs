@@ -4,7 +4,7 @@ use chalk_ir::{
     interner::Interner,
     visit::{ControlFlow, Visitor},
     visit::{SuperVisit, Visit},
-    AliasTy, DebruijnIndex, TyKind, WhereClause,
+    AliasTy, ConstValue, DebruijnIndex, TyKind, WhereClause,
 };
 use std::collections::BTreeSet;
 
@@ -65,6 +65,12 @@ pub fn collect_unrecorded_ids<'i, I: Interner, DB: RustIrDatabase<I>>(
                         .visit_with(&mut collector, DebruijnIndex::INNERMOST);
                     assoc_ty_datum.visit_with(&mut collector, DebruijnIndex::INNERMOST);
                 }
+                for assoc_const_id in &trait_datum.associated_const_ids {
+                    collector
+                        .db
+                        .associated_const_data(*assoc_const_id)
+                        .visit_with(&mut collector, DebruijnIndex::INNERMOST);
+                }
             }
             RecordedItemId::OpaqueTy(opaque_id) => {
                 collector
@@ -82,6 +88,10 @@ pub fn collect_unrecorded_ids<'i, I: Interner, DB: RustIrDatabase<I>>(
                     let assoc_ty_value = collector.db.associated_ty_value(*id);
                     assoc_ty_value.visit_with(&mut collector, DebruijnIndex::INNERMOST);
                 }
+                for id in &impl_datum.associated_const_value_ids {
+                    let assoc_const_value = collector.db.associated_const_value(*id);
+                    assoc_const_value.visit_with(&mut collector, DebruijnIndex::INNERMOST);
+                }
                 impl_datum.visit_with(&mut collector, DebruijnIndex::INNERMOST);
             }
         }
@@ -147,6 +157,20 @@ where
         ty.super_visit_with(self, outer_binder)
     }
 
+    fn visit_const(
+        &mut self,
+        constant: &chalk_ir::Const<I>,
+        outer_binder: chalk_ir::DebruijnIndex,
+    ) -> ControlFlow<()> {
+        if let ConstValue::Projection(projection) = &constant.data(self.db.interner()).value {
+            let assoc_const_datum = self
+                .db
+                .associated_const_data(projection.associated_const_id);
+            self.record(assoc_const_datum.trait_id)
+        }
+        constant.super_visit_with(self, outer_binder)
+    }
+
     fn visit_where_clause(
         &mut self,
         where_clause: &WhereClause<I>,
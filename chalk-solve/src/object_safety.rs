@@ -0,0 +1,147 @@
+//! Computes whether a trait is object safe from the information in its
+//! `TraitDatum`, the `AssociatedTyDatum`s of its associated types and the
+//! where clauses of its supertraits.
+//!
+//! Hosts can use `is_object_safe` to implement
+//! `RustIrDatabase::is_object_safe`. Note that chalk does not know about
+//! trait methods, so violations caused by methods (e.g. methods returning
+//! `Self` or having generic parameters) are not detected here.
+
+use crate::clauses::super_traits::super_traits;
+use crate::rust_ir::WellKnownTrait;
+use crate::RustIrDatabase;
+use chalk_ir::{
+    interner::Interner,
+    visit::{ControlFlow, SuperVisit, Visit, Visitor},
+    AliasEq, AliasTy, AssocTypeId, BoundVar, DebruijnIndex, GenericArg, TraitId, Ty, TyKind,
+    WhereClause,
+};
+
+/// A reason for a trait not being object safe.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectSafetyViolation<I: Interner> {
+    /// The trait requires `Self: Sized`, either directly or through one of
+    /// its supertraits.
+    SizedSelf,
+
+    /// A where clause of the given trait (which is the trait itself or one of
+    /// its supertraits) uses `Self` as a type argument, e.g.
+    /// `trait Foo where Self: Bar<Self>`.
+    SupertraitSelf(TraitId<I>),
+
+    /// The given associated type has generic parameters of its own.
+    GenericAssociatedType(AssocTypeId<I>),
+}
+
+/// Returns true if the trait has no object safety violations.
+pub fn is_object_safe<I: Interner>(db: &dyn RustIrDatabase<I>, trait_id: TraitId<I>) -> bool {
+    object_safety_violations(db, trait_id).is_empty()
+}
+
+/// Returns all the reasons why the trait with id `trait_id` is not object
+/// safe. The trait is object safe if this is empty.
+pub fn object_safety_violations<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    trait_id: TraitId<I>,
+) -> Vec<ObjectSafetyViolation<I>> {
+    let interner = db.interner();
+    let mut violations = Vec::new();
+
+    // The trait itself comes first; a trait can be reached through
+    // several paths in the supertrait graph, so only visit each once.
+    let mut trait_ids = Vec::new();
+    for super_trait_ref in super_traits(db, trait_id).skip_binders() {
+        let super_trait_id = super_trait_ref.skip_binders().trait_id;
+        if !trait_ids.contains(&super_trait_id) {
+            trait_ids.push(super_trait_id);
+        }
+    }
+
+    if let Some(sized_trait_id) = db.well_known_trait_id(WellKnownTrait::Sized) {
+        if trait_ids.contains(&sized_trait_id) {
+            violations.push(ObjectSafetyViolation::SizedSelf);
+        }
+    }
+
+    for &trait_id in &trait_ids {
+        let trait_datum = db.trait_datum(trait_id);
+
+        let where_clause_references_self = trait_datum
+            .binders
+            .skip_binders()
+            .where_clauses
+            .iter()
+            .any(|qwc| where_clause_references_self(interner, qwc.skip_binders()));
+        if where_clause_references_self {
+            violations.push(ObjectSafetyViolation::SupertraitSelf(trait_id));
+        }
+
+        // As in rustc, associated types with parameters of their own make
+        // the trait not object safe: there's no way to write the value of
+        // `Assoc<'a>` for every `'a` in the type `dyn Trait<Assoc = ..>`.
+        let trait_params_len = trait_datum.binders.len(interner);
+        for &assoc_ty_id in &trait_datum.associated_ty_ids {
+            let assoc_ty_datum = db.associated_ty_data(assoc_ty_id);
+            if assoc_ty_datum.binders.len(interner) > trait_params_len {
+                violations.push(ObjectSafetyViolation::GenericAssociatedType(assoc_ty_id));
+            }
+        }
+    }
+
+    violations
+}
+
+/// Checks if a where clause `Self: Trait<..>` or `<Self as Trait<..>>::Item<..> = U`
+/// of a trait uses `Self` anywhere in the generic arguments of `Trait` or `Item`.
+/// The where clause is expected to be one binder (the where clause's own) away from
+/// the trait's binders, in which `Self` is the first parameter.
+fn where_clause_references_self<I: Interner>(interner: &I, where_clause: &WhereClause<I>) -> bool {
+    let self_var = BoundVar::new(DebruijnIndex::ONE, 0);
+    let args: &[GenericArg<I>] = match where_clause {
+        WhereClause::Implemented(trait_ref) => {
+            if trait_ref.self_type_parameter(interner).bound_var(interner) != Some(self_var) {
+                return false;
+            }
+            trait_ref.substitution.as_slice(interner)
+        }
+        WhereClause::AliasEq(AliasEq {
+            alias: AliasTy::Projection(projection),
+            ..
+        }) => projection.substitution.as_slice(interner),
+        _ => return false,
+    };
+
+    let mut visitor = SelfOccurrenceCheck { interner };
+    args.iter()
+        .skip(1)
+        .any(|arg| arg.visit_with(&mut visitor, DebruijnIndex::ONE).is_break())
+}
+
+/// Looks for the `Self` parameter of a trait, i.e. `^N.0` where `N` is
+/// the number of binders between the visited value and the trait's binders.
+struct SelfOccurrenceCheck<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for SelfOccurrenceCheck<'i, I> {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn visit_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> ControlFlow<()> {
+        match ty.kind(self.interner) {
+            TyKind::BoundVar(bound_var)
+                if bound_var.debruijn == outer_binder && bound_var.index == 0 =>
+            {
+                ControlFlow::BREAK
+            }
+            _ => ty.super_visit_with(self, outer_binder),
+        }
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
@@ -9,9 +9,9 @@ use chalk_ir::interner::Interner;
 use chalk_ir::{
     try_break,
     visit::{ControlFlow, Visit},
-    AdtId, AliasEq, AliasTy, AssocTypeId, Binders, DebruijnIndex, FnDefId, GenericArg, ImplId,
-    OpaqueTyId, ProjectionTy, QuantifiedWhereClause, Substitution, ToGenericArg, TraitId, TraitRef,
-    Ty, TyKind, VariableKind, WhereClause, WithKind,
+    AdtId, AliasEq, AliasTy, AssocConstId, AssocTypeId, Binders, Const, DebruijnIndex, FnDefId,
+    GenericArg, ImplId, OpaqueTyId, ProjectionTy, QuantifiedWhereClause, Substitution,
+    ToGenericArg, TraitId, TraitRef, Ty, TyKind, VariableKind, WhereClause, WithKind,
 };
 use std::iter;
 
@@ -22,12 +22,20 @@ pub struct AssociatedTyValueId<I: Interner>(pub I::DefId);
 chalk_ir::id_visit!(AssociatedTyValueId);
 chalk_ir::id_fold!(AssociatedTyValueId);
 
+/// Identifier for an "associated const value" found in some impl.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssociatedConstValueId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_visit!(AssociatedConstValueId);
+chalk_ir::id_fold!(AssociatedConstValueId);
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Visit)]
 pub struct ImplDatum<I: Interner> {
     pub polarity: Polarity,
     pub binders: Binders<ImplDatumBound<I>>,
     pub impl_type: ImplType,
     pub associated_ty_value_ids: Vec<AssociatedTyValueId<I>>,
+    pub associated_const_value_ids: Vec<AssociatedConstValueId<I>>,
 }
 
 impl<I: Interner> ImplDatum<I> {
@@ -239,6 +247,8 @@ pub struct TraitDatum<I: Interner> {
 
     pub associated_ty_ids: Vec<AssocTypeId<I>>,
 
+    pub associated_const_ids: Vec<AssocConstId<I>>,
+
     /// If this is a well-known trait, which one? If `None`, this is a regular,
     /// user-defined trait.
     pub well_known: Option<WellKnownTrait>,
@@ -262,6 +272,10 @@ pub enum WellKnownTrait {
     Unpin,
     CoerceUnsized,
     DiscriminantKind,
+    /// The trait `Generator<R>` - the generic argument `R` is the resume type
+    /// of the generator. Its associated types `Yield` and `Return` are declared
+    /// in that order.
+    Generator,
 }
 
 chalk_ir::const_visit!(WellKnownTrait);
@@ -614,6 +628,68 @@ pub struct AssociatedTyValueBound<I: Interner> {
     pub ty: Ty<I>,
 }
 
+/// Represents an associated const declaration found inside of a trait:
+///
+/// ```notrust
+/// trait Foo<P1..Pn> { // P0 is Self
+///     const N: Ty;
+/// }
+/// ```
+///
+/// Unlike associated types, associated consts have no parameters of their
+/// own, so the parameters in scope are those of the trait.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssociatedConstDatum<I: Interner> {
+    /// The trait this associated const is defined in.
+    pub trait_id: TraitId<I>,
+
+    /// The ID of this associated const
+    pub id: AssocConstId<I>,
+
+    /// Name of this associated const.
+    pub name: I::Identifier,
+
+    /// The type of the const. The binders represent the `P0...Pn`
+    /// parameters of the trait.
+    pub ty: Binders<Ty<I>>,
+}
+
+// Manual implementation to avoid I::Identifier type.
+impl<I: Interner> Visit<I> for AssociatedConstDatum<I> {
+    fn visit_with<'i, B>(
+        &self,
+        visitor: &mut dyn chalk_ir::visit::Visitor<'i, I, BreakTy = B>,
+        outer_binder: DebruijnIndex,
+    ) -> ControlFlow<B>
+    where
+        I: 'i,
+    {
+        try_break!(self.trait_id.visit_with(visitor, outer_binder));
+        try_break!(self.id.visit_with(visitor, outer_binder));
+        self.ty.visit_with(visitor, outer_binder)
+    }
+}
+
+/// Represents the *value* of an associated const that is assigned
+/// from within some impl.
+///
+/// ```ignore
+/// impl Foo for Bar {
+///     const N: usize = 3; // <-- represents this line!
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, Visit)]
+pub struct AssociatedConstValue<I: Interner> {
+    /// Impl in which this associated const value is found.
+    pub impl_id: ImplId<I>,
+
+    /// Associated const being defined.
+    pub associated_const_id: AssocConstId<I>,
+
+    /// The value of the const. The binders are those of the impl.
+    pub value: Binders<Const<I>>,
+}
+
 /// Represents the bounds for an `impl Trait` type.
 ///
 /// ```ignore
@@ -663,15 +739,15 @@ pub struct GeneratorDatum<I: Interner> {
 pub struct GeneratorInputOutputDatum<I: Interner> {
     /// The generator resume type - a value of this type
     /// is supplied by the caller when resuming the generator.
-    /// Currently, this plays no rule in goal resolution.
+    /// This is the `R` in the built-in `Generator<R>` impl.
     pub resume_type: Ty<I>,
     /// The generator yield type - a value of this type
     /// is supplied by the generator during a yield.
-    /// Currently, this plays no role in goal resolution.
+    /// This is the value of `Generator::Yield`.
     pub yield_type: Ty<I>,
     /// The generator return type - a value of this type
     /// is supplied by the generator when it returns.
-    /// Currently, this plays no role in goal resolution
+    /// This is the value of `Generator::Return`.
     pub return_type: Ty<I>,
     /// The upvars stored by the generator. These represent
     /// types captured from the generator's environment,
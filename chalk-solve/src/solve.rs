@@ -5,6 +5,10 @@ use chalk_ir::*;
 use std::fmt;
 use tracing::debug;
 
+pub mod explain;
+pub mod proof_tree;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod truncate;
 
 /// A (possible) solution for a proposed goal.
@@ -302,6 +306,35 @@ where
         f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<I>>>, bool) -> bool,
     ) -> bool;
 
+    /// Attempts to solve the given goal like `solve` and, if it can be
+    /// proven, also returns a proof tree that shows which program clauses
+    /// were used to prove it and its subgoals.
+    ///
+    /// The tree is reconstructed by solving the subgoals of each of its
+    /// nodes separately, so this is a lot more expensive than `solve`.
+    fn solve_with_proof_tree(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Option<(Solution<I>, proof_tree::ProofTree<I>)> {
+        proof_tree::solve_with_proof_tree(self, program, goal)
+    }
+
+    /// Explains why the given goal cannot be proven, by returning the
+    /// deepest of its subgoals that can't be proven (typically because no
+    /// impl or where clause applies to them), along with the clauses that
+    /// required them. Returns an empty vector if the goal can be proven.
+    ///
+    /// Like `solve_with_proof_tree`, this solves many subgoals separately,
+    /// so it should only be used once `solve` has failed.
+    fn explain_failure(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Vec<explain::UnprovableGoal<I>> {
+        explain::explain_failure(self, program, goal)
+    }
+
     /// A convenience method for when one doesn't need the actual solution,
     /// only whether or not one exists.
     fn has_unique_solution(
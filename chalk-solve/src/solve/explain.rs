@@ -0,0 +1,289 @@
+//! Explains why goals cannot be proven; see [`Solver::explain_failure`].
+//!
+//! Starting from a goal that the solver could not prove, we look at every
+//! program clause whose consequence unifies with it and descend into those
+//! of its conditions that the solver can't prove on their own. The goals
+//! where this ends -- usually domain goals without any matching clause --
+//! are reported, along with the clauses that required them.
+
+use crate::display::{display_goal, display_program_clause};
+use crate::infer::InferenceTable;
+use crate::solve::proof_tree::ProofTreeBuilder;
+use crate::solve::Solver;
+use crate::RustIrDatabase;
+use chalk_ir::interner::Interner;
+use chalk_ir::zip::Zip;
+use chalk_ir::*;
+use std::fmt;
+use tracing::debug;
+
+/// How deep we look into the subgoals of a goal before giving up.
+const MAX_DEPTH: usize = 32;
+
+/// A goal that is a root cause of another goal not being provable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnprovableGoal<I: Interner> {
+    /// The goal that could not be proven. This is usually a domain goal that
+    /// no program clause matches, but it can also be a goal whose clauses
+    /// all match but whose conditions only fail in combination, a failed
+    /// unification or a negation. Unresolved inference variables are
+    /// replaced by bound variables, which are displayed as `_`.
+    pub goal: Goal<I>,
+
+    /// The program clauses that required `goal` to hold, starting with the
+    /// clause that has `goal` as a condition and ending with the clause for
+    /// the goal that was originally being proven. A clause is listed once for
+    /// every step it was used in, so e.g. `impl<T> Clone for Vec<T> where T:
+    /// Clone` appears twice for `Vec<Vec<Foo>>: Clone`.
+    pub required_by: Vec<ProgramClause<I>>,
+}
+
+impl<I: Interner> UnprovableGoal<I> {
+    /// Displays the goal, followed by the clauses that required it, one per
+    /// line. `db` provides the names of the items they refer to.
+    pub fn display<'a>(&'a self, db: &'a dyn RustIrDatabase<I>) -> UnprovableGoalDisplay<'a, I> {
+        UnprovableGoalDisplay { cause: self, db }
+    }
+}
+
+pub struct UnprovableGoalDisplay<'a, I: Interner> {
+    cause: &'a UnprovableGoal<I>,
+    db: &'a dyn RustIrDatabase<I>,
+}
+
+impl<'a, I: Interner> fmt::Display for UnprovableGoalDisplay<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let UnprovableGoalDisplay { cause, db } = self;
+        write!(f, "`{}` cannot be proven", display_goal(*db, &cause.goal))?;
+        for clause in &cause.required_by {
+            write!(
+                f,
+                "\n    required by `{}`",
+                display_program_clause(*db, clause)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn explain_failure<I: Interner, S: Solver<I> + ?Sized>(
+    solver: &mut S,
+    db: &dyn RustIrDatabase<I>,
+    goal: &UCanonical<InEnvironment<Goal<I>>>,
+) -> Vec<UnprovableGoal<I>> {
+    if solver.solve(db, goal).is_some() {
+        return vec![];
+    }
+
+    let interner = db.interner();
+    let (infer, _, InEnvironment { environment, goal }) =
+        InferenceTable::from_canonical(interner, goal.universes, goal.canonical.clone());
+    let mut explainer = FailureExplainer {
+        builder: ProofTreeBuilder::new(db, solver, infer),
+        required_by: vec![],
+        causes: vec![],
+    };
+    explainer.explain(&environment, goal);
+    explainer.causes
+}
+
+struct FailureExplainer<'s, I: Interner, S: Solver<I> + ?Sized> {
+    builder: ProofTreeBuilder<'s, I, S>,
+
+    /// The clauses whose conditions we are currently looking into, outermost
+    /// first.
+    required_by: Vec<ProgramClause<I>>,
+
+    causes: Vec<UnprovableGoal<I>>,
+}
+
+impl<'s, I: Interner, S: Solver<I> + ?Sized> FailureExplainer<'s, I, S> {
+    /// Looks for the reasons why `goal`, which is known not to hold, can't
+    /// be proven.
+    fn explain(&mut self, environment: &Environment<I>, goal: Goal<I>) {
+        let interner = self.builder.db.interner();
+        match goal.data(interner) {
+            GoalData::Quantified(QuantifierKind::ForAll, subgoal) => {
+                let subgoal = self
+                    .builder
+                    .infer
+                    .instantiate_binders_universally(interner, subgoal.clone());
+                self.explain(environment, subgoal);
+            }
+            GoalData::Quantified(QuantifierKind::Exists, subgoal) => {
+                let subgoal = self
+                    .builder
+                    .infer
+                    .instantiate_binders_existentially(interner, subgoal.clone());
+                self.explain(environment, subgoal);
+            }
+            GoalData::Implies(clauses, subgoal) => {
+                let environment =
+                    environment.add_clauses(interner, clauses.iter(interner).cloned());
+                self.explain(&environment, subgoal.clone());
+            }
+            GoalData::All(goals) => {
+                let goals = goals.iter(interner).cloned().collect();
+                if !self.explain_failing(environment, goals) {
+                    self.record(&goal);
+                }
+            }
+            GoalData::EqGoal(EqGoal { a, b }) => {
+                self.explain_relate(environment, Variance::Invariant, a, b, &goal)
+            }
+            GoalData::SubtypeGoal(SubtypeGoal { a, b }) => {
+                self.explain_relate(environment, Variance::Covariant, a, b, &goal)
+            }
+            GoalData::DomainGoal(domain_goal) => {
+                self.explain_domain_goal(environment, domain_goal, &goal)
+            }
+            GoalData::Not(_) | GoalData::CannotProve => self.record(&goal),
+        }
+    }
+
+    /// Explains each of `goals` that can't be proven on its own. Returns
+    /// false if there is no such goal, i.e. if the goals only fail when
+    /// proven together.
+    ///
+    /// A failing `FromEnv` goal only tells us that something is not in the
+    /// environment, which e.g. holds for the `FromEnv(T: Trait)` condition of
+    /// the implied bounds clause of every trait, so these are ignored.
+    fn explain_failing(&mut self, environment: &Environment<I>, goals: Vec<Goal<I>>) -> bool {
+        let interner = self.builder.db.interner();
+        let failing: Vec<_> = goals
+            .into_iter()
+            .filter(|goal| !is_from_env(interner, goal))
+            .filter(|goal| self.builder.solve(environment, goal).is_none())
+            .collect();
+        let explained = !failing.is_empty();
+        for goal in failing {
+            self.explain(environment, goal);
+        }
+        explained
+    }
+
+    fn explain_relate<T: Zip<I>>(
+        &mut self,
+        environment: &Environment<I>,
+        variance: Variance,
+        a: &T,
+        b: &T,
+        goal: &Goal<I>,
+    ) {
+        let interner = self.builder.db.interner();
+        let explained = match self.builder.infer.relate(
+            interner,
+            self.builder.db.unification_database(),
+            environment,
+            variance,
+            a,
+            b,
+        ) {
+            Ok(result) => {
+                let goals = result.goals.into_iter().map(|goal| goal.goal).collect();
+                self.explain_failing(environment, goals)
+            }
+            Err(NoSolution) => false,
+        };
+        if !explained {
+            self.record(goal);
+        }
+    }
+
+    fn explain_domain_goal(
+        &mut self,
+        environment: &Environment<I>,
+        domain_goal: &DomainGoal<I>,
+        goal: &Goal<I>,
+    ) {
+        let interner = self.builder.db.interner();
+        let canonical_goal = self
+            .builder
+            .infer
+            .canonicalize(
+                interner,
+                InEnvironment::new(environment, domain_goal.clone()),
+            )
+            .quantified;
+        if self.builder.stack.contains(&canonical_goal) || self.builder.stack.len() >= MAX_DEPTH {
+            debug!("not explaining {:?} any further", canonical_goal);
+            return;
+        }
+
+        let clauses = match self.builder.clauses_for_goal(environment, &canonical_goal) {
+            Ok(clauses) => clauses,
+            Err(Floundered) => {
+                self.record(goal);
+                return;
+            }
+        };
+
+        self.builder.stack.push(canonical_goal);
+        let mut explained = false;
+        for clause in clauses {
+            let snapshot = self.builder.infer.snapshot();
+
+            let ProgramClauseData(implication) = clause.data(interner);
+            let ProgramClauseImplication {
+                consequence,
+                conditions,
+                ..
+            } = self
+                .builder
+                .infer
+                .instantiate_binders_existentially(interner, implication.clone());
+
+            if let Ok(result) = self.builder.infer.relate(
+                interner,
+                self.builder.db.unification_database(),
+                environment,
+                Variance::Invariant,
+                domain_goal,
+                &consequence,
+            ) {
+                debug!("explaining failure of clause {:?}", clause);
+                let subgoals = conditions
+                    .iter(interner)
+                    .cloned()
+                    .chain(result.goals.into_iter().map(|goal| goal.goal))
+                    .collect();
+                self.required_by.push(clause.clone());
+                explained |= self.explain_failing(environment, subgoals);
+                self.required_by.pop();
+            }
+
+            self.builder.infer.rollback_to(snapshot);
+        }
+        self.builder.stack.pop();
+
+        if !explained {
+            self.record(goal);
+        }
+    }
+
+    fn record(&mut self, goal: &Goal<I>) {
+        let interner = self.builder.db.interner();
+        let goal = self
+            .builder
+            .infer
+            .canonicalize(interner, goal.clone())
+            .quantified
+            .value;
+        let cause = UnprovableGoal {
+            goal,
+            required_by: self.required_by.iter().rev().cloned().collect(),
+        };
+        if !self.causes.contains(&cause) {
+            self.causes.push(cause);
+        }
+    }
+}
+
+/// Checks if `goal` is a `FromEnv` goal, possibly wrapped in quantifiers.
+fn is_from_env<I: Interner>(interner: &I, goal: &Goal<I>) -> bool {
+    match goal.data(interner) {
+        GoalData::Quantified(_, subgoal) => is_from_env(interner, subgoal.skip_binders()),
+        GoalData::DomainGoal(DomainGoal::FromEnv(_)) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,396 @@
+//! Reconstructs proof trees for solved goals; see
+//! [`Solver::solve_with_proof_tree`].
+//!
+//! Neither solver keeps track of the clauses it used, so the tree is rebuilt
+//! after the fact: we instantiate the goal in an inference table, and for
+//! every domain goal we look for a program clause whose conditions the solver
+//! can prove. Once a clause is chosen, its conditions are expanded in turn.
+
+use crate::clauses::program_clauses_for_goal_in_env;
+use crate::infer::ucanonicalize::UniverseMapExt;
+use crate::infer::{InferenceTable, ParameterEnaVariableExt};
+use crate::solve::{Solution, Solver};
+use crate::RustIrDatabase;
+use chalk_ir::interner::Interner;
+use chalk_ir::zip::Zip;
+use chalk_ir::*;
+use std::fmt;
+use tracing::debug;
+
+/// A proof of a goal: the goal itself, how it was proven and the proofs of
+/// the subgoals that had to hold for it.
+///
+/// Inference variables that are still unresolved once the whole tree is
+/// built are replaced by bound variables, which are numbered consistently
+/// across all the nodes of the tree. Placeholders introduced by `forall`
+/// goals appear as-is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofTree<I: Interner> {
+    /// The goal proven at this node.
+    pub goal: Goal<I>,
+
+    /// The program clause used to prove `goal`: an impl, a where clause from
+    /// the environment or a built-in clause. This is `None` for goals that
+    /// are not domain goals, like `forall` goals or unification. It is also
+    /// `None` for domain goals that are not expanded, because they are
+    /// already being proven further up the tree (which happens for
+    /// coinductive goals) or because no single clause could be found to
+    /// prove them.
+    pub clause: Option<ProgramClause<I>>,
+
+    /// The proofs of the subgoals of this node: the conditions of `clause`,
+    /// or the parts `goal` is made of.
+    pub subgoals: Vec<ProofTree<I>>,
+}
+
+impl<I: Interner> ProofTree<I> {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(f, "{:indent$}{:?}", "", self.goal, indent = indent)?;
+        if let Some(clause) = &self.clause {
+            write!(f, " by {:?}", clause)?;
+        }
+        writeln!(f)?;
+        for subgoal in &self.subgoals {
+            subgoal.fmt_indented(f, indent + 4)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the goals of all the nodes in the tree, in pre-order.
+    fn goals(&self) -> Vec<Goal<I>> {
+        let mut goals = vec![self.goal.clone()];
+        for subgoal in &self.subgoals {
+            goals.extend(subgoal.goals());
+        }
+        goals
+    }
+
+    /// Replaces the goals of all the nodes in the tree, in pre-order.
+    fn set_goals(&mut self, goals: &mut impl Iterator<Item = Goal<I>>) {
+        self.goal = goals.next().unwrap();
+        for subgoal in &mut self.subgoals {
+            subgoal.set_goals(goals);
+        }
+    }
+}
+
+/// Prints every goal on its own line, followed by the clause used to prove
+/// it, with the subgoals indented below it.
+impl<I: Interner> fmt::Display for ProofTree<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+pub(super) fn solve_with_proof_tree<I: Interner, S: Solver<I> + ?Sized>(
+    solver: &mut S,
+    db: &dyn RustIrDatabase<I>,
+    goal: &UCanonical<InEnvironment<Goal<I>>>,
+) -> Option<(Solution<I>, ProofTree<I>)> {
+    let solution = solver.solve(db, goal)?;
+    let interner = db.interner();
+
+    let (infer, subst, InEnvironment { environment, goal }) =
+        InferenceTable::from_canonical(interner, goal.universes, goal.canonical.clone());
+    let mut builder = ProofTreeBuilder::new(db, solver, infer);
+
+    // Start from the values the solution picked for the variables of the goal
+    if let Some(solution_subst) = solution.definite_subst(interner) {
+        let free_vars = subst.iter(interner).cloned().collect();
+        if let Err(NoSolution) = builder.apply_solution(free_vars, solution_subst) {
+            debug!("proof tree: could not apply root solution {:?}", solution);
+        }
+    }
+
+    let mut tree = builder.prove(&environment, goal);
+
+    let goals = Goals::from_iter(interner, tree.goals());
+    let goals = builder.infer.canonicalize(interner, goals).quantified.value;
+    tree.set_goals(&mut goals.iter(interner).cloned());
+
+    Some((solution, tree))
+}
+
+pub(super) struct ProofTreeBuilder<'s, I: Interner, S: Solver<I> + ?Sized> {
+    pub(super) db: &'s dyn RustIrDatabase<I>,
+    solver: &'s mut S,
+    pub(super) infer: InferenceTable<I>,
+
+    /// The domain goals currently being expanded, used to detect cycles.
+    pub(super) stack: Vec<Canonical<InEnvironment<DomainGoal<I>>>>,
+}
+
+impl<'s, I: Interner, S: Solver<I> + ?Sized> ProofTreeBuilder<'s, I, S> {
+    pub(super) fn new(
+        db: &'s dyn RustIrDatabase<I>,
+        solver: &'s mut S,
+        infer: InferenceTable<I>,
+    ) -> Self {
+        ProofTreeBuilder {
+            db,
+            solver,
+            infer,
+            stack: vec![],
+        }
+    }
+
+    fn prove(&mut self, environment: &Environment<I>, goal: Goal<I>) -> ProofTree<I> {
+        let interner = self.db.interner();
+        let (clause, subgoals) = match goal.data(interner) {
+            // `forall<> { G }` and `exists<> { G }` are just `G`
+            GoalData::Quantified(_, subgoal) if subgoal.binders.is_empty(interner) => {
+                let subgoal = subgoal
+                    .clone()
+                    .substitute(interner, &Substitution::empty(interner));
+                return self.prove(environment, subgoal);
+            }
+            GoalData::Quantified(QuantifierKind::ForAll, subgoal) => {
+                let subgoal = self
+                    .infer
+                    .instantiate_binders_universally(interner, subgoal.clone());
+                (None, vec![self.prove(environment, subgoal)])
+            }
+            GoalData::Quantified(QuantifierKind::Exists, subgoal) => {
+                let subgoal = self
+                    .infer
+                    .instantiate_binders_existentially(interner, subgoal.clone());
+                // Pick values for the new variables that make the subgoal hold
+                if let Some((solution, free_vars, universes)) = self.solve(environment, &subgoal) {
+                    if let Some(subst) = solution.definite_subst(interner) {
+                        let subst = universes.map_from_canonical(interner, &subst);
+                        // If the values don't fit, the variables stay
+                        // unconstrained and the subgoal is expanded as is.
+                        if let Err(NoSolution) = self.apply_solution(free_vars, subst) {
+                            debug!("proof tree: could not apply solution to {:?}", subgoal);
+                        }
+                    }
+                }
+                (None, vec![self.prove(environment, subgoal)])
+            }
+            GoalData::Implies(clauses, subgoal) => {
+                let environment =
+                    environment.add_clauses(interner, clauses.iter(interner).cloned());
+                (None, vec![self.prove(&environment, subgoal.clone())])
+            }
+            GoalData::All(goals) => (
+                None,
+                goals
+                    .iter(interner)
+                    .map(|goal| self.prove(environment, goal.clone()))
+                    .collect(),
+            ),
+            GoalData::EqGoal(EqGoal { a, b }) => {
+                (None, self.relate(environment, Variance::Invariant, a, b))
+            }
+            GoalData::SubtypeGoal(SubtypeGoal { a, b }) => {
+                (None, self.relate(environment, Variance::Covariant, a, b))
+            }
+            GoalData::DomainGoal(domain_goal) => self.prove_domain_goal(environment, domain_goal),
+            GoalData::Not(_) | GoalData::CannotProve => (None, vec![]),
+        };
+        ProofTree {
+            goal,
+            clause,
+            subgoals,
+        }
+    }
+
+    /// Unifies `a` and `b`, returning the proofs of the goals that
+    /// unification produced (e.g. for normalizing associated types).
+    fn relate<T: Zip<I>>(
+        &mut self,
+        environment: &Environment<I>,
+        variance: Variance,
+        a: &T,
+        b: &T,
+    ) -> Vec<ProofTree<I>> {
+        let interner = self.db.interner();
+        match self.infer.relate(
+            interner,
+            self.db.unification_database(),
+            environment,
+            variance,
+            a,
+            b,
+        ) {
+            Ok(result) => result
+                .goals
+                .into_iter()
+                .map(|goal| self.prove(&goal.environment, goal.goal))
+                .collect(),
+            Err(NoSolution) => vec![],
+        }
+    }
+
+    fn prove_domain_goal(
+        &mut self,
+        environment: &Environment<I>,
+        domain_goal: &DomainGoal<I>,
+    ) -> (Option<ProgramClause<I>>, Vec<ProofTree<I>>) {
+        let interner = self.db.interner();
+        let canonical_goal = self
+            .infer
+            .canonicalize(
+                interner,
+                InEnvironment::new(environment, domain_goal.clone()),
+            )
+            .quantified;
+        if self.stack.contains(&canonical_goal) {
+            debug!("cycle in proof tree: {:?}", canonical_goal);
+            return (None, vec![]);
+        }
+
+        let clauses = match self.clauses_for_goal(environment, &canonical_goal) {
+            Ok(clauses) => clauses,
+            Err(Floundered) => return (None, vec![]),
+        };
+
+        self.stack.push(canonical_goal);
+        // Prefer a clause whose conditions certainly hold, but settle for one
+        // with ambiguous conditions if that's all there is.
+        let mut result = (None, vec![]);
+        'search: for &unique_only in &[true, false] {
+            for clause in &clauses {
+                if let Some(subgoals) =
+                    self.prove_from_clause(environment, domain_goal, clause, unique_only)
+                {
+                    result = (Some(clause.clone()), subgoals);
+                    break 'search;
+                }
+            }
+        }
+        self.stack.pop();
+        result
+    }
+
+    /// Collects the clauses that could be used to prove `canonical_goal`,
+    /// whose environment is instantiated as `environment`. This shares
+    /// `program_clauses_for_goal_in_env` with the recursive solver.
+    pub(super) fn clauses_for_goal(
+        &self,
+        environment: &Environment<I>,
+        canonical_goal: &Canonical<InEnvironment<DomainGoal<I>>>,
+    ) -> Result<Vec<ProgramClause<I>>, Floundered> {
+        let interner = self.db.interner();
+        let u_canonical_goal = InferenceTable::u_canonicalize(interner, canonical_goal).quantified;
+        program_clauses_for_goal_in_env(self.db, &u_canonical_goal, environment)
+    }
+
+    /// Tries to prove `domain_goal` with `clause`. If the conditions of the
+    /// clause hold (certainly, if `unique_only` is set), applies what was
+    /// learned about the variables and returns the proofs of the conditions.
+    fn prove_from_clause(
+        &mut self,
+        environment: &Environment<I>,
+        domain_goal: &DomainGoal<I>,
+        clause: &ProgramClause<I>,
+        unique_only: bool,
+    ) -> Option<Vec<ProofTree<I>>> {
+        let interner = self.db.interner();
+        let snapshot = self.infer.snapshot();
+
+        let ProgramClauseData(implication) = clause.data(interner);
+        let ProgramClauseImplication {
+            consequence,
+            conditions,
+            ..
+        } = self
+            .infer
+            .instantiate_binders_existentially(interner, implication.clone());
+
+        let relation_goals = match self.infer.relate(
+            interner,
+            self.db.unification_database(),
+            environment,
+            Variance::Invariant,
+            domain_goal,
+            &consequence,
+        ) {
+            Ok(result) => result.goals,
+            Err(NoSolution) => {
+                self.infer.rollback_to(snapshot);
+                return None;
+            }
+        };
+
+        let subgoals: Vec<_> = conditions
+            .iter(interner)
+            .cloned()
+            .chain(relation_goals.into_iter().map(|goal| goal.goal))
+            .collect();
+        let all_subgoals = Goal::all(interner, subgoals.iter().cloned());
+
+        match self.solve(environment, &all_subgoals) {
+            Some((solution, free_vars, universes)) if solution.is_unique() || !unique_only => {
+                if let Some(subst) = solution.definite_subst(interner) {
+                    let subst = universes.map_from_canonical(interner, &subst);
+                    if let Err(NoSolution) = self.apply_solution(free_vars, subst) {
+                        self.infer.rollback_to(snapshot);
+                        return None;
+                    }
+                }
+                self.infer.commit(snapshot);
+                Some(
+                    subgoals
+                        .into_iter()
+                        .map(|subgoal| self.prove(environment, subgoal))
+                        .collect(),
+                )
+            }
+            _ => {
+                self.infer.rollback_to(snapshot);
+                None
+            }
+        }
+    }
+
+    /// Solves `goal` with the solver, returning the solution along with what
+    /// is needed to map it back into our inference table.
+    pub(super) fn solve(
+        &mut self,
+        environment: &Environment<I>,
+        goal: &Goal<I>,
+    ) -> Option<(Solution<I>, Vec<GenericArg<I>>, UniverseMap)> {
+        let interner = self.db.interner();
+        let canonicalized = self
+            .infer
+            .canonicalize(interner, InEnvironment::new(environment, goal.clone()));
+        let free_vars = canonicalized
+            .free_vars
+            .into_iter()
+            .map(|free_var| free_var.to_generic_arg(interner))
+            .collect();
+        let u_canonicalized = InferenceTable::u_canonicalize(interner, &canonicalized.quantified);
+
+        let solution = self.solver.solve(self.db, &u_canonicalized.quantified)?;
+        Some((solution, free_vars, u_canonicalized.universes))
+    }
+
+    /// Unifies the variables `free_vars` of a canonicalized goal with the
+    /// values its solution gave them. The solution must already be mapped
+    /// back to the universes of our inference table. If the values don't
+    /// unify, the inference table is left untouched.
+    fn apply_solution(
+        &mut self,
+        free_vars: Vec<GenericArg<I>>,
+        subst: Canonical<ConstrainedSubst<I>>,
+    ) -> Fallible<()> {
+        let interner = self.db.interner();
+        let mut infer = self.infer.clone();
+        let ConstrainedSubst { subst, .. } = infer.instantiate_canonical(interner, subst);
+
+        let empty_env = &Environment::new(interner);
+        for (free_var, value) in free_vars.iter().zip(subst.iter(interner)) {
+            infer.relate(
+                interner,
+                self.db.unification_database(),
+                empty_env,
+                Variance::Invariant,
+                free_var,
+                value,
+            )?;
+        }
+        self.infer = infer;
+        Ok(())
+    }
+}
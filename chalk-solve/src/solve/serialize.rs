@@ -0,0 +1,239 @@
+//! Serialization of solutions with `serde`, for tools that want to consume
+//! them without parsing their `Display` output.
+//!
+//! Serializing types and lifetimes requires access to the interner, so the
+//! values are wrapped in a [`Serializable`] along with it. Types, lifetimes
+//! and clauses are serialized as strings using their `Debug`
+//! representation, so that they use the names from the program. A unique
+//! solution `for<?U0> { substitution [?0 := Vec<^0.0>], lifetime
+//! constraints [] }` is serialized like this (shown as JSON):
+//!
+//! ```json
+//! {
+//!   "result": "unique",
+//!   "variables": [{ "name": "^0.0", "kind": "type", "universe": 0 }],
+//!   "bindings": { "?0": "Vec<^0.0>" },
+//!   "constraints": []
+//! }
+//! ```
+//!
+//! Ambiguous solutions have `"result": "ambiguous"` and a `"guidance"` of
+//! `"definite"`, `"suggested"` (both with variables and bindings, but
+//! without constraints) or `"unknown"`. Constraints are serialized as e.g.
+//! `{ "kind": "lifetime_outlives", "a": "'!1_0", "b": "'!1_1",
+//! "environment": [] }` or `{ "kind": "type_outlives", "type": "T",
+//! "lifetime": "'a", "environment": [] }`.
+
+use crate::solve::{Guidance, Solution, SubstitutionResult};
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+/// A value along with the interner needed to serialize it.
+pub struct Serializable<'a, T: ?Sized, I: Interner> {
+    value: &'a T,
+    interner: &'a I,
+}
+
+impl<'a, T: ?Sized, I: Interner> Serializable<'a, T, I> {
+    pub fn new(value: &'a T, interner: &'a I) -> Self {
+        Serializable { value, interner }
+    }
+
+    fn wrap<U: ?Sized>(&self, value: &'a U) -> Serializable<'a, U, I> {
+        Serializable::new(value, self.interner)
+    }
+
+    /// Adds the `variables` and `bindings` of a canonical substitution.
+    fn subst_entries<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        binders: &'a CanonicalVarKinds<I>,
+        subst: &'a Substitution<I>,
+    ) -> Result<(), M::Error> {
+        map.serialize_entry("variables", &self.wrap(binders))?;
+        map.serialize_entry("bindings", &self.wrap(subst))
+    }
+
+    /// Adds the `guidance` of an ambiguous solution, along with its
+    /// substitution if there is one.
+    fn guidance_entries<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        guidance: &'a Guidance<I>,
+    ) -> Result<(), M::Error> {
+        match guidance {
+            Guidance::Definite(subst) => {
+                map.serialize_entry("guidance", "definite")?;
+                self.subst_entries(map, &subst.binders, &subst.value)
+            }
+            Guidance::Suggested(subst) => {
+                map.serialize_entry("guidance", "suggested")?;
+                self.subst_entries(map, &subst.binders, &subst.value)
+            }
+            Guidance::Unknown => map.serialize_entry("guidance", "unknown"),
+        }
+    }
+
+    /// Adds the `variables`, `bindings` and `constraints` of a canonical
+    /// constrained substitution.
+    fn constrained_subst_entries<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        constrained: &'a Canonical<ConstrainedSubst<I>>,
+    ) -> Result<(), M::Error> {
+        self.subst_entries(map, &constrained.binders, &constrained.value.subst)?;
+        map.serialize_entry("constraints", &self.wrap(&constrained.value.constraints))
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, Solution<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self.value {
+            Solution::Unique(constrained) => {
+                map.serialize_entry("result", "unique")?;
+                self.constrained_subst_entries(&mut map, constrained)?;
+            }
+            Solution::Ambig(guidance) => {
+                map.serialize_entry("result", "ambiguous")?;
+                self.guidance_entries(&mut map, guidance)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, Guidance<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.guidance_entries(&mut map, self.value)?;
+        map.end()
+    }
+}
+
+/// Serializes an answer of `Solver::solve_multiple`, with a `result` of
+/// `"definite"`, `"ambiguous"` or `"floundered"`.
+impl<I: Interner> Serialize
+    for Serializable<'_, SubstitutionResult<Canonical<ConstrainedSubst<I>>>, I>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self.value {
+            SubstitutionResult::Definite(constrained) => {
+                map.serialize_entry("result", "definite")?;
+                self.constrained_subst_entries(&mut map, constrained)?;
+            }
+            SubstitutionResult::Ambiguous(constrained) => {
+                map.serialize_entry("result", "ambiguous")?;
+                self.constrained_subst_entries(&mut map, constrained)?;
+            }
+            SubstitutionResult::Floundered => map.serialize_entry("result", "floundered")?,
+        }
+        map.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, Canonical<ConstrainedSubst<I>>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.constrained_subst_entries(&mut map, self.value)?;
+        map.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, ConstrainedSubst<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("bindings", &self.wrap(&self.value.subst))?;
+        map.serialize_entry("constraints", &self.wrap(&self.value.constraints))?;
+        map.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, CanonicalVarKinds<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let binders = self.value.as_slice(self.interner);
+        let mut seq = serializer.serialize_seq(Some(binders.len()))?;
+        for (index, binder) in binders.iter().enumerate() {
+            seq.serialize_element(&Variable { index, binder })?;
+        }
+        seq.end()
+    }
+}
+
+/// A variable of a canonical value, named like the bound variables that
+/// refer to it in the value.
+struct Variable<'a, I: Interner> {
+    index: usize,
+    binder: &'a CanonicalVarKind<I>,
+}
+
+impl<I: Interner> Serialize for Variable<'_, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &format!("^0.{}", self.index))?;
+        match &self.binder.kind {
+            VariableKind::Ty(TyVariableKind::General) => map.serialize_entry("kind", "type")?,
+            VariableKind::Ty(TyVariableKind::Integer) => map.serialize_entry("kind", "integer")?,
+            VariableKind::Ty(TyVariableKind::Float) => map.serialize_entry("kind", "float")?,
+            VariableKind::Lifetime => map.serialize_entry("kind", "lifetime")?,
+            VariableKind::Const(ty) => {
+                map.serialize_entry("kind", "const")?;
+                map.serialize_entry("type", &format!("{:?}", ty))?;
+            }
+        }
+        map.serialize_entry("universe", &self.binder.skip_kind().counter)?;
+        map.end()
+    }
+}
+
+/// Serializes a substitution for the existential variables of a goal as a
+/// map from `?0`, `?1`, ... to their values.
+impl<I: Interner> Serialize for Serializable<'_, Substitution<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let args = self.value.as_slice(self.interner);
+        let mut map = serializer.serialize_map(Some(args.len()))?;
+        for (index, arg) in args.iter().enumerate() {
+            map.serialize_entry(&format!("?{}", index), &format!("{:?}", arg))?;
+        }
+        map.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, Constraints<I>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let constraints = self.value.as_slice(self.interner);
+        let mut seq = serializer.serialize_seq(Some(constraints.len()))?;
+        for constraint in constraints {
+            seq.serialize_element(&self.wrap(constraint))?;
+        }
+        seq.end()
+    }
+}
+
+impl<I: Interner> Serialize for Serializable<'_, InEnvironment<Constraint<I>>, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let InEnvironment { environment, goal } = self.value;
+        let mut map = serializer.serialize_map(None)?;
+        match goal {
+            Constraint::LifetimeOutlives(a, b) => {
+                map.serialize_entry("kind", "lifetime_outlives")?;
+                map.serialize_entry("a", &format!("{:?}", a))?;
+                map.serialize_entry("b", &format!("{:?}", b))?;
+            }
+            Constraint::TypeOutlives(ty, lifetime) => {
+                map.serialize_entry("kind", "type_outlives")?;
+                map.serialize_entry("type", &format!("{:?}", ty))?;
+                map.serialize_entry("lifetime", &format!("{:?}", lifetime))?;
+            }
+        }
+        let clauses: Vec<_> = environment
+            .clauses
+            .iter(self.interner)
+            .map(|clause| format!("{:?}", clause))
+            .collect();
+        map.serialize_entry("environment", &clauses)?;
+        map.end()
+    }
+}
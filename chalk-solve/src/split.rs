@@ -57,6 +57,18 @@ pub trait Split<I: Interner>: RustIrDatabase<I> {
         }
     }
 
+    /// Given a projection `<P0 as Trait<P1..Pn>>::N` of an associated
+    /// const, returns the trait reference `P0: Trait<P1..Pn>`. Associated
+    /// consts have no parameters of their own, so all the parameters of the
+    /// projection belong to the trait.
+    fn trait_ref_from_projection_const(&self, projection: &ProjectionConst<I>) -> TraitRef<I> {
+        let associated_const_data = self.associated_const_data(projection.associated_const_id);
+        TraitRef {
+            trait_id: associated_const_data.trait_id,
+            substitution: projection.substitution.clone(),
+        }
+    }
+
     /// Given the full set of parameters (or binders) for an
     /// associated type *value* (which appears in an impl), splits
     /// them into the substitutions for the *impl* and those for the
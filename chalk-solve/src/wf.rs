@@ -1,7 +1,13 @@
 use std::{fmt, iter};
 
 use crate::{
-    ext::*, goal_builder::GoalBuilder, rust_ir::*, solve::Solver, split::Split, RustIrDatabase,
+    display::{display_goal, display_impl_header},
+    ext::*,
+    goal_builder::GoalBuilder,
+    rust_ir::*,
+    solve::Solver,
+    split::Split,
+    RustIrDatabase,
 };
 use chalk_ir::{
     cast::*,
@@ -12,35 +18,112 @@ use chalk_ir::{
 };
 use tracing::debug;
 
+/// The `failing_goal`s of these errors are closed goals: they are wrapped in
+/// the quantifiers they appear under, e.g. `forall<T> { WellFormed(Vec<T>) }`.
 #[derive(Debug)]
 pub enum WfError<I: Interner> {
-    IllFormedTypeDecl(chalk_ir::AdtId<I>),
+    /// The type declaration is not well-formed. `failing_goal` is the
+    /// requirement that could not be proven, such as `WellFormed` of a field
+    /// type or a field type being `Sized`.
+    IllFormedTypeDecl {
+        adt_id: chalk_ir::AdtId<I>,
+        failing_goal: Option<Goal<I>>,
+    },
     IllFormedOpaqueTypeDecl(chalk_ir::OpaqueTyId<I>),
-    IllFormedTraitImpl(chalk_ir::TraitId<I>),
+    /// The impl `impl_id` of the trait `trait_id` is not well-formed.
+    /// `failing_goal` is the requirement that could not be proven, such as
+    /// `WellFormed` of the trait ref or of a type in a where clause, or a
+    /// bound on an associated type value. It is `None` if the impl does not
+    /// meet the built-in rules of a well-known trait.
+    IllFormedTraitImpl {
+        trait_id: chalk_ir::TraitId<I>,
+        impl_id: ImplId<I>,
+        failing_goal: Option<Goal<I>>,
+    },
+}
+
+impl<I: Interner> WfError<I> {
+    /// Displays the error along with the offending impl and the goal that
+    /// could not be proven, using `db` for the names of the items they
+    /// refer to.
+    pub fn display<'a>(&'a self, db: &'a dyn RustIrDatabase<I>) -> WfErrorDisplay<'a, I> {
+        WfErrorDisplay { error: self, db }
+    }
 }
 
+/// Only displays which item is not well-formed; see `WfError::display` for
+/// the details.
 impl<I: Interner> fmt::Display for WfError<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            WfError::IllFormedTypeDecl(id) => write!(
+            WfError::IllFormedTypeDecl { adt_id, .. } => write!(
                 f,
                 "type declaration `{:?}` does not meet well-formedness requirements",
-                id
+                adt_id
             ),
             WfError::IllFormedOpaqueTypeDecl(id) => write!(
                 f,
                 "opaque type declaration `{:?}` does not meet well-formedness requirements",
                 id
             ),
-            WfError::IllFormedTraitImpl(id) => write!(
+            WfError::IllFormedTraitImpl { trait_id, .. } => write!(
                 f,
                 "trait impl for `{:?}` does not meet well-formedness requirements",
-                id
+                trait_id
             ),
         }
     }
 }
 
+pub struct WfErrorDisplay<'a, I: Interner> {
+    error: &'a WfError<I>,
+    db: &'a dyn RustIrDatabase<I>,
+}
+
+impl<'a, I: Interner> fmt::Display for WfErrorDisplay<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let WfErrorDisplay { error, db } = self;
+        let failing_goal = match error {
+            WfError::IllFormedTypeDecl {
+                adt_id,
+                failing_goal,
+            } => {
+                write!(
+                    f,
+                    "type declaration `{}` does not meet well-formedness requirements",
+                    db.adt_name(*adt_id)
+                )?;
+                failing_goal
+            }
+            WfError::IllFormedOpaqueTypeDecl(id) => {
+                return write!(
+                    f,
+                    "opaque type declaration `{}` does not meet well-formedness requirements",
+                    db.opaque_type_name(*id)
+                )
+            }
+            WfError::IllFormedTraitImpl {
+                trait_id,
+                impl_id,
+                failing_goal,
+            } => {
+                write!(
+                    f,
+                    "trait impl for `{}` does not meet well-formedness requirements \
+                     in `{}`",
+                    db.trait_name(*trait_id),
+                    display_impl_header(*db, *impl_id)
+                )?;
+                failing_goal
+            }
+        };
+        match failing_goal {
+            Some(goal) => write!(f, ": could not prove `{}`", display_goal(*db, goal)),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<I: Interner> std::error::Error for WfError<I> {}
 
 pub struct WfSolver<'a, I: Interner> {
@@ -231,8 +314,6 @@ where
     }
 
     pub fn verify_adt_decl(&self, adt_id: AdtId<I>) -> Result<(), WfError<I>> {
-        let interner = self.db.interner();
-
         // Given a struct like
         //
         // ```rust
@@ -298,14 +379,12 @@ where
             },
         );
 
-        let wg_goal = wg_goal.into_closed_goal(interner);
-        let mut fresh_solver = (self.solver_builder)();
-        let is_legal = fresh_solver.has_unique_solution(self.db, &wg_goal);
-
-        if !is_legal {
-            Err(WfError::IllFormedTypeDecl(adt_id))
-        } else {
-            Ok(())
+        match self.find_failing_goal(wg_goal) {
+            Some(failing_goal) => Err(WfError::IllFormedTypeDecl {
+                adt_id,
+                failing_goal,
+            }),
+            None => Ok(()),
         }
     }
 
@@ -331,15 +410,39 @@ where
 
         debug!("WF trait goal: {:?}", impl_goal);
 
-        let mut fresh_solver = (self.solver_builder)();
-        let is_legal =
-            fresh_solver.has_unique_solution(self.db, &impl_goal.into_closed_goal(interner));
+        match self.find_failing_goal(impl_goal) {
+            Some(failing_goal) => Err(WfError::IllFormedTraitImpl {
+                trait_id,
+                impl_id,
+                failing_goal,
+            }),
+            None => Ok(()),
+        }
+    }
 
-        if is_legal {
-            Ok(())
-        } else {
-            Err(WfError::IllFormedTraitImpl(trait_id))
+    /// Checks whether `goal` holds. If it does not, returns `Some` with the
+    /// first of its sub-goals that can't be proven on its own, if any, wrapped
+    /// in the quantifiers it appears under.
+    fn find_failing_goal(&self, goal: Goal<I>) -> Option<Option<Goal<I>>> {
+        let interner = self.db.interner();
+        let mut fresh_solver = (self.solver_builder)();
+        if fresh_solver.has_unique_solution(self.db, &goal.clone().into_closed_goal(interner)) {
+            return None;
         }
+
+        // The WF goals are conjunctions nested in `forall` and `if`, so
+        // we can try each leaf of the conjunctions on its own, in the same
+        // environment, to find out which one failed.
+        let failing_goal = leaf_goals(interner, &goal)
+            .into_iter()
+            .find(|(leaf, in_context)| {
+                debug!("checking WF sub-goal: {:?}", leaf);
+                let mut fresh_solver = (self.solver_builder)();
+                !fresh_solver
+                    .has_unique_solution(self.db, &in_context.clone().into_closed_goal(interner))
+            })
+            .map(|(leaf, _)| leaf);
+        Some(failing_goal)
     }
 
     pub fn verify_opaque_ty_decl(&self, opaque_ty_id: OpaqueTyId<I>) -> Result<(), WfError<I>> {
@@ -426,17 +529,55 @@ where
             | WellKnownTrait::FnMut
             | WellKnownTrait::Unsize
             | WellKnownTrait::Sized
-            | WellKnownTrait::DiscriminantKind => false,
+            | WellKnownTrait::DiscriminantKind
+            | WellKnownTrait::Generator => false,
         };
 
         if is_legal {
             Ok(())
         } else {
-            Err(WfError::IllFormedTraitImpl(impl_datum.trait_id()))
+            Err(WfError::IllFormedTraitImpl {
+                trait_id: impl_datum.trait_id(),
+                impl_id,
+                failing_goal: None,
+            })
         }
     }
 }
 
+/// Splits a goal like `forall<T> { if (FromEnv(T: Eq)) { A && B } }` into
+/// its leaves, `A` and `B`. Each leaf is returned both wrapped in the
+/// quantifiers it appears under, e.g. `forall<T> { A }`, so that its
+/// variables are bound, and wrapped in the quantifiers and hypotheses it
+/// appears under, so it can be solved on its own, e.g.
+/// `forall<T> { if (FromEnv(T: Eq)) { A } }`.
+fn leaf_goals<I: Interner>(interner: &I, goal: &Goal<I>) -> Vec<(Goal<I>, Goal<I>)> {
+    match goal.data(interner) {
+        GoalData::Quantified(kind, subgoal) => {
+            let (binders, subgoal) = subgoal.as_ref().into();
+            let quantify = |goal| {
+                GoalData::Quantified(*kind, Binders::new(binders.clone(), goal)).intern(interner)
+            };
+            leaf_goals(interner, subgoal)
+                .into_iter()
+                .map(|(leaf, in_context)| (quantify(leaf), quantify(in_context)))
+                .collect()
+        }
+        GoalData::Implies(clauses, subgoal) => leaf_goals(interner, subgoal)
+            .into_iter()
+            .map(|(leaf, in_context)| {
+                let in_context = GoalData::Implies(clauses.clone(), in_context).intern(interner);
+                (leaf, in_context)
+            })
+            .collect(),
+        GoalData::All(goals) => goals
+            .iter(interner)
+            .flat_map(|goal| leaf_goals(interner, goal))
+            .collect(),
+        _ => vec![(goal.clone(), goal.clone())],
+    }
+}
+
 fn impl_header_wf_goal<I: Interner>(
     db: &dyn RustIrDatabase<I>,
     impl_id: ImplId<I>,
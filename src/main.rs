@@ -1,18 +1,24 @@
 #[macro_use]
 extern crate serde_derive;
 
+mod script;
+
 use std::fs::File;
 use std::io::Read;
+use std::path::PathBuf;
 use std::process::exit;
+use std::sync::Arc;
 
 use chalk_integration::db::ChalkDatabase;
 use chalk_integration::interner::ChalkIr;
-use chalk_integration::lowering::*;
+use chalk_integration::program_sources::STD_PRELUDE;
 use chalk_integration::query::LoweringDatabase;
 use chalk_integration::SolverChoice;
+use chalk_ir::cast::Cast;
+use chalk_ir::Goal;
 use chalk_solve::ext::*;
-use chalk_solve::logging;
-use chalk_solve::RustIrDatabase;
+use chalk_solve::{display, logging};
+use chalk_solve::{RustIrDatabase, Serializable};
 use docopt::Docopt;
 use rustyline::error::ReadlineError;
 
@@ -29,8 +35,21 @@ Options:
   --help              Show this screen.
   --program=PATH      Specifies the path to the `.chalk` file containing traits/impls.
   --goal=GOAL         Specifies a goal to evaluate (may be given more than once).
-  --overflow-depth=N  Specifies the overflow depth [default: 10].
+  --clauses=GOAL      Specifies a goal to list the program clauses for (may be given more than once).
+  --test=PATH         Runs the goals of a `.chalk` test script and checks their results
+                      (may be given more than once).
+  --solver=SOLVER     Specifies the solver to use, `slg` or `recursive` [default: slg].
+  --overflow-depth=N  Specifies the overflow depth; the default is 10 for the SLG solver,
+                      where it is the maximum size of goals and answers, and 100 for the
+                      recursive solver.
+  --max-size=N        Specifies the maximum size of goals and answers, overriding
+                      `--overflow-depth` for the SLG solver; the default is 10 for the
+                      SLG solver and 30 for the recursive solver.
+  --caching=CACHING   Turns caching in the recursive solver `on` or `off` [default: on].
   --multiple          Output multiple answers instead of ambiguous solution.
+  --format=FORMAT     Prints solutions as `text` or as `json` objects [default: text].
+  --prelude           Adds declarations of the well-known traits (`Sized`, `Clone`, ...)
+                      to the program.
 ";
 
 /// This struct represents the various command line options available.
@@ -38,8 +57,44 @@ Options:
 struct Args {
     flag_program: Option<String>,
     flag_goal: Vec<String>,
-    flag_overflow_depth: usize,
+    flag_clauses: Vec<String>,
+    flag_test: Vec<String>,
+    flag_solver: SolverKind,
+    flag_overflow_depth: Option<usize>,
+    flag_max_size: Option<usize>,
+    flag_caching: Caching,
     flag_multiple: bool,
+    flag_format: Format,
+    flag_prelude: bool,
+}
+
+/// The solvers that can be selected with `--solver` or the `solver` command.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+enum SolverKind {
+    Slg,
+    Recursive,
+}
+
+impl SolverKind {
+    fn from_name(name: &str) -> Option<SolverKind> {
+        match name {
+            "slg" => Some(SolverKind::Slg),
+            "recursive" => Some(SolverKind::Recursive),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+enum Caching {
+    On,
+    Off,
 }
 
 /// A loaded and parsed program.
@@ -50,27 +105,94 @@ struct LoadedProgram {
 
 impl LoadedProgram {
     /// Creates a new Program struct, given a `.chalk` file as a String and
-    /// a [`SolverChoice`].
-    ///
-    /// [`SolverChoice`]: struct.solve.SolverChoice.html
-    fn new(text: String, solver_choice: SolverChoice) -> Result<LoadedProgram> {
-        let db = ChalkDatabase::with(&text, solver_choice);
+    /// the path it was read from, if any, against which its includes are
+    /// resolved.
+    fn new(text: String, path: Option<&str>, args: &Args) -> Result<LoadedProgram> {
+        let mut db = ChalkDatabase::with(&text, args.solver_choice());
+        db.set_program_path(path.map(|path| Arc::new(PathBuf::from(path))));
+        db.set_prelude(args.prelude());
         Ok(LoadedProgram { text, db })
     }
 
+    /// Adds an item to the program, replacing the item with the same name
+    /// if there is one.
+    fn define(&mut self, item_text: &str) -> Result<()> {
+        let items = chalk_parse::parse_program(item_text)?.items;
+        let name = match items.as_slice() {
+            [item] => item.name().map(|name| name.str.clone()),
+            _ => return Err("expected a single item".into()),
+        };
+        let span = match name {
+            Some(name) => self.item_span(&name)?,
+            None => None,
+        };
+        let text = match span {
+            Some(span) => format!(
+                "{}{}{}",
+                &self.text[..span.lo],
+                item_text,
+                &self.text[span.hi..]
+            ),
+            None if self.text.is_empty() || self.text.ends_with('\n') => {
+                format!("{}{}\n", self.text, item_text)
+            }
+            None => format!("{}\n{}\n", self.text, item_text),
+        };
+        self.update_text(text)
+    }
+
+    /// Removes the item with the given name, along with the line break
+    /// that ends it, from the program.
+    fn undef(&mut self, name: &str) -> Result<()> {
+        let span = self
+            .item_span(name)?
+            .ok_or_else(|| format!("no item named `{}`", name))?;
+        let rest = &self.text[span.hi..];
+        let rest = rest.strip_prefix('\n').unwrap_or(rest);
+        let text = format!("{}{}", &self.text[..span.lo], rest);
+        self.update_text(text)
+    }
+
+    /// Finds the text of the item with the given name.
+    fn item_span(&self, name: &str) -> Result<Option<chalk_parse::ast::Span>> {
+        let items = chalk_parse::parse_program(&self.text)?.items;
+        Ok(items
+            .iter()
+            .find(|item| matches!(item.name(), Some(item_name) if item_name.str == *name))
+            .map(|item| item.span()))
+    }
+
+    /// Replaces the program text and checks the new program. If the
+    /// check fails, the old text is restored; as the old program was
+    /// fine, the errors are caused by the change. Since the database is
+    /// updated rather than recreated, the solver's cached results are
+    /// kept if the lowered program doesn't change.
+    fn update_text(&mut self, text: String) -> Result<()> {
+        self.db.set_program_text(Arc::new(text.clone()));
+        if let Err(err) = self.db.checked_program() {
+            self.db.set_program_text(Arc::new(self.text.clone()));
+            return Err(err.into());
+        }
+        self.text = text;
+        Ok(())
+    }
+
     /// Parse a goal and attempt to solve it, using the specified solver.
     fn goal(
         &self,
         mut rl: Option<&mut rustyline::Editor<()>>,
         text: &str,
         multiple_answers: bool,
+        format: Format,
     ) -> Result<()> {
-        let program = self.db.checked_program()?;
-        let goal = lower_goal(&*chalk_parse::parse_goal(text)?, &*program)?;
+        let goal = self.db.parse_and_lower_goal(text)?;
         let peeled_goal = goal.into_peeled_goal(self.db.interner());
         if multiple_answers {
             if self.db.solve_multiple(&peeled_goal, &mut |v, has_next| {
-                println!("{}\n", v.as_ref().map(|v| v.display(&ChalkIr)));
+                match format {
+                    Format::Text => println!("{}\n", v.as_ref().map(|v| v.display(&ChalkIr))),
+                    Format::Json => println!("{}", to_json(&Serializable::new(&v, &ChalkIr))),
+                }
                 if has_next {
                     if let Some(ref mut rl) = rl {
                         loop {
@@ -92,9 +214,15 @@ impl LoadedProgram {
                 } else {
                     true
                 }
-            }) {
+            }) && format == Format::Text
+            {
                 println!("No more solutions");
             }
+        } else if format == Format::Json {
+            match self.db.solve(&peeled_goal) {
+                Some(v) => println!("{}", to_json(&Serializable::new(&v, &ChalkIr))),
+                None => println!("{}", serde_json::json!({ "result": "none" })),
+            }
         } else {
             match self.db.solve(&peeled_goal) {
                 Some(v) => println!("{}\n", v.display(&ChalkIr)),
@@ -103,24 +231,103 @@ impl LoadedProgram {
         }
         Ok(())
     }
+
+    /// Parse a goal and print the program clauses that the solver would try
+    /// to use to prove its first domain goal.
+    fn clauses(&self, text: &str) -> Result<()> {
+        let goal = self.db.parse_and_lower_goal(text)?;
+        let domain_goal = goal
+            .into_first_domain_goal(self.db.interner())
+            .ok_or("goal does not contain a domain goal")?;
+        let clauses = self
+            .db
+            .program_clauses_for_goal(&domain_goal)
+            .map_err(|_| "goal floundered")?;
+        let interner = self.db.interner();
+        let goal: Goal<ChalkIr> = domain_goal.canonical.value.goal.cast(interner);
+        println!(
+            "Program clauses for `{}`:",
+            display::display_goal(&self.db, &goal)
+        );
+        for clause in &clauses {
+            println!("    {}", display::display_program_clause(&self.db, clause));
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Parse a goal and, if it can't be proven, print the subgoals that
+    /// cause it to fail along with the clauses that required them.
+    fn explain(&self, text: &str) -> Result<()> {
+        let goal = self.db.parse_and_lower_goal(text)?;
+        let peeled_goal = goal.into_peeled_goal(self.db.interner());
+        let causes = self.db.explain_failure(&peeled_goal);
+        if causes.is_empty() {
+            println!("The goal can be proven.");
+        } else {
+            println!("No possible solution.");
+        }
+        for cause in &causes {
+            println!("note: {}", cause.display(&self.db));
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Parse a goal and solve it with both the SLG and the recursive solver,
+    /// printing the solutions side by side if they differ.
+    fn compare(&self, args: &Args, text: &str) -> Result<()> {
+        let goal = self.db.parse_and_lower_goal(text)?;
+        let peeled_goal = goal.into_peeled_goal(self.db.interner());
+        let solve = |solver: SolverKind| {
+            let mut solver = args.solver_choice_for(solver).into_solver();
+            match solver.solve(&self.db, &peeled_goal) {
+                Some(v) => v.display(&ChalkIr).to_string(),
+                None => "No possible solution".to_string(),
+            }
+        };
+        let slg = solve(SolverKind::Slg);
+        let recursive = solve(SolverKind::Recursive);
+        if slg == recursive {
+            println!("Both solvers agree: {}\n", slg);
+        } else {
+            println!("The solvers disagree:");
+            println!("- slg:       {}", slg);
+            println!("+ recursive: {}\n", recursive);
+        }
+        Ok(())
+    }
 }
 
 fn run() -> Result<()> {
     // Parse the command line arguments.
-    let args: &Args = &Docopt::new(USAGE)
+    let mut args: Args = Docopt::new(USAGE)
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
+    let args = &mut args;
 
     // Validate arguments.
-    if args.flag_overflow_depth == 0 {
+    if args.flag_overflow_depth == Some(0) {
         eprintln!("error: overflow depth must be at least 1");
         exit(1);
     }
 
+    // Run the test scripts, if given.
+    if !args.flag_test.is_empty() {
+        let mut passed = true;
+        for path in &args.flag_test {
+            passed &= script::run_script(args, path)?;
+        }
+        if !passed {
+            exit(1);
+        }
+        return Ok(());
+    }
+
     // Load the .chalk file, if given.
     let mut prog = None;
-    if let Some(program) = &args.flag_program {
-        match load_program(args, program) {
+    if let Some(program) = args.flag_program.clone() {
+        match load_program(args, &program) {
             Ok(p) => prog = Some(p),
             Err(err) => {
                 eprintln!("error loading program: {}", err);
@@ -129,7 +336,7 @@ fn run() -> Result<()> {
         }
     }
 
-    if args.flag_goal.is_empty() {
+    if args.flag_goal.is_empty() && args.flag_clauses.is_empty() {
         // The user specified no goal. Enter interactive mode.
         readline_loop(&mut rustyline::Editor::new(), "?- ", |rl, line| {
             if let Err(e) = process(args, line, rl, &mut prog) {
@@ -147,7 +354,13 @@ fn run() -> Result<()> {
         // and exit.
         prog.db.with_program(|_| -> Result<()> {
             for g in &args.flag_goal {
-                if let Err(e) = prog.goal(None, g, args.flag_multiple) {
+                if let Err(e) = prog.goal(None, g, args.flag_multiple, args.flag_format) {
+                    eprintln!("error: {}", e);
+                    exit(1);
+                }
+            }
+            for g in &args.flag_clauses {
+                if let Err(e) = prog.clauses(g) {
                     eprintln!("error: {}", e);
                     exit(1);
                 }
@@ -194,11 +407,13 @@ where
 // TODO: Could we pass in an Options struct or something? The Args struct
 // still has Strings where it should have Enums... (e.g. solver_choice)
 fn process(
-    args: &Args,
+    args: &mut Args,
     command: &str,
     rl: &mut rustyline::Editor<()>,
     prog: &mut Option<LoadedProgram>,
 ) -> Result<()> {
+    // Lines read from a pipe rather than a terminal keep their newline.
+    let command = command.trim();
     if command.is_empty() {
         // Ignore empty commands.
     } else if command == "help" || command == "h" {
@@ -207,7 +422,7 @@ fn process(
         help()
     } else if command == "program" {
         // Load a .chalk file via stdin, until EOF is found.
-        let chalk_prog = LoadedProgram::new(read_program(rl)?, args.solver_choice())?;
+        let chalk_prog = LoadedProgram::new(read_program(rl)?, None, args)?;
         // Let's do a sanity check before going forward.
         let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
@@ -215,9 +430,30 @@ fn process(
         // Load a .chalk file.
         let filename = &command["load ".len()..];
         let chalk_prog = load_program(args, filename)?;
-        // Let's do a sanity check before going forward.
-        let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
+    } else if let Some(item) = command.strip_prefix("define ") {
+        // Add or replace a single item, starting a new program if needed.
+        if prog.is_none() {
+            *prog = Some(LoadedProgram::new(String::new(), None, args)?);
+        }
+        let prog = prog.as_mut().unwrap();
+        prog.define(item)?;
+    } else if let Some(name) = command.strip_prefix("undef ") {
+        let prog = prog
+            .as_mut()
+            .ok_or("no program currently loaded; type 'help' to see available commands")?;
+        prog.undef(name.trim())?;
+    } else if command == "solver" || command.starts_with("solver ") {
+        match command.split_whitespace().nth(1) {
+            Some(name) => {
+                args.flag_solver = SolverKind::from_name(name)
+                    .ok_or_else(|| format!("unknown solver `{}`", name))?;
+                if let Some(prog) = prog {
+                    prog.db.set_solver_choice(args.solver_choice());
+                }
+            }
+            None => println!("{:?}", args.solver_choice()),
+        }
     } else if command.starts_with("debug ") {
         match command.split_whitespace().nth(1) {
             Some(level) => std::env::set_var("CHALK_DEBUG", level),
@@ -240,10 +476,22 @@ fn process(
                 // TODO: Write a line of documentation here.
                 "lowered" => println!("{:#?}", prog.db.environment()),
 
+                _ if command.starts_with("clauses ") => {
+                    prog.clauses(&command["clauses ".len()..])?
+                }
+
+                _ if command.starts_with("compare ") => {
+                    prog.compare(args, &command["compare ".len()..])?
+                }
+
+                _ if command.starts_with("explain ") => {
+                    prog.explain(&command["explain ".len()..])?
+                }
+
                 // Assume this is a goal.
                 // TODO: Print out "type 'help' to see available commands" if it
                 // fails to parse?
-                _ => prog.goal(Some(rl), command, args.flag_multiple)?,
+                _ => prog.goal(Some(rl), command, args.flag_multiple, args.flag_format)?,
             }
             Ok(())
         })?
@@ -258,7 +506,11 @@ fn process(
 fn load_program(args: &Args, filename: &str) -> Result<LoadedProgram> {
     let mut text = String::new();
     File::open(filename)?.read_to_string(&mut text)?;
-    Ok(LoadedProgram::new(text, args.solver_choice())?)
+    let chalk_prog = LoadedProgram::new(text, Some(filename), args)?;
+    // Let's do a sanity check before going forward; this also reads the
+    // included files.
+    let _ = chalk_prog.db.checked_program()?;
+    Ok(chalk_prog)
 }
 
 /// Print out help for commands in interpreter mode.
@@ -269,11 +521,23 @@ fn help() {
     println!("  program       provide a program via stdin");
     println!("  load <file>   load program from <file>");
     println!("  print         print the current program");
+    println!("  define <item> add <item> to the program, replacing the item with the same name");
+    println!("  undef <name>  remove the item named <name> from the program");
     println!("  lowered       print the lowered program");
     println!("  <goal>        attempt to solve <goal>");
+    println!("  clauses <goal> list the program clauses for the first domain goal in <goal>");
+    println!("  compare <goal> solve <goal> with both solvers and compare the solutions");
+    println!("  explain <goal> explain why <goal> can't be proven");
+    println!("  solver        print the current solver");
+    println!("  solver <name> switch to the `slg` or `recursive` solver");
     println!("  debug <level> set debug level to <level>");
 }
 
+/// Serializes a solution or an answer to JSON.
+fn to_json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string(value).expect("solutions can always be serialized")
+}
+
 /// Read a program from the command-line. Stop reading when EOF is read. If
 /// an error occurs while reading, a `Err` is returned.
 fn read_program(rl: &mut rustyline::Editor<()>) -> Result<String> {
@@ -287,10 +551,33 @@ fn read_program(rl: &mut rustyline::Editor<()>) -> Result<String> {
 }
 
 impl Args {
+    /// The solver selected with `--solver` or the `solver` command.
     fn solver_choice(&self) -> SolverChoice {
-        SolverChoice::SLG {
-            max_size: self.flag_overflow_depth,
-            expected_answers: None,
+        self.solver_choice_for(self.flag_solver)
+    }
+
+    fn solver_choice_for(&self, solver: SolverKind) -> SolverChoice {
+        match solver {
+            // For the SLG solver, the overflow depth has always been the
+            // maximum size of goals and answers.
+            SolverKind::Slg => {
+                let max_size = self.flag_max_size.or(self.flag_overflow_depth);
+                SolverChoice::slg(max_size.unwrap_or(10), None)
+            }
+            SolverKind::Recursive => SolverChoice::Recursive {
+                overflow_depth: self.flag_overflow_depth.unwrap_or(100),
+                caching_enabled: self.flag_caching == Caching::On,
+                max_size: self.flag_max_size.unwrap_or(30),
+            },
+        }
+    }
+
+    /// The prelude enabled with `--prelude`, if any.
+    fn prelude(&self) -> Option<Arc<String>> {
+        if self.flag_prelude {
+            Some(Arc::new(STD_PRELUDE.to_string()))
+        } else {
+            None
         }
     }
 }
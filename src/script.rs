@@ -0,0 +1,413 @@
+//! Test scripts: `.chalk` files that interleave program items with goals
+//! and the results they are expected to have, e.g.
+//!
+//! ```notrust
+//! trait Foo {}
+//! struct A {}
+//! impl Foo for A {}
+//!
+//! goal { A: Foo } yields { "Unique" }
+//!
+//! struct B {}
+//!
+//! goal { B: Foo } yields[recursive] { "No possible solution" }
+//! goal { exists<T> { T: Foo } } yields_all { "substitution [?0 := A]" }
+//! ```
+//!
+//! Each goal is solved in the program made up of the items above it. The
+//! expectations mirror those of the `test!` macro in `tests/test`:
+//!
+//! - `yields { "Y" }` checks that both solvers yield `Y`;
+//! - `yields_all { "Y1", ..., "YN" }` checks that the SLG solver yields
+//!   exactly these answers, in this order;
+//! - `yields_first { "Y1", ..., "YN" }` checks that the SLG solver yields
+//!   at least these answers, in this order.
+//!
+//! Each of them can be restricted to (or, for `yields_all` and
+//! `yields_first`, moved to) one solver by writing e.g. `yields[recursive]`,
+//! and a goal can have several of them. Like in the tests, results are
+//! compared ignoring whitespace, and an expectation only has to be a
+//! prefix of the result. Goals must start on a new line.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::query::LoweringDatabase;
+use chalk_ir::{ConstrainedSubst, Constraints};
+use chalk_solve::ext::*;
+use chalk_solve::{RustIrDatabase, Solution, SubstitutionResult};
+
+use crate::{Args, Result, SolverKind};
+
+/// A goal in a test script along with its expected results.
+struct ScriptGoal {
+    /// The line of the script the goal starts on.
+    line: usize,
+
+    /// The program items that precede the goal.
+    program: String,
+
+    goal: String,
+
+    expectations: Vec<(SolverKind, Expected)>,
+}
+
+enum Expected {
+    Aggregated(String),
+    All(Vec<String>),
+    First(Vec<String>),
+}
+
+/// Runs the goals of the test script at `path`, printing a report for the
+/// goals whose results don't match the expected ones. Returns false if
+/// there were any such goals.
+pub(crate) fn run_script(args: &Args, path: &str) -> Result<bool> {
+    let mut text = String::new();
+    File::open(path)?.read_to_string(&mut text)?;
+    let goals = parse_script(&text).map_err(|e| format!("{}:{}", path, e))?;
+
+    let mut db = ChalkDatabase::with("", args.solver_choice());
+    db.set_program_path(Some(Arc::new(PathBuf::from(path))));
+    db.set_prelude(args.prelude());
+    let mut passed = 0;
+    let mut failed = 0;
+    for goal in goals {
+        if *db.program_text() != goal.program {
+            db.set_program_text(Arc::new(goal.program.clone()));
+        }
+        let program = db
+            .checked_program()
+            .map_err(|e| format!("{}:{}: error in program: {}", path, goal.line, e))?;
+
+        for (solver, expected) in &goal.expectations {
+            let solver_choice = args.solver_choice_for(*solver);
+            db.set_solver_choice(solver_choice);
+            let peeled_goal = db
+                .parse_and_lower_goal(&goal.goal)
+                .map_err(|e| format!("{}:{}: {}", path, goal.line, e))?
+                .into_peeled_goal(db.interner());
+            let mismatch = chalk_integration::tls::set_current_program(&program, || {
+                check_goal(&db, &peeled_goal, expected)
+            });
+
+            match mismatch {
+                None => passed += 1,
+                Some((expected, actual)) => {
+                    failed += 1;
+                    println!(
+                        "{}:{}: goal `{}` failed using {:?}",
+                        path,
+                        goal.line,
+                        goal.goal.trim(),
+                        solver_choice
+                    );
+                    println!("- expected: {}", expected);
+                    println!("+ actual:   {}", actual);
+                    println!();
+                }
+            }
+        }
+    }
+
+    println!("{}: {} passed, {} failed", path, passed, failed);
+    Ok(failed == 0)
+}
+
+/// Solves `goal` with the solver of `db`, returning the first expected
+/// result that doesn't match along with the actual result, if any.
+fn check_goal(
+    db: &ChalkDatabase,
+    goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<ChalkIr>>>,
+    expected: &Expected,
+) -> Option<(String, String)> {
+    let check = |expected: &str, actual: String| {
+        if result_matches(&actual, expected) {
+            None
+        } else {
+            Some((expected.to_string(), actual))
+        }
+    };
+    let all = matches!(expected, Expected::All(_));
+    match expected {
+        Expected::Aggregated(expected) => check(expected, display_solution(db.solve(goal))),
+        Expected::All(expected) | Expected::First(expected) => {
+            // Ask for one answer more than expected, to find out whether
+            // there are unexpected ones.
+            let mut answers = vec![];
+            db.solve_multiple(goal, &mut |answer, _| {
+                answers.push(display_answer(answer));
+                answers.len() <= expected.len()
+            });
+            for (i, expected) in expected.iter().enumerate() {
+                let answer = answers.get(i).cloned();
+                let mismatch = check(expected, answer.unwrap_or_else(|| "no more answers".into()));
+                if mismatch.is_some() {
+                    return mismatch;
+                }
+            }
+            if all && answers.len() > expected.len() {
+                return Some((
+                    "no more answers".to_string(),
+                    answers[expected.len()].clone(),
+                ));
+            }
+            None
+        }
+    }
+}
+
+/// Displays a solution like the tests do, sorting the lifetime constraints
+/// since the solvers may produce them in different orders.
+fn display_solution(mut solution: Option<Solution<ChalkIr>>) -> String {
+    if let Some(Solution::Unique(solution)) = &mut solution {
+        let mut sorted = solution.value.constraints.as_slice(&ChalkIr).to_vec();
+        sorted.sort_by_key(|c| format!("{:?}", c));
+        solution.value.constraints = Constraints::from_iter(&ChalkIr, sorted);
+    }
+    match solution {
+        Some(v) => v.display(&ChalkIr).to_string(),
+        None => "No possible solution".to_string(),
+    }
+}
+
+fn display_answer(
+    answer: SubstitutionResult<chalk_ir::Canonical<ConstrainedSubst<ChalkIr>>>,
+) -> String {
+    answer.as_ref().map(|v| v.display(&ChalkIr)).to_string()
+}
+
+/// Checks if `expected` is a prefix of `actual`, ignoring whitespace.
+fn result_matches(actual: &str, expected: &str) -> bool {
+    let actual: String = actual.chars().filter(|c| !c.is_whitespace()).collect();
+    let expected: String = expected.chars().filter(|c| !c.is_whitespace()).collect();
+    actual.starts_with(&expected)
+}
+
+fn parse_script(text: &str) -> std::result::Result<Vec<ScriptGoal>, String> {
+    let mut parser = Parser { text, pos: 0 };
+    let mut program = String::new();
+    let mut goals = vec![];
+    let mut depth = 0;
+    let mut line_start = true;
+    while let Some(c) = parser.peek() {
+        if depth == 0 && line_start && parser.at_keyword("goal") {
+            goals.push(parser.goal(&program)?);
+            line_start = false;
+            continue;
+        }
+
+        parser.bump();
+        program.push(c);
+        match c {
+            '/' if parser.peek() == Some('/') => {
+                // Copy comments as they are, braces and all.
+                while let Some(c) = parser.peek().filter(|&c| c != '\n') {
+                    parser.bump();
+                    program.push(c);
+                }
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => return Err(parser.error("unmatched `}`")),
+            '}' => depth -= 1,
+            '\n' => line_start = true,
+            c if !c.is_whitespace() => line_start = false,
+            _ => {}
+        }
+    }
+    Ok(goals)
+}
+
+struct Parser<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.text[..self.pos].matches('\n').count() + 1
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{}: {}", self.line(), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        let rest = &self.text[self.pos..];
+        rest.starts_with(keyword)
+            && !matches!(rest[keyword.len()..].chars().next(), Some(c) if c.is_alphanumeric() || c == '_')
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += keyword.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: char) -> std::result::Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", expected)))
+        }
+    }
+
+    /// Parses `goal { G }` followed by its expectations.
+    fn goal(&mut self, program: &str) -> std::result::Result<ScriptGoal, String> {
+        let line = self.line();
+        self.eat_keyword("goal");
+        let goal = self.braced_text()?;
+
+        let mut expectations = vec![];
+        loop {
+            let end = self.pos;
+            self.skip_whitespace();
+            let kind = if self.eat_keyword("yields_all") {
+                "yields_all"
+            } else if self.eat_keyword("yields_first") {
+                "yields_first"
+            } else if self.eat_keyword("yields") {
+                "yields"
+            } else {
+                // Leave the whitespace, the next goal must start on a new line.
+                self.pos = end;
+                break;
+            };
+
+            let solvers = if self.peek() == Some('[') {
+                self.bump();
+                let end = self.text[self.pos..]
+                    .find(']')
+                    .ok_or_else(|| self.error("expected `]`"))?;
+                let name = self.text[self.pos..self.pos + end].trim();
+                let solver = SolverKind::from_name(name)
+                    .ok_or_else(|| self.error(&format!("unknown solver `{}`", name)))?;
+                self.pos += end + 1;
+                vec![solver]
+            } else if kind == "yields" {
+                vec![SolverKind::Slg, SolverKind::Recursive]
+            } else {
+                vec![SolverKind::Slg]
+            };
+
+            let strings = self.string_list()?;
+            let expected = |strings: Vec<String>| -> std::result::Result<Expected, String> {
+                Ok(match kind {
+                    "yields_all" => Expected::All(strings),
+                    "yields_first" => Expected::First(strings),
+                    _ if strings.len() == 1 => Expected::Aggregated(strings[0].clone()),
+                    _ => return Err(format!("{}: `yields` expects one result", line)),
+                })
+            };
+            for solver in solvers {
+                expectations.push((solver, expected(strings.clone())?));
+            }
+        }
+
+        if expectations.is_empty() {
+            return Err(self.error("expected `yields`, `yields_all` or `yields_first`"));
+        }
+        Ok(ScriptGoal {
+            line,
+            program: program.to_string(),
+            goal,
+            expectations,
+        })
+    }
+
+    /// Parses `{ ... }` with balanced braces inside, returning the text
+    /// between the outer braces.
+    fn braced_text(&mut self) -> std::result::Result<String, String> {
+        self.expect('{')?;
+        let start = self.pos;
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                Some('}') if depth == 0 => break,
+                Some('}') => depth -= 1,
+                Some('{') => depth += 1,
+                Some(_) => {}
+                None => return Err(self.error("expected `}`")),
+            }
+            self.bump();
+        }
+        let text = self.text[start..self.pos].to_string();
+        self.bump();
+        Ok(text)
+    }
+
+    /// Parses `{ "S1", ..., "SN" }`.
+    fn string_list(&mut self) -> std::result::Result<Vec<String>, String> {
+        self.expect('{')?;
+        let mut strings = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('}') => {
+                    self.bump();
+                    return Ok(strings);
+                }
+                Some('"') => strings.push(self.string()?),
+                _ => return Err(self.error("expected a string or `}`")),
+            }
+            self.skip_whitespace();
+            if self.peek() == Some(',') {
+                self.bump();
+            }
+        }
+    }
+
+    /// Parses a string literal. Like in Rust, a backslash escapes a quote,
+    /// a backslash or a newline (along with the whitespace following it).
+    fn string(&mut self) -> std::result::Result<String, String> {
+        self.bump();
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.bump();
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.bump();
+                    match self.peek() {
+                        Some(c @ '"') | Some(c @ '\\') => {
+                            self.bump();
+                            string.push(c);
+                        }
+                        Some('\n') => self.skip_whitespace(),
+                        _ => return Err(self.error("unknown escape in string")),
+                    }
+                }
+                Some(c) => {
+                    self.bump();
+                    string.push(c);
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+}
@@ -61,3 +61,44 @@ fn test_basic_const_values_in_assoc_ty_values() {
         }
     );
 }
+
+#[test]
+fn test_assoc_consts() {
+    // Test we render associated const declarations, values and projections.
+    reparse_test!(
+        program {
+            struct Foo<const N> { }
+            trait Bar {
+                const N: u32;
+            }
+            trait Baz<T> {
+                const M: u32;
+            }
+            impl<const N> Bar for Foo<N> {
+                const N: u32 = N;
+            }
+            impl<T, const N> Baz<T> for Foo<N> where T: Bar {
+                const M: u32 = <T as Bar>::N;
+            }
+            impl<T> Bar for Foo<{<T as Bar>::N}> where T: Bar {
+                const N: u32 = <T as Baz<T>>::M;
+            }
+        }
+    );
+}
+
+#[test]
+fn test_typed_const_generics() {
+    // Test we render the types of const parameters, and values of each type.
+    reparse_test!(
+        program {
+            struct Foo<const B: bool, const C: char, const N: i8, const M: usize> { }
+            trait Bar<const N: u8> {
+                const V: char;
+            }
+            impl<const N: u8> Bar<N> for Foo<true, 'x', -3, 7> {
+                const V: char = 'y';
+            }
+        }
+    );
+}
@@ -10,6 +10,7 @@ mod fn_;
 mod formatting;
 mod impl_;
 mod lifetimes;
+mod modules;
 mod opaque_ty;
 mod self_;
 mod struct_;
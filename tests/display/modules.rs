@@ -0,0 +1,64 @@
+#[test]
+fn test_items_in_modules() {
+    // Items in modules are written with their qualified names
+    reparse_test!(
+        program {
+            trait Trait {}
+            mod a {
+                struct Foo {}
+                trait Trait {}
+                impl Trait for Foo {}
+                mod b {
+                    struct Foo<T> where T: Trait {}
+                }
+            }
+            impl<T> Trait for a::b::Foo<T> where T: a::Trait {}
+        }
+        produces {
+            trait Trait {}
+            struct a::Foo {}
+            trait a::Trait {}
+            impl a::Trait for a::Foo {}
+            struct a::b::Foo<T> where T: a::Trait {}
+            impl<T> Trait for a::b::Foo<T> where T: a::Trait {}
+        }
+    );
+}
+
+#[test]
+fn test_qualified_names_in_types() {
+    // Test that names of types in modules are written in full wherever
+    // they are used
+    reparse_test!(
+        program {
+            struct Foo {}
+            mod a {
+                struct Foo {}
+                struct Bar<T> {}
+                fn baz(x: Bar<crate::Foo>) -> Foo;
+                opaque type Opaque: Trait = Bar<Foo>;
+                trait Trait {
+                    type Assoc;
+                }
+            }
+            struct Baz {
+                x: a::Bar<a::Foo>,
+                y: <a::Foo as a::Trait>::Assoc
+            }
+        }
+        produces {
+            struct Foo {}
+            struct a::Foo {}
+            struct a::Bar<T> {}
+            fn a::baz(x: a::Bar<Foo>) -> a::Foo;
+            opaque type a::Opaque: a::Trait = a::Bar<a::Foo>;
+            trait a::Trait {
+                type Assoc;
+            }
+            struct Baz {
+                x: a::Bar<a::Foo>,
+                y: <a::Foo as a::Trait>::Assoc
+            }
+        }
+    );
+}
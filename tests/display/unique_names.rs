@@ -49,6 +49,9 @@ where
     fn assoc_type_name(&self, _assoc_ty_id: chalk_ir::AssocTypeId<I>) -> String {
         "Foo".to_owned()
     }
+    fn assoc_const_name(&self, _assoc_const_id: chalk_ir::AssocConstId<I>) -> String {
+        "Foo".to_owned()
+    }
     fn opaque_type_name(&self, _opaque_ty_id: chalk_ir::OpaqueTyId<I>) -> String {
         "Foo".to_owned()
     }
@@ -97,6 +100,18 @@ where
     ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedTyValue<I>> {
         self.db.associated_ty_value(id)
     }
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+    fn associated_const_value(
+        &self,
+        id: chalk_solve::rust_ir::AssociatedConstValueId<I>,
+    ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedConstValue<I>> {
+        self.db.associated_const_value(id)
+    }
     fn generator_datum(
         &self,
         generator_id: chalk_ir::GeneratorId<I>,
@@ -190,6 +205,14 @@ where
         self.db.discriminant_type(ty)
     }
 
+    fn try_evaluate_const(
+        &self,
+        ty: &chalk_ir::Ty<I>,
+        unevaluated: &chalk_ir::UnevaluatedConst<I>,
+    ) -> Option<chalk_ir::ConcreteConst<I>> {
+        self.db.try_evaluate_const(ty, unevaluated)
+    }
+
     fn unification_database(&self) -> &dyn UnificationDatabase<I> {
         self.db.unification_database()
     }
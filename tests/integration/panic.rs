@@ -82,6 +82,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
                 coinductive: false,
             },
             associated_ty_ids: vec![],
+            associated_const_ids: vec![],
             well_known: None,
         })
     }
@@ -115,6 +116,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
             binders,
             impl_type: ImplType::Local,
             associated_ty_value_ids: vec![],
+            associated_const_value_ids: vec![],
         })
     }
 
@@ -125,6 +127,20 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
         unimplemented!()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        unimplemented!()
+    }
+
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        unimplemented!()
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
         unimplemented!()
     }
@@ -258,6 +274,14 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
         unimplemented!()
     }
 
+    fn try_evaluate_const(
+        &self,
+        ty: &Ty<ChalkIr>,
+        unevaluated: &UnevaluatedConst<ChalkIr>,
+    ) -> Option<ConcreteConst<ChalkIr>> {
+        unimplemented!()
+    }
+
     fn unification_database(&self) -> &dyn UnificationDatabase<ChalkIr> {
         self
     }
@@ -6,5 +6,7 @@ mod test;
 mod display;
 mod logging_db;
 mod lowering;
+mod repl;
+mod scripts;
 
 mod integration;
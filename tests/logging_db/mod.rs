@@ -90,6 +90,33 @@ fn records_generics() {
     }
 }
 
+#[test]
+fn records_items_in_modules() {
+    logging_db_output_sufficient! {
+        program {
+            trait Trait {}
+            mod a {
+                struct S {}
+                mod b {
+                    struct S {}
+                    impl Trait for a::S {}
+                }
+            }
+        }
+
+        goal {
+            a::S: Trait
+        } yields {
+            "Unique"
+        }
+        goal {
+            a::b::S: Trait
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
 #[test]
 fn records_parents_parent() {
     logging_db_output_sufficient! {
@@ -138,6 +165,30 @@ fn records_associated_type_bounds() {
     }
 }
 
+#[test]
+fn records_associated_consts() {
+    logging_db_output_sufficient! {
+        program {
+            trait Foo {
+                const N: u32;
+            }
+            trait Bar {}
+
+            struct S {}
+            impl Foo for S {
+                const N: u32 = 3;
+            }
+            impl Bar for [S; 3] {}
+        }
+
+        goal {
+            [S; <S as Foo>::N]: Bar
+        } yields {
+            "Unique"
+        }
+    }
+}
+
 #[test]
 fn records_generic_impls() {
     logging_db_output_sufficient! {
@@ -1,6 +1,12 @@
 use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::program_sources::STD_PRELUDE;
 use chalk_integration::query::LoweringDatabase;
-use chalk_integration::SolverChoice;
+use chalk_integration::{Identifier, SolverChoice};
+use chalk_parse::ast::Span;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::rust_ir::WellKnownTrait;
+use std::sync::Arc;
 
 #[test]
 fn lower_success() {
@@ -149,6 +155,226 @@ fn assoc_tys() {
     }
 }
 
+#[test]
+fn assoc_consts() {
+    lowering_success! {
+        program {
+            struct Foo { }
+            trait Bar<T> { const N: u32; }
+            impl<T> Bar<T> for Foo { const N: u32 = 3; }
+
+            trait Baz { }
+            impl<T> Baz for [T; <Foo as Bar<T>>::N] { }
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            trait Bar { const N: u32; }
+            impl Bar for Foo { const M: u32 = 3; }
+        }
+        error_msg {
+            "no associated const `M` defined in trait"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            trait Bar { }
+            impl Bar for [u8; <Foo as Bar>::N] { }
+        }
+        error_msg {
+            "no associated const `N` defined in trait"
+        }
+    }
+}
+
+#[test]
+fn unevaluated_consts() {
+    lowering_success! {
+        program {
+            struct Foo<const N> { }
+            trait Bar { }
+            impl<const N, const M> Bar for Foo<{(N + 1) * M - 2}> { }
+            impl<const N> Bar for [u8; {N * 2}] { }
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N> { }
+            trait Bar { }
+            impl<T> Bar for Foo<{T + 1}> { }
+        }
+        error_msg {
+            "incorrect parameter kind for `T`: expected const, found type"
+        }
+    }
+}
+
+#[test]
+fn typed_consts() {
+    lowering_success! {
+        program {
+            struct Foo<const B: bool, const N: usize> { }
+            trait Bar { const C: char; }
+            impl<const M: usize> Bar for Foo<false, {M * 2}> { const C: char = 'c'; }
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const B: bool> { }
+            trait Bar { }
+            impl Bar for Foo<3> { }
+        }
+        error_msg {
+            "incorrect const type for `Foo`: expected bool, found u32"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N: usize> { }
+            trait Bar { }
+            impl<const N: u8> Bar for Foo<N> { }
+        }
+        error_msg {
+            "incorrect const type for `Foo`: expected usize, found u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N: usize> { }
+            trait Bar { }
+            impl<const N: u8> Bar for Foo<{N + 1}> { }
+        }
+        error_msg {
+            "incorrect const type for `N`: expected usize, found u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Bar<const N: i32> { }
+            impl Bar<3u8> for u32 { }
+        }
+        error_msg {
+            "incorrect const type for `Bar`: expected i32, found u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            trait Bar { const C: bool; }
+            impl Bar for Foo { const C: bool = 'c'; }
+        }
+        error_msg {
+            "incorrect const type for `C`: expected bool, found char"
+        }
+    }
+}
+
+#[test]
+fn const_literals_out_of_range() {
+    lowering_error! {
+        program {
+            struct Foo<const N: u8> { }
+            trait Bar { }
+            impl Bar for Foo<300> { }
+        }
+        error_msg {
+            "literal `300` does not fit in type u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N: u8> { }
+            trait Bar { }
+            impl Bar for Foo<300u8> { }
+        }
+        error_msg {
+            "literal `300` does not fit in type u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N: u8> { }
+            trait Bar { }
+            impl Bar for Foo<-1> { }
+        }
+        error_msg {
+            "literal `-1` does not fit in type u8"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            trait Bar { const N: i8; }
+            impl Bar for Foo { const N: i8 = 128; }
+        }
+        error_msg {
+            "literal `128` does not fit in type i8"
+        }
+    }
+}
+
+#[test]
+fn oversized_integer_literals() {
+    lowering_success! {
+        program {
+            struct Foo<const N: i128> { }
+            trait Bar { }
+            impl Bar for Foo<-170141183460469231731687303715884105728> { }
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<const N: i128> { }
+            trait Bar { }
+            impl Bar for Foo<170141183460469231731687303715884105728> { }
+        }
+        error_msg {
+            "parse error: integer literal is too large"
+        }
+    }
+}
+
+#[test]
+fn const_types_in_goals() {
+    let db = ChalkDatabase::with("", SolverChoice::default());
+    db.parse_and_lower_goal("exists<const X: usize> { X = 3 }")
+        .unwrap();
+    db.parse_and_lower_goal("exists<const X: i8> { -3 = X }")
+        .unwrap();
+
+    for (goal, error) in &[
+        (
+            "exists<const X: bool> { X = 3 }",
+            "incorrect const type: expected bool, found u32",
+        ),
+        (
+            "exists<const X: bool> { 3 = X }",
+            "incorrect const type: expected bool, found u32",
+        ),
+        (
+            "exists<const X: usize, const Y: u8> { X = Y }",
+            "incorrect const type for `Y`: expected usize, found u8",
+        ),
+    ] {
+        let actual = db.parse_and_lower_goal(goal).unwrap_err().to_string();
+        crate::test_util::assert_same(&actual, error);
+    }
+}
+
 #[test]
 fn goal_quantifiers() {
     let db = ChalkDatabase::with("trait Foo<A, B> { }", SolverChoice::default());
@@ -163,6 +389,56 @@ fn goal_quantifiers() {
     });
 }
 
+#[test]
+fn solver_kept_while_program_unchanged() {
+    let mut db = ChalkDatabase::with("struct Foo { }", SolverChoice::default());
+    let solver = db.solver();
+
+    db.set_program_text(Arc::new("// A comment\nstruct Foo { }".to_string()));
+    assert!(db.solver() == solver);
+
+    db.set_program_text(Arc::new("struct Foo { } struct Bar { }".to_string()));
+    assert!(db.solver() != solver);
+}
+
+#[test]
+fn first_domain_goal_clauses() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            struct Foo { }
+            struct Vec<T> { }
+            impl<T> Clone for Vec<T> where T: Clone { }
+        ",
+        SolverChoice::default(),
+    );
+    let goal = db
+        .parse_and_lower_goal(
+            "exists<T> { T = Foo, forall<U> { if (U: Clone) { Vec<U>: Clone } } }",
+        )
+        .unwrap();
+    db.with_program(|_| {
+        let domain_goal = goal.into_first_domain_goal(&ChalkIr).unwrap();
+        assert_eq!(
+            format!("{:?}", domain_goal.canonical.value.goal),
+            "Implemented(Vec<!1_0>: Clone)"
+        );
+        let clauses: Vec<_> = db
+            .program_clauses_for_goal(&domain_goal)
+            .unwrap()
+            .iter()
+            .map(|clause| format!("{:?}", clause))
+            .collect();
+        assert_eq!(
+            clauses,
+            vec![
+                "for<type> Implemented(^0.0: Clone) :- FromEnv(^0.0: Clone)",
+                "for<type> Implemented(Vec<^0.0>: Clone) :- ForAll<> { Implemented(^1.0: Clone) }",
+            ]
+        );
+    });
+}
+
 #[test]
 fn atc_accounting() {
     let db = ChalkDatabase::with(
@@ -814,3 +1090,256 @@ fn algebraic_data_types() {
         }
     }
 }
+
+fn checked_program_error(program_text: &str) -> String {
+    ChalkDatabase::with(program_text, SolverChoice::default())
+        .checked_program()
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn error_positions() {
+    assert_eq!(
+        checked_program_error("struct Foo { }\ntrait Bar { }\n\n    impl Bar for Baz { }\n"),
+        "invalid parameter name `Baz`\n\
+         position (line 4, column 18): `impl Bar for Baz { }`\n\
+         \x20                                           ^^^\n"
+    );
+
+    // Errors without a position of their own point at the innermost type,
+    // where clause or item they occur in.
+    assert_eq!(
+        checked_program_error("struct Foo<'a> {\n    f: for<'a> fn(&'a u32)\n}"),
+        "duplicate or shadowed parameters\n\
+         position (line 2, column 8): `f: for<'a> fn(&'a u32)`\n\
+         \x20                                ^^^^^^^^^^^^^^^^^^^\n"
+    );
+    assert_eq!(
+        checked_program_error("trait Foo<'a> where forall<'a> Self: Foo<'a> { }"),
+        "duplicate or shadowed parameters\n\
+         position (line 1, column 21): `trait Foo<'a> where forall<'a> Self: Foo<'a> { }`\n\
+         \x20                                                  ^^^^^^^^^^^^^^^^^^^^^^^^\n"
+    );
+    assert_eq!(
+        checked_program_error("struct Foo { }\nextern \"Foo\" fn foo(); "),
+        "invalid extern ABI `Foo`\n\
+         position (line 2, column 1): `extern \"Foo\" fn foo();`\n\
+         \x20                             ^^^^^^^^^^^^^^^^^^^^^^\n"
+    );
+
+    let db = ChalkDatabase::with("trait Foo { }", SolverChoice::default());
+    assert_eq!(
+        db.parse_and_lower_goal("exists<T> { T: Bar }")
+            .unwrap_err()
+            .to_string(),
+        "invalid trait name `Bar`\n\
+         position (line 1, column 16): `exists<T> { T: Bar }`\n\
+         \x20                                             ^^^\n"
+    );
+}
+
+#[test]
+fn coherence_and_wf_error_positions() {
+    assert_eq!(
+        checked_program_error(
+            "trait Foo { }\nstruct A { }\nimpl Foo for A { }\nimpl Foo for A where A: Foo { }"
+        ),
+        "overlapping impls of trait `Foo`, `impl Foo for A` and `impl Foo for A where A: Foo`: \
+         both impls apply to `A: Foo`\n\
+         position (line 3, column 1): `impl Foo for A { }`\n\
+         \x20                             ^^^^^^^^^^^^^^^^^^\n\n\
+         position (line 4, column 1): `impl Foo for A where A: Foo { }`\n\
+         \x20                             ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^\n"
+    );
+    assert_eq!(
+        checked_program_error(
+            "trait Copy { }\nstruct Foo<T> where T: Copy { }\nstruct Bar {\n    f: Foo<Bar>\n}"
+        ),
+        "type declaration `Bar` does not meet well-formedness requirements: \
+         could not prove `WellFormed(Foo<Bar>)`\n\
+         position (line 3, column 1): `struct Bar {`\n\
+         \x20                             ^^^^^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn parse_error_recovery() {
+    let text = "struct Foo { }\n\
+                impl Bar for { }\n\
+                trait Bar { }\n\
+                impl<T> Bar for Foo where T: { }\n\
+                struct Baz { }";
+    let (program, diagnostics) = chalk_parse::parse_program_with_recovery(text);
+    let spans: Vec<_> = program.items.iter().map(|item| item.span()).collect();
+    assert_eq!(
+        spans,
+        vec![Span::new(0, 14), Span::new(32, 45), Span::new(79, 93)]
+    );
+    let spans: Vec<_> = diagnostics.iter().map(|d| d.span).collect();
+    assert_eq!(
+        spans,
+        vec![Some(Span::new(28, 29)), Some(Span::new(75, 76))]
+    );
+
+    let error = chalk_parse::parse_program(text).unwrap_err().to_string();
+    let positions: Vec<_> = error
+        .lines()
+        .filter(|line| line.starts_with("position"))
+        .collect();
+    assert_eq!(
+        positions,
+        vec![
+            "position (line 2, column 14): `impl Bar for { }`",
+            "position (line 4, column 30): `impl<T> Bar for Foo where T: { }`",
+        ]
+    );
+}
+
+/// Creates a database for the program in `main.chalk`, after writing the
+/// given files to a new directory.
+fn db_with_files(test_name: &str, files: &[(&str, &str)]) -> ChalkDatabase {
+    let dir = std::env::temp_dir().join(format!("chalk-{}-{}", test_name, std::process::id()));
+    for (name, text) in files {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+    let path = dir.join("main.chalk");
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut db = ChalkDatabase::with(&text, SolverChoice::default());
+    db.set_program_path(Some(Arc::new(path)));
+    db
+}
+
+#[test]
+fn include_files() {
+    let db = db_with_files(
+        "include",
+        &[
+            (
+                "main.chalk",
+                "include \"lib/traits.chalk\";\ninclude \"types.chalk\";\nimpl Clone for Foo { }",
+            ),
+            (
+                "types.chalk",
+                "include \"lib/traits.chalk\";\nstruct Foo { }",
+            ),
+            ("lib/traits.chalk", "trait Clone { }"),
+        ],
+    );
+    let program = db.checked_program().unwrap();
+    assert_eq!(program.trait_ids.len(), 1);
+    assert_eq!(program.adt_ids.len(), 1);
+    assert_eq!(program.impl_data.len(), 1);
+}
+
+#[test]
+fn include_errors() {
+    let db = db_with_files(
+        "include-cycle",
+        &[
+            ("main.chalk", "include \"a.chalk\";"),
+            ("a.chalk", "include \"b.chalk\";"),
+            ("b.chalk", "include \"a.chalk\";"),
+        ],
+    );
+    let error = db.checked_program().unwrap_err().to_string();
+    assert!(error.starts_with("include cycle: "), "{}", error);
+    let cycle: Vec<_> = error["include cycle: ".len()..].split(" -> ").collect();
+    assert!(cycle[0].ends_with("a.chalk`"), "{}", error);
+    assert!(cycle[1].ends_with("b.chalk`"), "{}", error);
+    assert_eq!(cycle[2], cycle[0]);
+
+    let db = db_with_files(
+        "include-in-module",
+        &[("main.chalk", "mod a { include \"a.chalk\"; }")],
+    );
+    assert_eq!(
+        db.checked_program().unwrap_err().to_string(),
+        "`include \"a.chalk\";` is not allowed in a module"
+    );
+
+    let db = db_with_files(
+        "include-error",
+        &[
+            ("main.chalk", "include \"a.chalk\";\nstruct Foo { }"),
+            ("a.chalk", "// A comment\nimpl Clone for Foo { }"),
+        ],
+    );
+    let error = db.checked_program().unwrap_err().to_string();
+    assert!(
+        error.starts_with("invalid trait name `Clone`\nin `"),
+        "{}",
+        error
+    );
+    assert!(
+        error.ends_with(
+            "a.chalk`:\n\
+             position (line 2, column 6): `impl Clone for Foo { }`\n\
+             \x20                                  ^^^^^\n"
+        ),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn prelude() {
+    let mut db = ChalkDatabase::with("struct Foo { }", SolverChoice::default());
+    assert!(db.checked_program().unwrap().well_known_traits.is_empty());
+
+    db.set_prelude(Some(Arc::new(STD_PRELUDE.to_string())));
+    let program = db.checked_program().unwrap();
+    assert!(program
+        .well_known_traits
+        .contains_key(&WellKnownTrait::Sized));
+    assert!(program.well_known_traits.contains_key(&WellKnownTrait::Fn));
+
+    db.set_program_text(Arc::new("impl Copy for Foo { }".to_string()));
+    let error = db.checked_program().unwrap_err().to_string();
+    assert!(
+        error.starts_with("invalid parameter name `Foo`"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn prelude_skips_declared_items() {
+    let mut db = ChalkDatabase::with(
+        "#[lang(sized)] trait MySized { } trait Clone { }",
+        SolverChoice::default(),
+    );
+    db.set_prelude(Some(Arc::new(STD_PRELUDE.to_string())));
+    let program = db.checked_program().unwrap();
+    let trait_id = |name: &str| program.trait_ids[&Identifier::from(name)];
+
+    // The program's own lang item is used rather than the prelude's
+    assert!(!program.trait_ids.contains_key(&Identifier::from("Sized")));
+    assert_eq!(
+        program.well_known_traits[&WellKnownTrait::Sized],
+        trait_id("MySized")
+    );
+
+    // The program's `Clone` replaces the prelude's
+    assert!(!program
+        .well_known_traits
+        .contains_key(&WellKnownTrait::Clone));
+    assert!(program
+        .well_known_traits
+        .contains_key(&WellKnownTrait::Copy));
+}
+
+#[test]
+fn prelude_and_libstd() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/libstd.chalk");
+    let text = std::fs::read_to_string(path).unwrap();
+    let mut db = ChalkDatabase::with(&text, SolverChoice::default());
+    db.set_program_path(Some(Arc::new(path.into())));
+    db.set_prelude(Some(Arc::new(STD_PRELUDE.to_string())));
+    let program = db.checked_program().unwrap();
+    assert!(program
+        .well_known_traits
+        .contains_key(&WellKnownTrait::Sized));
+}
@@ -0,0 +1,158 @@
+//! Tests running the `chalk` binary as a repl, with commands on stdin
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs `chalk` with `args`, feeding it `input`, and returns its output.
+fn run_repl(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chalk"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    println!("{}{}", stdout, stderr);
+    stdout + &stderr
+}
+
+#[test]
+fn default_solver() {
+    let output = run_repl(&[], "solver\n");
+    assert!(output.contains("SLG { max_size: 10, expected_answers: None }"));
+
+    let output = run_repl(&["--solver=recursive"], "solver\n");
+    assert!(
+        output.contains("Recursive { overflow_depth: 100, caching_enabled: true, max_size: 30 }")
+    );
+}
+
+#[test]
+fn overflow_depth_sets_slg_max_size() {
+    let output = run_repl(&["--overflow-depth=3"], "solver\n");
+    assert!(output.contains("SLG { max_size: 3, expected_answers: None }"));
+
+    let output = run_repl(&["--overflow-depth=3", "--max-size=5"], "solver\n");
+    assert!(output.contains("SLG { max_size: 5, expected_answers: None }"));
+}
+
+#[test]
+fn recursive_solver_flags() {
+    let output = run_repl(
+        &[
+            "--solver=recursive",
+            "--overflow-depth=7",
+            "--max-size=5",
+            "--caching=off",
+        ],
+        "solver\n",
+    );
+    assert!(output.contains("Recursive { overflow_depth: 7, caching_enabled: false, max_size: 5 }"));
+}
+
+#[test]
+fn solver_command() {
+    let output = run_repl(
+        &[],
+        "solver recursive\nsolver\nsolver slg\nsolver\nsolver foo\n",
+    );
+    let recursive = output
+        .find("Recursive { overflow_depth: 100, caching_enabled: true, max_size: 30 }")
+        .unwrap();
+    let slg = output
+        .find("SLG { max_size: 10, expected_answers: None }")
+        .unwrap();
+    assert!(recursive < slg);
+    assert!(output.contains("error: unknown solver `foo`"));
+}
+
+#[test]
+fn compare_command() {
+    let program = "\
+        define struct Vec<T> { }\n\
+        define trait A { }\n\
+        define trait B { }\n\
+        define impl<T> A for Vec<T> where T: A, T: B { }\n\
+        define impl A for u32 { }\n\
+        define impl B for u32 { }\n\
+        define impl A for i32 { }\n\
+        define impl B for i8 { }\n";
+
+    let output = run_repl(&[], &format!("{}compare Vec<u32>: A\n", program));
+    assert!(output.contains("Both solvers agree: Unique; substitution [], lifetime constraints []"));
+
+    let output = run_repl(
+        &[],
+        &format!("{}compare exists<T> {{ Vec<T>: A }}\n", program),
+    );
+    assert!(output.contains(
+        "The solvers disagree:\n\
+         - slg:       Unique; substitution [?0 := Uint(U32)], lifetime constraints []\n\
+         + recursive: Ambiguous; no inference guidance\n"
+    ));
+}
+
+#[test]
+fn clauses_command() {
+    let program = "\
+        define trait Clone { }\n\
+        define struct Vec<T> { }\n\
+        define impl<T> Clone for Vec<T> where T: Clone { }\n";
+
+    let output = run_repl(
+        &[],
+        &format!(
+            "{}clauses forall<U> {{ if (U: Clone) {{ Vec<U>: Clone }} }}\n",
+            program
+        ),
+    );
+    assert!(output.contains(
+        "Program clauses for `Vec<<placeholder>>: Clone`:\n    \
+         forall<_1_0> { _1_0: Clone :- FromEnv(_1_0: Clone) }\n    \
+         forall<_1_0> { Vec<_1_0>: Clone :- _1_0: Clone }\n\n"
+    ));
+}
+
+#[test]
+fn define_and_undef_commands() {
+    let output = run_repl(
+        &[],
+        "define struct Foo { }\n\
+         define trait Clone { }\n\
+         define struct Bar { }\n\
+         Foo: Clone\n\
+         define impl Clone for Foo { }\n\
+         Foo: Clone\n\
+         define struct Foo<T> { }\n\
+         undef Bar\n\
+         print\n",
+    );
+    assert!(output.contains(
+        "No possible solution.\n\n\
+         Unique; substitution [], lifetime constraints []\n"
+    ));
+    // Redefining `Foo` breaks the impl, so the old definition is kept
+    assert!(output.contains("error: `Foo` takes 1 type parameters, not 0"));
+    assert!(output.contains("\n\nstruct Foo { }\ntrait Clone { }\nimpl Clone for Foo { }\n\n"));
+
+    let output = run_repl(
+        &[],
+        "define struct Foo { }\n\
+         define struct Foo<T> { }\n\
+         undef Bar\n\
+         define struct A { } struct B { }\n\
+         print\n",
+    );
+    assert!(output.contains("error: no item named `Bar`"));
+    assert!(output.contains("error: expected a single item"));
+    assert!(output.starts_with("struct Foo<T> { }\n\n"));
+}
@@ -0,0 +1,47 @@
+//! Tests running the `chalk` binary on test scripts
+
+use std::process::{Command, Output};
+
+fn run_script(path: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_chalk"))
+        .arg(format!("--test={}", path))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn passing_script() {
+    let output = run_script(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/scripts/impls.chalk"
+    ));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.ends_with("9 passed, 0 failed\n"));
+}
+
+#[test]
+fn failing_script() {
+    let dir = std::env::temp_dir().join(format!("chalk-failing-script-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("failing.chalk");
+    std::fs::write(
+        &path,
+        r#"
+        trait Foo {}
+        struct A {}
+        goal { A: Foo } yields[slg] { "Unique" }
+        impl Foo for A {}
+        goal { A: Foo } yields[slg] { "Unique" }
+        "#,
+    )
+    .unwrap();
+    let output = run_script(path.to_str().unwrap());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains(":4: goal `A: Foo` failed using SLG"));
+    assert!(stdout.contains("- expected: Unique\n+ actual:   No possible solution\n"));
+    assert!(stdout.ends_with("1 passed, 1 failed\n"));
+}
@@ -0,0 +1,281 @@
+//! Tests targeting the built-in CoerceUnsized impls
+
+use super::*;
+
+#[test]
+fn coerce_unsized_refs() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+        }
+
+        goal {
+            forall<'a> {
+                &'a [u8; 3]: CoerceUnsized<&'a [u8]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                &'a mut [u8; 3]: CoerceUnsized<&'a mut [u8]>
+            }
+        } yields {
+            "Unique"
+        }
+
+        // `&mut T` can be coerced to a shorter-lived `&U`
+        goal {
+            forall<'a, 'b> {
+                &'a mut [u8; 3]: CoerceUnsized<&'b [u8]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_1 }]"
+        }
+
+        // Mutability can't be added
+        goal {
+            forall<'a> {
+                &'a [u8; 3]: CoerceUnsized<&'a mut [u8]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The pointee must implement `Unsize`
+        goal {
+            forall<'a> {
+                &'a [u8; 3]: CoerceUnsized<&'a [u16]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                &'a [u8]: CoerceUnsized<&'a [u8]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The target pointee is inferred from the `Unsize` impl
+        goal {
+            forall<'a> {
+                exists<T> {
+                    &'a [u8; 3]: CoerceUnsized<&'a [T]>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U8)]"
+        }
+
+        goal {
+            exists<T> {
+                T: CoerceUnsized<*const [u8]>
+            }
+        } yields_first[SolverChoice::slg(10, None)] {
+            "Floundered"
+        }
+    }
+}
+
+#[test]
+fn coerce_unsized_raw_ptrs() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+        }
+
+        goal {
+            *mut [u8; 3]: CoerceUnsized<*mut [u8]>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            *mut [u8; 3]: CoerceUnsized<*const [u8]>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            *const [u8; 3]: CoerceUnsized<*const [u8]>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        // Mutability can't be added
+        goal {
+            *const [u8; 3]: CoerceUnsized<*mut [u8]>
+        } yields {
+            "No possible solution"
+        }
+
+        // References can be coerced to raw pointers
+        goal {
+            forall<'a> {
+                &'a mut [u8; 3]: CoerceUnsized<*const [u8]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<'a> {
+                &'a mut [u8; 3]: CoerceUnsized<*mut [u8]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<'a> {
+                &'a [u8; 3]: CoerceUnsized<*const [u8]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<'a> {
+                &'a [u8; 3]: CoerceUnsized<*mut [u8]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // But raw pointers can't be coerced to references
+        goal {
+            forall<'a> {
+                *const [u8; 3]: CoerceUnsized<&'a [u8]>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn coerce_unsized_struct() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+
+            struct MyBox<T> {
+                ptr: *const T,
+            }
+
+            impl<T, U> CoerceUnsized<MyBox<U>> for MyBox<T> where T: Unsize<U> {}
+        }
+
+        goal {
+            MyBox<[u8; 3]>: CoerceUnsized<MyBox<[u8]>>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            MyBox<[u8]>: CoerceUnsized<MyBox<[u8]>>
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn coerce_unsized_struct_builtin() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+
+            #[phantom_data]
+            struct PhantomData<T> {}
+
+            struct Ptr<T> {
+                ptr: *const T,
+                phantom: PhantomData<T>,
+            }
+
+            struct Pair<T> {
+                a: *const T,
+                b: *const T,
+            }
+
+            struct Wrapper<A, T> {
+                extra: A,
+                inner: Ptr<T>,
+            }
+
+            struct Direct<T> {
+                value: T,
+            }
+
+            enum Either<T> {
+                Left(*const T),
+            }
+        }
+
+        goal {
+            Ptr<[u8; 3]>: CoerceUnsized<Ptr<[u8]>>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Ptr<[u8]>: CoerceUnsized<Ptr<[u8]>>
+        } yields {
+            "No possible solution"
+        }
+
+        // Coercions go through the field that changes, recursively
+        goal {
+            Wrapper<u32, [u8; 3]>: CoerceUnsized<Wrapper<u32, [u8]>>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Wrapper<u32, [u8; 3]>: CoerceUnsized<Wrapper<i32, [u8]>>
+        } yields {
+            "No possible solution"
+        }
+
+        // More than one field changes
+        goal {
+            Pair<[u8; 3]>: CoerceUnsized<Pair<[u8]>>
+        } yields {
+            "No possible solution"
+        }
+
+        // The field that changes isn't a pointer
+        goal {
+            Direct<[u8; 3]>: CoerceUnsized<Direct<[u8]>>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Either<[u8; 3]>: CoerceUnsized<Either<[u8]>>
+        } yields {
+            "No possible solution"
+        }
+    }
+}
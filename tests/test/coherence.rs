@@ -10,7 +10,8 @@ fn two_impls_for_same_type() {
             impl Foo for Bar { }
         }
         error_msg {
-            "overlapping impls of trait `Foo`"
+            "overlapping impls of trait `Foo`, `impl Foo for Bar` and `impl Foo for Bar`: \
+             both impls apply to `Bar: Foo`"
         }
     }
 
@@ -22,7 +23,8 @@ fn two_impls_for_same_type() {
             impl Foo for Bar<3> { }
         }
         error_msg {
-            "overlapping impls of trait `Foo`"
+            "overlapping impls of trait `Foo`, `impl Foo for Bar<3>` and `impl Foo for Bar<3>`: \
+             both impls apply to `Bar<3>: Foo`"
         }
     }
 }
@@ -76,7 +78,8 @@ fn two_blanket_impls() {
             impl Baz for Quux { }
         }
         error_msg {
-            "overlapping impls of trait `Foo`"
+            "overlapping impls of trait `Foo`, `impl<_1_0> Foo for _1_0 where _1_0: Bar` and \
+             `impl<_1_0> Foo for _1_0 where _1_0: Baz`: both impls apply to `Quux: Foo`"
         }
     }
 }
@@ -92,7 +95,9 @@ fn two_blanket_impls_open_ended() {
             impl<T> Foo for T where T: Baz { }
         }
         error_msg {
-            "overlapping impls of trait `Foo`"
+            "overlapping impls of trait `Foo`, `impl<_1_0> Foo for _1_0 where _1_0: Bar` and \
+             `impl<_1_0> Foo for _1_0 where _1_0: Baz`: an upstream or downstream crate could add \
+             impls that make both impls apply to `_: Foo`"
         }
     }
 }
@@ -133,7 +138,8 @@ fn multiple_parameters() {
             impl<T> Foo<Baz> for T { }
             impl<T> Foo<T> for Baz { }
         } error_msg {
-            "overlapping impls of trait `Foo`"
+            "overlapping impls of trait `Foo`, `impl<_1_0> Foo<Baz> for _1_0` and \
+             `impl<_1_0> Foo<_1_0> for Baz`: both impls apply to `Baz: Foo<Baz>`"
         }
     }
 }
@@ -308,7 +314,10 @@ fn downstream_impl_of_fundamental_43355() {
             // This makes the first impl now apply to A, which means that both of these impls now
             // overlap for A even though they didn't overlap in the original crate where A is defined.
         } error_msg {
-            "overlapping impls of trait `Trait1`"
+            "overlapping impls of trait `Trait1`, \
+             `impl<_1_0, _1_1> Trait1<_1_0> for _1_1 where _1_1: Trait2<_1_0>` and \
+             `impl<_1_0> Trait1<Box<_1_0>> for A`: an upstream or downstream crate could add \
+             impls that make both impls apply to `A: Trait1<Box<_>>`"
         }
     }
 }
@@ -326,7 +335,9 @@ fn fundamental_traits() {
             impl Bar for Foo { }
             impl<T> Bar for T where T: Sized { }
         } error_msg {
-            "overlapping impls of trait `Bar`"
+            "overlapping impls of trait `Bar`, `impl Bar for Foo` and \
+             `impl<_1_0> Bar for _1_0 where _1_0: Sized`: an upstream or downstream crate could \
+             add impls that make both impls apply to `Foo: Bar`"
         }
     }
 
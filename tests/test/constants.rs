@@ -149,3 +149,394 @@ fn placeholders_eq() {
         }
     }
 }
+
+#[test]
+fn assoc_const_normalize() {
+    test! {
+        program {
+            struct S<const N> {}
+            struct Foo {}
+
+            trait Tr {
+                const N: u32;
+            }
+
+            trait Tr2 {}
+
+            impl Tr for Foo {
+                const N: u32 = 3;
+            }
+
+            impl Tr2 for S<3> {}
+        }
+
+        goal {
+            S<{<Foo as Tr>::N}>: Tr2
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; <Foo as Tr>::N] = [u8; 3]
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; <Foo as Tr>::N] = [u8; 5]
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_generic_impl() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Tr {
+                const N: u32;
+            }
+
+            trait Tr2 {}
+
+            impl<const M> Tr for S<M> {
+                const N: u32 = M;
+            }
+
+            impl Tr2 for S<7> {}
+        }
+
+        goal {
+            S<{<S<7> as Tr>::N}>: Tr2
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            S<{<S<4> as Tr>::N}>: Tr2
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const C> {
+                S<{<S<C> as Tr>::N}>: Tr2
+            }
+        } yields {
+            "Unique; substitution [?0 := 7], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_projections_with_different_params() {
+    test! {
+        program {
+            trait Tr {
+                const N: u32;
+            }
+
+            impl Tr for u8 {
+                const N: u32 = 3;
+            }
+
+            impl Tr for u16 {
+                const N: u32 = 3;
+            }
+
+            impl Tr for u32 {
+                const N: u32 = 4;
+            }
+        }
+
+        goal {
+            [u8; <u8 as Tr>::N] = [u8; <u16 as Tr>::N]
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; <u8 as Tr>::N] = [u8; <u32 as Tr>::N]
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_placeholder() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Tr {
+                const N: u32;
+            }
+
+            trait Tr2 {}
+
+            impl<const M> Tr2 for S<M> {}
+            impl Tr2 for [u8; 3] {}
+        }
+
+        goal {
+            forall<T> {
+                if (T: Tr) {
+                    S<{<T as Tr>::N}>: Tr2
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Tr) {
+                    [u8; <T as Tr>::N]: Tr2
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn unevaluated_const_concrete() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Tr {}
+
+            impl Tr for S<6> {}
+        }
+
+        goal {
+            [u8; {2 + 1}] = [u8; 3]
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; {2 * (1 + 2)}] = [u8; 7]
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            S<{2 * 3}>: Tr
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            S<{0 - 1}>: Tr
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn unevaluated_const_generic() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Tr {}
+
+            impl<const N> Tr for S<{N + 1}> {}
+        }
+
+        goal {
+            S<4>: Tr
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+
+        goal {
+            exists<const N> {
+                S<{N + 1}>: Tr
+            }
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+
+        goal {
+            exists<const N> {
+                N = 3,
+                [u8; {N + 1}] = [u8; 4]
+            }
+        } yields {
+            "Unique; substitution [?0 := 3], lifetime constraints []"
+        }
+
+        goal {
+            exists<const N> {
+                N = 3,
+                [u8; {N * N}] = [u8; 4]
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<const N> {
+                [u8; {N + 1}] = [u8; 4]
+            }
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+    }
+}
+
+#[test]
+fn unevaluated_const_not_injective() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Tr {}
+
+            impl<const N> Tr for S<{N * 0}> {}
+        }
+
+        goal {
+            exists<const N, const M> {
+                N = 2,
+                M = 3,
+                [u8; {N * 0}] = [u8; {M * 0}]
+            }
+        } yields {
+            "Unique; substitution [?0 := 2, ?1 := 3], lifetime constraints []"
+        }
+
+        goal {
+            exists<const N, const M> {
+                N = 2,
+                M = 3,
+                [u8; {N * 1}] = [u8; {M * 1}]
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // Any `N` would do, but the solver can't evaluate `{N * 0}` to find
+        // out without picking one.
+        goal {
+            S<{5 * 0}>: Tr
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+    }
+}
+
+#[test]
+fn typed_consts() {
+    test! {
+        program {
+            struct B<const V: bool> {}
+            struct C<const V: char> {}
+            struct I<const V: i8> {}
+            struct U<const V: usize> {}
+
+            trait Tr {}
+
+            impl Tr for B<true> {}
+            impl Tr for C<'c'> {}
+            impl Tr for I<-1> {}
+            impl Tr for U<3> {}
+        }
+
+        goal {
+            B<true>: Tr
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            B<false>: Tr
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const V: bool> {
+                B<V>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := true], lifetime constraints []"
+        }
+
+        goal {
+            exists<const V: char> {
+                C<V>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := 'c'], lifetime constraints []"
+        }
+
+        goal {
+            exists<const V: i8> {
+                I<V>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := -1], lifetime constraints []"
+        }
+
+        goal {
+            exists<const V: usize> {
+                V = 3,
+                U<V>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := 3], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn typed_unevaluated_consts() {
+    test! {
+        program {
+            struct I<const V: i8> {}
+            struct U<const V: u8> {}
+
+            trait Tr {}
+
+            impl Tr for I<-1> {}
+            impl Tr for U<200> {}
+        }
+
+        goal {
+            exists<const V: i8> {
+                V = 1,
+                I<{V - 2}>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := 1], lifetime constraints []"
+        }
+
+        goal {
+            exists<const V: u8> {
+                V = 100,
+                U<{V + V}>: Tr
+            }
+        } yields {
+            "Unique; substitution [?0 := 100], lifetime constraints []"
+        }
+
+        goal {
+            exists<const V: u8> {
+                V = 228,
+                U<{V + V}>: Tr
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
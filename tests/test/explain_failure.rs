@@ -0,0 +1,99 @@
+//! Tests for the failure explanations returned by `Solver::explain_failure`
+
+use super::*;
+
+/// Tries to solve `goal_text` with each of the solvers, and checks that the
+/// causes of its failure are displayed as `expected`.
+fn assert_failure_causes(program_text: &str, goal_text: &str, expected: &[&str]) {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let db = ChalkDatabase::with(program_text, solver_choice);
+        let program = db.checked_program().unwrap();
+        chalk_integration::tls::set_current_program(&program, || {
+            let goal = db.parse_and_lower_goal(goal_text).unwrap();
+            let peeled_goal = goal.into_peeled_goal(db.interner());
+            let causes = db.explain_failure(&peeled_goal);
+            println!("using solver: {:?}", solver_choice);
+            assert_eq!(causes.len(), expected.len(), "causes: {:#?}", causes);
+            for (cause, expected) in causes.iter().zip(expected) {
+                assert_same(&cause.display(&db).to_string(), expected);
+            }
+        });
+    }
+}
+
+#[test]
+fn explain_missing_impl() {
+    assert_failure_causes(
+        "
+        trait Clone {}
+        struct Foo {}
+        ",
+        "Foo: Clone",
+        &["`Foo: Clone` cannot be proven"],
+    );
+}
+
+#[test]
+fn explain_missing_impl_required_by_impl() {
+    assert_failure_causes(
+        "
+        trait Clone {}
+        struct Foo {}
+        struct Vec<T> {}
+        impl<T> Clone for Vec<T> where T: Clone {}
+        ",
+        "Vec<Vec<Foo>>: Clone",
+        // The impl is listed twice: once for `Vec<Vec<Foo>>: Clone` and once
+        // for `Vec<Foo>: Clone`.
+        &["
+        `Foo: Clone` cannot be proven
+            required by `forall<_1_0> { Vec<_1_0>: Clone :- _1_0: Clone }`
+            required by `forall<_1_0> { Vec<_1_0>: Clone :- _1_0: Clone }`
+        "],
+    );
+}
+
+#[test]
+fn explain_only_failing_conditions() {
+    assert_failure_causes(
+        "
+        trait Foo {}
+        trait Bar {}
+        trait Baz {}
+        struct A {}
+        struct B {}
+        struct Pair<T, U> {}
+        impl Foo for A {}
+        impl<T, U> Baz for Pair<T, U> where T: Foo, U: Foo, T: Bar {}
+        ",
+        "Pair<A, B>: Baz",
+        &[
+            "
+            `B: Foo` cannot be proven
+                required by `forall<_1_0, _1_1> { Pair<_1_0, _1_1>: Baz :- \
+                    _1_0: Foo, _1_1: Foo, _1_0: Bar }`
+            ",
+            "
+            `A: Bar` cannot be proven
+                required by `forall<_1_0, _1_1> { Pair<_1_0, _1_1>: Baz :- \
+                    _1_0: Foo, _1_1: Foo, _1_0: Bar }`
+            ",
+        ],
+    );
+}
+
+#[test]
+fn explain_goal_that_holds() {
+    assert_failure_causes(
+        "
+        trait Clone {}
+        struct Foo {}
+        impl Clone for Foo {}
+        ",
+        "Foo: Clone",
+        &[],
+    );
+}
@@ -167,6 +167,59 @@ fn generic_fn_implements_fn_traits() {
     }
 }
 
+#[test]
+fn fn_def_implements_fn_traits_only_if_safe_rust_abi() {
+    test! {
+        program {
+            #[lang(fn_once)]
+            trait FnOnce<Args> {
+                type Output;
+            }
+
+            #[lang(fn_mut)]
+            trait FnMut<Args> where Self: FnOnce<Args> { }
+
+            #[lang(fn)]
+            trait Fn<Args> where Self: FnMut<Args> { }
+
+            extern "Rust" fn rust_abi(one: i32);
+            unsafe fn unsafe_fn(one: i32);
+            extern "C" fn c_abi(one: i32);
+            extern "C" fn variadic(one: i32, _: ...);
+        }
+
+        goal {
+            rust_abi: Fn<(i32,)>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            unsafe_fn: FnOnce<(i32,)>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            c_abi: FnOnce<(i32,)>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            variadic: FnOnce<(i32,)>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> { Normalize(<c_abi as FnOnce<(i32,)>>::Output -> T) }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
 #[test]
 fn fn_defs() {
     test! {
@@ -75,3 +75,23 @@ fn foreign_ty_is_not_clone() {
         goal { not { A: Clone } } yields { "Unique" }
     }
 }
+
+// foreign types don't implement auto traits unless explicitly impl'd
+#[test]
+fn foreign_ty_auto_traits() {
+    test! {
+        program {
+            #[auto] trait Send {}
+            #[auto] trait Sync {}
+            extern type A;
+            extern type B;
+            impl Send for B {}
+        }
+
+        goal { not { A: Send } } yields { "Unique" }
+
+        goal { B: Send } yields { "Unique" }
+
+        goal { not { B: Sync } } yields { "Unique" }
+    }
+}
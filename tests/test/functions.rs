@@ -109,6 +109,13 @@ fn function_implement_fn_traits() {
             "No possible solution"
         }
 
+        // Function pointers with a non-Rust ABI don't implement the Fn traits either
+        goal {
+            extern "C" fn(u8): FnOnce<(u8,)>
+        } yields {
+            "No possible solution"
+        }
+
         // Function pointres implicity return `()` when no return
         // type is specified - make sure that normalization understands
         // this
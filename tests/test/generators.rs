@@ -94,3 +94,215 @@ fn generator_test() {
         }
     }
 }
+
+#[test]
+fn generator_trait() {
+    test! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Yield;
+                type Return;
+            }
+
+            struct StructOne {}
+            struct StructTwo {}
+
+            generator simple_gen<>[resume = StructOne, yield = StructTwo] -> u8 {
+                upvars []
+                witnesses []
+            }
+
+            generator generic_gen<T>[resume = T, yield = StructTwo] -> T {
+                upvars [T]
+                witnesses []
+            }
+        }
+
+        goal {
+            simple_gen: Generator<StructOne>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            simple_gen: Generator<StructTwo>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<R> {
+                simple_gen: Generator<R>
+            }
+        } yields {
+            "Unique; substitution [?0 := StructOne]"
+        }
+
+        goal {
+            Normalize(<simple_gen as Generator<StructOne>>::Yield -> StructTwo)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Normalize(<simple_gen as Generator<StructOne>>::Return -> u8)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> {
+                Normalize(<simple_gen as Generator<StructOne>>::Return -> T)
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U8)]"
+        }
+
+        goal {
+            forall<T> {
+                generic_gen<T>: Generator<T>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<T> {
+                Normalize(<generic_gen<T> as Generator<T>>::Return -> T)
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            exists<T, U> {
+                Normalize(<generic_gen<T> as Generator<T>>::Yield -> U)
+            }
+        } yields {
+            "Unique; for<?U0> { substitution [?0 := ^0.0, ?1 := StructTwo]"
+        }
+
+        goal {
+            exists<G> {
+                G: Generator<StructOne>
+            }
+        } yields_first[SolverChoice::slg(10, None)] {
+            "Floundered"
+        }
+    }
+}
+
+#[test]
+fn generator_trait_impl_is_not_allowed() {
+    lowering_error! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Yield;
+                type Return;
+            }
+
+            struct Foo {}
+
+            impl Generator<()> for Foo {
+                type Yield = ();
+                type Return = ();
+            }
+        } error_msg {
+            "trait impl for `Generator` does not meet well-formedness requirements"
+        }
+    }
+}
+
+#[test]
+fn generators_are_sized() {
+    test! {
+        program {
+            #[lang(sized)]
+            trait Sized { }
+
+            generator empty_gen<>[resume = (), yield = ()] {
+                upvars []
+                witnesses []
+            }
+
+            generator generic_gen<T>[resume = T, yield = ()] -> T {
+                upvars [T]
+                witnesses []
+            }
+        }
+
+        goal {
+            empty_gen: Sized
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                generic_gen<T>: Sized
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn generator_trait_associated_types_by_name() {
+    test! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Return;
+                type Yield;
+            }
+
+            struct StructOne {}
+            struct StructTwo {}
+
+            generator simple_gen<>[resume = StructOne, yield = StructTwo] -> u8 {
+                upvars []
+                witnesses []
+            }
+        }
+
+        goal {
+            Normalize(<simple_gen as Generator<StructOne>>::Yield -> StructTwo)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Normalize(<simple_gen as Generator<StructOne>>::Return -> u8)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn generator_trait_missing_associated_type() {
+    test! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Yield;
+            }
+
+            struct StructOne {}
+
+            generator simple_gen<>[resume = StructOne, yield = StructOne] {
+                upvars []
+                witnesses []
+            }
+        }
+
+        goal {
+            simple_gen: Generator<StructOne>
+        } yields {
+            "No possible solution"
+        }
+    }
+}
@@ -55,6 +55,8 @@ fn basic() {
             forall<T> { if (T: Sized) { T: Sized } }
         } yields_all[SolverChoice::slg(10, None)] {
             "substitution [], lifetime constraints []"
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [], lifetime constraints []"
         }
     }
 }
@@ -408,17 +410,81 @@ fn non_enumerable_traits_direct() {
             "Floundered"
         }
 
+        goal {
+            exists<A> { A: NonEnumerable }
+        } yields_all[SolverChoice::recursive_default()] {
+            "Ambiguous(for<?U0> { substitution [?0 := ^0.0], lifetime constraints [] })"
+        }
+
         goal {
             exists<A> { A: Enumerable }
         } yields_all[SolverChoice::slg(3, None)] {
             "substitution [?0 := Foo], lifetime constraints []",
             "substitution [?0 := Bar], lifetime constraints []"
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := Foo], lifetime constraints []",
+            "substitution [?0 := Bar], lifetime constraints []"
         }
 
         goal {
             Foo: NonEnumerable
         } yields_all[SolverChoice::slg(3, None)] {
             "substitution [], lifetime constraints []"
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn multiple_answers_for_conjunctions() {
+    test! {
+        program {
+            struct Foo { }
+            struct Bar { }
+            struct Baz { }
+
+            trait A { }
+            impl A for Foo { }
+            impl A for Bar { }
+            impl A for Baz { }
+
+            trait B { }
+            impl B for Bar { }
+            impl B for Baz { }
+
+            trait C<T> { }
+            impl C<Foo> for Bar { }
+            impl C<Bar> for Baz { }
+        }
+
+        goal {
+            exists<T> { T: A, T: B }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := Bar], lifetime constraints []",
+            "substitution [?0 := Baz], lifetime constraints []"
+        } yields_all[SolverChoice::slg(3, None)] {
+            "substitution [?0 := Bar], lifetime constraints []",
+            "substitution [?0 := Baz], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { T: A, T: B }
+        } yields_first[SolverChoice::recursive_default()] {
+            "substitution [?0 := Bar], lifetime constraints []"
+        }
+
+        goal {
+            exists<T, U> { T: B, T: C<U>, U: A }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := Bar, ?1 := Foo], lifetime constraints []",
+            "substitution [?0 := Baz, ?1 := Bar], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { T: A, T = Foo }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := Foo], lifetime constraints []"
         }
     }
 }
@@ -576,6 +642,13 @@ fn flounder_ambiguous() {
         } yields {
             "Ambiguous; no inference guidance"
         }
+
+        goal {
+            exists<T> { Ref<T>: IntoIterator }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := A], lifetime constraints []",
+            "Ambiguous(for<?U0> { substitution [?0 := ^0.0], lifetime constraints [] })"
+        }
     }
 }
 
@@ -828,3 +901,48 @@ fn env_bound_vars() {
         }
     }
 }
+
+#[test]
+fn recursive_solver_should_continue() {
+    let db = ChalkDatabase::with(
+        "
+        struct Foo { }
+        struct Bar<T> { }
+        trait Baz { }
+        impl Baz for Foo { }
+        impl<T> Baz for Bar<T> where T: Baz { }
+        ",
+        SolverChoice::recursive_default(),
+    );
+
+    db.with_program(|_| {
+        let goal = db
+            .parse_and_lower_goal("exists<T> { T = Foo, Bar<Bar<T>>: Baz }")
+            .unwrap();
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+
+        // Asked to stop right away, we don't learn anything.
+        let result = db.solve_limited(&peeled_goal, &|| false);
+        assert_result(result, "Ambiguous; no inference guidance", db.interner());
+
+        // Asked to stop part way through, what we learned is only a suggestion.
+        let calls = std::cell::Cell::new(0);
+        let result = db.solve_limited(&peeled_goal, &|| {
+            calls.set(calls.get() + 1);
+            calls.get() < 5
+        });
+        assert_result(
+            result,
+            "Ambiguous; suggested substitution [?0 := Foo]",
+            db.interner(),
+        );
+
+        // The aborted searches must not have poisoned the cache.
+        let result = db.solve(&peeled_goal);
+        assert_result(
+            result,
+            "Unique; substitution [?0 := Foo], lifetime constraints []",
+            db.interner(),
+        );
+    });
+}
@@ -83,8 +83,8 @@ macro_rules! parse_test_data {
     };
 
     // goal { G } yields_all { "Y1", "Y2", ... , "YN" } -- test that the SLG
-    // solver gets exactly N answers in this order (the recursive solver can't
-    // return multiple answers)
+    // solver gets exactly N answers in this order (the recursive solver
+    // produces answers differently, so use `yields_all[C]` to test it)
     (@program[$program:tt] @parsed_goals[$($parsed_goals:tt)*] @unparsed_goals[
         goal $goal:tt yields_all { $($expected:expr),* }
         $($unparsed_goals:tt)*
@@ -175,6 +175,20 @@ macro_rules! parse_test_data {
               @unparsed_goals[goal $($unparsed_goals)*])
     };
 
+    // same as above, but there are multiple yields_all clauses => duplicate the goal
+    (@program[$program:tt] @parsed_goals[$($parsed_goals:tt)*] @unparsed_goals[
+        goal $goal:tt
+            yields_all[$C:expr] { $($expected:expr),* }
+        yields_all $($unparsed_tail:tt)*
+    ]) => {
+        parse_test_data!(@program[$program]
+              @parsed_goals[
+                  $($parsed_goals)*
+                      (stringify!($goal), $C, TestGoal::All(vec![$($expected),*]))
+              ]
+              @unparsed_goals[goal $goal yields_all $($unparsed_tail)*])
+    };
+
     // same as above, but for the final goal in the list.
     (@program[$program:tt] @parsed_goals[$($parsed_goals:tt)*] @unparsed_goals[
         goal $goal:tt yields_all[$C:expr] { $($expected:expr),* }
@@ -237,14 +251,6 @@ fn solve_goal(program_text: &str, goals: Vec<(&str, SolverChoice, TestGoal)>, co
         };
 
         for (goal_text, solver_choice, expected) in goals {
-            match (&solver_choice, &expected) {
-                (SolverChoice::Recursive { .. }, TestGoal::All(_))
-                | (SolverChoice::Recursive { .. }, TestGoal::First(_)) => {
-                    panic!("cannot test the recursive solver with yields_first or yields_all");
-                }
-                _ => {}
-            };
-
             if db.solver_choice() != solver_choice {
                 db.set_solver_choice(solver_choice);
             }
@@ -324,12 +330,14 @@ fn solve_goal(program_text: &str, goals: Vec<(&str, SolverChoice, TestGoal)>, co
 mod arrays;
 mod auto_traits;
 mod closures;
+mod coerce_unsized;
 mod coherence_goals;
 mod coinduction;
 mod constants;
 mod cycle;
 mod discriminant_kind;
 mod existential_types;
+mod explain_failure;
 mod fn_def;
 mod foreign_types;
 mod functions;
@@ -338,14 +346,17 @@ mod implied_bounds;
 mod impls;
 mod lifetimes;
 mod misc;
+mod modules;
 mod negation;
 mod never;
 mod numerics;
 mod object_safe;
 mod opaque_types;
 mod projection;
+mod proof_tree;
 mod refs;
 mod scalars;
+mod serialize;
 mod slices;
 mod string;
 mod subtype;
@@ -0,0 +1,137 @@
+//! Tests for modules and qualified names
+
+use super::*;
+
+#[test]
+fn same_name_in_different_modules() {
+    test! {
+        program {
+            trait Clone { }
+            mod std {
+                struct Vec<T> { }
+                impl<T> Clone for Vec<T> where T: Clone { }
+            }
+            mod my_crate {
+                struct Vec<T> { }
+                struct Foo { }
+                impl Clone for Foo { }
+            }
+        }
+
+        goal {
+            std::Vec<my_crate::Foo>: Clone
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            my_crate::Vec<my_crate::Foo>: Clone
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn names_are_looked_up_in_enclosing_modules() {
+    test! {
+        program {
+            trait Trait { }
+            struct Foo { }
+            mod a {
+                struct Foo { }
+                mod b {
+                    struct Bar { }
+                    // `Foo` is `a::Foo`, while `crate::Foo` is the top-level one
+                    impl Trait for Foo { }
+                    impl Trait for b::Bar { }
+                    struct Baz<T> where T: Trait { }
+                    impl Trait for Baz<crate::Foo> { }
+                }
+            }
+        }
+
+        goal {
+            a::Foo: Trait
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Foo: Trait
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            a::b::Bar: Trait
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn modules_can_be_reopened() {
+    test! {
+        program {
+            mod a {
+                trait Trait { }
+            }
+            mod a {
+                struct Foo { }
+                impl Trait for Foo { }
+            }
+        }
+
+        goal {
+            a::Foo: a::Trait
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn module_lowering_errors() {
+    lowering_error! {
+        program {
+            mod a {
+                struct Foo { }
+            }
+            trait Trait { }
+            impl Trait for Foo { }
+        }
+        error_msg {
+            "invalid parameter name `Foo`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Trait { }
+            mod a {
+                struct Foo { }
+                impl Trait for crate::Foo { }
+            }
+        }
+        error_msg {
+            "invalid parameter name `crate::Foo`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            mod a {
+                mod b {
+                    trait Trait { }
+                }
+                struct Foo { }
+                impl Trait for Foo { }
+            }
+        }
+        error_msg {
+            "invalid trait name `Trait`"
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use chalk_solve::object_safety::ObjectSafetyViolation;
 
 #[test]
 fn object_safe_flag() {
@@ -13,3 +14,108 @@ fn object_safe_flag() {
         goal { not { ObjectSafe(Bar) } } yields { "Unique" }
     }
 }
+
+#[test]
+fn object_safe_flag_with_violations() {
+    test! {
+        program {
+            #[lang(sized)]
+            trait Sized {}
+
+            #[object_safe]
+            trait SizedSelf where Self: Sized {}
+
+            #[object_safe]
+            trait WithGat {
+                type Assoc<'a>;
+            }
+
+            #[object_safe]
+            trait SizedSuper where Self: SizedSelf {}
+        }
+
+        goal { not { ObjectSafe(SizedSelf) } } yields { "Unique" }
+        goal { not { ObjectSafe(WithGat) } } yields { "Unique" }
+        goal { not { ObjectSafe(SizedSuper) } } yields { "Unique" }
+    }
+}
+
+fn object_safety_violations(
+    program_text: &str,
+    trait_name: &str,
+) -> Vec<ObjectSafetyViolation<ChalkIr>> {
+    let db = ChalkDatabase::with(program_text, SolverChoice::default());
+    let program = db.program_ir().unwrap();
+    let trait_id = program
+        .trait_ids
+        .iter()
+        .find(|(name, _)| &***name == trait_name)
+        .map(|(_, id)| *id)
+        .unwrap();
+    chalk_solve::object_safety::object_safety_violations(&*program, trait_id)
+}
+
+#[test]
+fn computed_object_safety() {
+    let program_text = "
+        #[lang(sized)]
+        trait Sized {}
+
+        trait Foo {}
+        trait Generic<T> {}
+        trait WithItem<T> {
+            type Item;
+        }
+        trait WithGat {
+            type Assoc<'a>;
+        }
+
+        trait SizedSelf where Self: Sized {}
+        trait SizedSuper where Self: SizedSelf {}
+        trait SelfArg where Self: Generic<Self> {}
+        trait SelfProjection where Self: WithItem<u32>, <Self as WithItem<u32>>::Item: Foo {}
+        trait SelfProjectionArg where Self: WithItem<Self, Item = u32> {}
+        trait Everything where Self: Sized, Self: Generic<Self> {
+            type Gat<T>;
+        }
+        trait Diamond where Self: SelfArg, Self: Generic<u32> {}
+    ";
+
+    let violations = |trait_name| object_safety_violations(program_text, trait_name);
+
+    assert_eq!(violations("Foo"), vec![]);
+    assert_eq!(violations("Generic"), vec![]);
+    assert_eq!(violations("SelfProjection"), vec![]);
+    assert!(matches!(
+        violations("WithGat")[..],
+        [ObjectSafetyViolation::GenericAssociatedType(_)]
+    ));
+    assert_eq!(
+        violations("SizedSelf"),
+        vec![ObjectSafetyViolation::SizedSelf]
+    );
+    assert_eq!(
+        violations("SizedSuper"),
+        vec![ObjectSafetyViolation::SizedSelf]
+    );
+    assert!(matches!(
+        violations("SelfArg")[..],
+        [ObjectSafetyViolation::SupertraitSelf(_)]
+    ));
+    assert!(matches!(
+        violations("SelfProjectionArg")[..],
+        [ObjectSafetyViolation::SupertraitSelf(_)]
+    ));
+    assert!(matches!(
+        violations("Everything")[..],
+        [
+            ObjectSafetyViolation::SizedSelf,
+            ObjectSafetyViolation::SupertraitSelf(_),
+            ObjectSafetyViolation::GenericAssociatedType(_),
+        ]
+    ));
+    assert!(matches!(
+        violations("Diamond")[..],
+        [ObjectSafetyViolation::SupertraitSelf(_)]
+    ));
+}
@@ -50,6 +50,42 @@ fn opaque_reveal() {
     }
 }
 
+#[test]
+fn opaque_reveal_normalization() {
+    test! {
+        program {
+            struct Ty { }
+            trait Clone { }
+            impl Clone for Ty { }
+            opaque type T: Clone = Ty;
+        }
+
+        // Outside of `Reveal`, the placeholder is the only thing `T`
+        // normalizes to
+        goal {
+            exists<U> {
+                T = U
+            }
+        } yields {
+            "Unique; substitution [?0 := !T], lifetime constraints []"
+        }
+
+        // Under `Reveal`, the recursive solver prefers the hidden type over
+        // the placeholder
+        goal {
+            if (Reveal) {
+                exists<U> {
+                    T = U
+                }
+            }
+        } yields[SolverChoice::slg_default()] {
+            "Ambiguous; no inference guidance"
+        } yields[SolverChoice::recursive_default()] {
+            "Unique; substitution [?0 := Ty], lifetime constraints []"
+        }
+    }
+}
+
 #[test]
 fn opaque_where_clause() {
     test! {
@@ -211,16 +247,72 @@ fn opaque_auto_traits() {
 
             opaque type Opaque1: Trait = Bar;
             opaque type Opaque2: Trait = Baz;
+            opaque type Opaque3: Trait + Send = Bar;
         }
 
+        goal {
+            if (Reveal) {
+                Opaque1: Send
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            if (Reveal) {
+                Opaque2: Send
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // Auto traits only leak from the hidden type under `Reveal`
         goal {
             Opaque1: Send
+        } yields {
+            "No possible solution"
+        }
+
+        // ... unless they're part of the opaque type's bounds
+        goal {
+            Opaque3: Send
         } yields {
             "Unique"
         }
+    }
+}
+
+#[test]
+fn opaque_auto_traits_generic() {
+    test! {
+        program {
+            struct Wrapper<T> { t: T }
+            trait Trait { }
+
+            impl<T> Trait for Wrapper<T> { }
+
+            #[auto]
+            trait Send { }
+
+            opaque type Opaque<T>: Trait = Wrapper<T>;
+        }
 
         goal {
-            Opaque2: Send
+            forall<T> {
+                if (Reveal; T: Send) {
+                    Opaque<T>: Send
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (Reveal) {
+                    Opaque<T>: Send
+                }
+            }
         } yields {
             "No possible solution"
         }
@@ -251,13 +343,23 @@ fn opaque_auto_traits_indirect() {
         }
 
         goal {
-            Opaque1: SendDerived
+            if (Reveal) {
+                Opaque1: SendDerived
+            }
         } yields {
             "Unique"
         }
 
         goal {
-            Opaque2: SendDerived
+            if (Reveal) {
+                Opaque2: SendDerived
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Opaque1: SendDerived
         } yields {
             "No possible solution"
         }
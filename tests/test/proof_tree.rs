@@ -0,0 +1,112 @@
+//! Tests for the proof trees returned by `Solver::solve_with_proof_tree`
+
+use super::*;
+
+/// Solves `goal_text` with each of the solvers, and checks that the proof
+/// tree is displayed as `expected`.
+fn assert_proof_tree(program_text: &str, goal_text: &str, expected: &str) {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let db = ChalkDatabase::with(program_text, solver_choice);
+        let program = db.checked_program().unwrap();
+        chalk_integration::tls::set_current_program(&program, || {
+            let goal = db.parse_and_lower_goal(goal_text).unwrap();
+            let peeled_goal = goal.into_peeled_goal(db.interner());
+            let (_, tree) = db.solve_with_proof_tree(&peeled_goal).unwrap();
+            println!("using solver: {:?}", solver_choice);
+            assert_same(&tree.to_string(), expected);
+        });
+    }
+}
+
+#[test]
+fn proof_tree_impls() {
+    assert_proof_tree(
+        "
+        trait Clone {}
+        struct Vec<T> {}
+        impl Clone for u32 {}
+        impl<T> Clone for Vec<T> where T: Clone {}
+        ",
+        "Vec<Vec<u32>>: Clone",
+        "
+        Implemented(Vec<Vec<Uint(U32)>>: Clone) \
+            by for<type> Implemented(Vec<^0.0>: Clone) :- ForAll<> { Implemented(^1.0: Clone) }
+            Implemented(Vec<Uint(U32)>: Clone) \
+                by for<type> Implemented(Vec<^0.0>: Clone) :- ForAll<> { Implemented(^1.0: Clone) }
+                Implemented(Uint(U32): Clone) by for<> Implemented(Uint(U32): Clone)
+        ",
+    );
+}
+
+#[test]
+fn proof_tree_inference_and_where_clauses() {
+    assert_proof_tree(
+        "
+        trait Foo {}
+        trait Bar {}
+        struct A {}
+        struct B {}
+        impl Foo for A {}
+        impl<T> Bar for T where T: Foo {}
+        ",
+        "exists<T> { T: Bar }",
+        "
+        Implemented(A: Bar) by for<type> Implemented(^0.0: Bar) :- ForAll<> { Implemented(^1.0: Foo) }
+            Implemented(A: Foo) by for<> Implemented(A: Foo)
+        ",
+    );
+    assert_proof_tree(
+        "
+        trait Foo {}
+        trait Bar {}
+        impl<T> Bar for T where T: Foo {}
+        ",
+        "forall<T> { if (T: Foo) { T: Bar } }",
+        "
+        Implemented(!1_0: Bar) by for<type> Implemented(^0.0: Bar) :- ForAll<> { Implemented(^1.0: Foo) }
+            Implemented(!1_0: Foo) by for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)
+                FromEnv(!1_0: Foo) by for<> FromEnv(!1_0: Foo)
+        ",
+    );
+}
+
+#[test]
+fn proof_tree_projection() {
+    assert_proof_tree(
+        "
+        trait Iterator { type Item; }
+        struct Counter {}
+        impl Iterator for Counter { type Item = u32; }
+        ",
+        "<Counter as Iterator>::Item = u32",
+        "
+        (<Counter as Iterator>::Item = Uint(U32))
+            AliasEq(<Counter as Iterator>::Item = Uint(U32)) \
+                by for<type, type> AliasEq(<^0.0 as Iterator>::Item = ^0.1) \
+                    :- Normalize(<^0.0 as Iterator>::Item -> ^0.1)
+                Normalize(<Counter as Iterator>::Item -> Uint(U32)) \
+                    by for<> Normalize(<Counter as Iterator>::Item -> Uint(U32))
+        ",
+    );
+}
+
+#[test]
+fn proof_tree_coinductive_cycle() {
+    assert_proof_tree(
+        "
+        #[auto] trait Send {}
+        struct List<T> { data: T, next: List<T> }
+        ",
+        "List<u32>: Send",
+        "
+        Implemented(List<Uint(U32)>: Send) \
+            by for<> Implemented(List<Uint(U32)>: Send) \
+                :- Implemented(Uint(U32): Send), Implemented(List<Uint(U32)>: Send)
+            Implemented(Uint(U32): Send) by for<> Implemented(Uint(U32): Send)
+            Implemented(List<Uint(U32)>: Send)
+        ",
+    );
+}
@@ -0,0 +1,95 @@
+//! Tests for the serialization of solutions with `serde`
+
+use super::*;
+use chalk_solve::Serializable;
+use serde_json::{json, Value};
+
+/// Solves `goal_text` with each of the solvers, and checks that the solution
+/// is serialized as `expected`.
+fn assert_serialized(program_text: &str, goal_text: &str, expected: Value) {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let db = ChalkDatabase::with(program_text, solver_choice);
+        let program = db.checked_program().unwrap();
+        chalk_integration::tls::set_current_program(&program, || {
+            let goal = db.parse_and_lower_goal(goal_text).unwrap();
+            let peeled_goal = goal.into_peeled_goal(db.interner());
+            let solution = db.solve(&peeled_goal).unwrap();
+            println!("using solver: {:?}", solver_choice);
+            let value = serde_json::to_value(Serializable::new(&solution, &ChalkIr)).unwrap();
+            assert_eq!(value, expected);
+        });
+    }
+}
+
+#[test]
+fn serialize_unique_solution() {
+    assert_serialized(
+        "
+        trait Foo {}
+        struct Vec<T> {}
+        impl<T> Foo for Vec<T> {}
+        ",
+        "exists<T, U> { T = Vec<U>, T: Foo }",
+        json!({
+            "result": "unique",
+            "variables": [{ "name": "^0.0", "kind": "type", "universe": 0 }],
+            "bindings": { "?0": "Vec<^0.0>", "?1": "^0.0" },
+            "constraints": [],
+        }),
+    );
+}
+
+#[test]
+fn serialize_lifetime_constraints() {
+    assert_serialized(
+        "
+        trait Foo<'x> {}
+        struct A {}
+        impl<'x> Foo<'x> for &'x A {}
+        ",
+        "forall<'a, 'b> { &'a A: Foo<'b> }",
+        json!({
+            "result": "unique",
+            "variables": [],
+            "bindings": {},
+            "constraints": [
+                { "kind": "lifetime_outlives", "a": "'!1_0", "b": "'!1_1", "environment": [] },
+                { "kind": "lifetime_outlives", "a": "'!1_1", "b": "'!1_0", "environment": [] },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn serialize_ambiguous_solution() {
+    assert_serialized(
+        "
+        #[non_enumerable]
+        trait Constraint {}
+        trait Trait<T> {}
+        struct A<T> {}
+        impl<T> Trait<T> for A<T> where T: Constraint {}
+        ",
+        "exists<T, U> { A<T>: Trait<U> }",
+        json!({
+            "result": "ambiguous",
+            "guidance": "definite",
+            "variables": [{ "name": "^0.0", "kind": "type", "universe": 0 }],
+            "bindings": { "?0": "^0.0", "?1": "^0.0" },
+        }),
+    );
+    assert_serialized(
+        "
+        trait Foo {}
+        struct A {}
+        struct B {}
+        impl Foo for A {}
+        impl Foo for B {}
+        ",
+        "exists<T> { T: Foo }",
+        json!({ "result": "ambiguous", "guidance": "unknown" }),
+    );
+}
@@ -142,6 +142,200 @@ fn dyn_to_dyn_unsizing() {
     }
 }
 
+#[test]
+fn dyn_upcasting() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[object_safe]
+            trait Super {}
+            #[object_safe]
+            trait GenericSuper<T> {}
+            #[object_safe]
+            trait SuperWithItem {
+                type Item;
+            }
+            #[object_safe]
+            trait Sub where Self: Super, Self: GenericSuper<u32> {}
+            #[object_safe]
+            trait SubSub where Self: Sub {}
+            #[object_safe]
+            trait SubWithItem<T> where Self: SuperWithItem<Item = T>, Self: GenericSuper<T> {
+                type OtherItem;
+            }
+            #[object_safe]
+            trait Ambiguous where Self: GenericSuper<u32>, Self: GenericSuper<u64> {}
+            #[object_safe]
+            trait Unrelated {}
+
+            #[auto]
+            #[object_safe]
+            trait Auto {}
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Upcasting through several levels of supertraits
+        goal {
+            forall<'a> {
+                dyn SubSub + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Generic arguments of the supertrait must match
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn GenericSuper<u32> + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn GenericSuper<u64> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                exists<T> {
+                    dyn Sub + 'a: Unsize<dyn GenericSuper<T> + 'a>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U32)]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn SubWithItem<u8, OtherItem = u16> + 'a: Unsize<dyn GenericSuper<u8> + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Auto traits can be dropped, but not added
+        goal {
+            forall<'a> {
+                dyn Sub + Auto + 'a: Unsize<dyn Super + Auto + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + Auto + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Super + Auto + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The source lifetime must outlive the target lifetime
+        goal {
+            forall<'a, 'b> {
+                dyn Sub + 'a: Unsize<dyn Super + 'b>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_1 }]"
+        }
+
+        // Can't upcast to a subtrait or to an unrelated trait
+        goal {
+            forall<'a> {
+                dyn Super + 'a: Unsize<dyn Sub + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Unrelated + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The same supertrait with different arguments makes the upcast ambiguous
+        goal {
+            forall<'a> {
+                dyn Ambiguous + 'a: Unsize<dyn GenericSuper<u32> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn dyn_upcasting_projection_bounds() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[object_safe]
+            trait Super {}
+            #[object_safe]
+            trait SuperWithItem {
+                type Item;
+            }
+            #[object_safe]
+            trait Sub where Self: Super, Self: SuperWithItem {
+                type OtherItem;
+            }
+        }
+
+        // Projection bounds of the subtrait are dropped when upcasting
+        goal {
+            forall<'a> {
+                dyn Sub<OtherItem = u16> + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub<OtherItem = u16> + 'a: Unsize<dyn SuperWithItem + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Projection bounds of the target have to be present on the source
+        goal {
+            forall<'a> {
+                dyn Sub<OtherItem = u16> + 'a: Unsize<dyn SuperWithItem<Item = u16> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
 #[test]
 fn ty_to_dyn_unsizing() {
     test! {
@@ -412,6 +606,96 @@ fn array_unsizing() {
         } yields {
             "No possible solution"
         }
+
+        // The element type can be inferred from either side
+        goal {
+            exists<T> {
+                [Foo<u8>; 2]: Unsize<[T]>
+            }
+        } yields {
+            "Unique; substitution [?0 := Foo<Uint(U8)>]"
+        }
+
+        goal {
+            exists<T> {
+                [T; 2]: Unsize<[Foo<u8>]>
+            }
+        } yields {
+            "Unique; substitution [?0 := Foo<Uint(U8)>]"
+        }
+
+        // Array length is a placeholder
+        goal {
+            forall<const N> {
+                [Foo<u8>; N]: Unsize<[Foo<u8>]>
+            }
+        } yields {
+            "Unique"
+        }
+
+        // Array length is an inference variable, which stays unconstrained
+        goal {
+            exists<const N> {
+                [Foo<u8>; N]: Unsize<[Foo<u8>]>
+            }
+        } yields {
+            "Unique; for<?U0> { substitution [?0 := ^0.0], lifetime constraints [] }"
+        }
+
+        goal {
+            forall<const N> {
+                [Foo<u8>; N]: Unsize<[Foo<u16>]>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn array_unsizing_through_box() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+
+            struct Box<T> {
+                ptr: *const T,
+            }
+
+            impl<T, U> CoerceUnsized<Box<U>> for Box<T> where T: Unsize<U> {}
+        }
+
+        goal {
+            Box<[u8; 3]>: CoerceUnsized<Box<[u8]>>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<const N> {
+                Box<[u8; N]>: CoerceUnsized<Box<[u8]>>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<const N> {
+                Box<[u8; N]>: CoerceUnsized<Box<[u8]>>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Box<[u8; 3]>: CoerceUnsized<Box<[u16]>>
+        } yields {
+            "No possible solution"
+        }
     }
 }
 
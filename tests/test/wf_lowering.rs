@@ -54,7 +54,8 @@ fn cyclic_traits() {
 
             impl<T> A for T where T: B {}
         } error_msg {
-            "trait impl for `B` does not meet well-formedness requirements"
+            "trait impl for `B` does not meet well-formedness requirements \
+             in `impl<_1_0> B for _1_0`: could not prove `forall<_1_0> { WellFormed(_1_0: B) }`"
         }
     }
 
@@ -105,7 +106,8 @@ fn ill_formed_assoc_ty() {
                 type Value = OnlyFoo<MyType>;
             }
         } error_msg {
-            "trait impl for `Bar` does not meet well-formedness requirements"
+            "trait impl for `Bar` does not meet well-formedness requirements \
+             in `impl Bar for MyType`: could not prove `WellFormed(OnlyFoo<MyType>)`"
         }
     }
 }
@@ -144,7 +146,8 @@ fn ill_formed_ty_decl() {
                 value: Set<K>
             }
         } error_msg {
-            "type declaration `MyType` does not meet well-formedness requirements"
+            "type declaration `MyType` does not meet well-formedness requirements: \
+             could not prove `forall<_1_0> { WellFormed(Set<_1_0>) }`"
         }
     }
 }
@@ -182,7 +185,9 @@ fn wf_requiremements_for_projection() {
                 type Value = <T as Iterator>::Item;
             }
         } error_msg {
-            "trait impl for `Foo` does not meet well-formedness requirements"
+            "trait impl for `Foo` does not meet well-formedness requirements \
+             in `impl<_1_0> Foo for _1_0`: \
+             could not prove `forall<_1_0> { WellFormed(<_1_0 as Iterator>::Item) }`"
         }
     }
 
@@ -390,7 +395,8 @@ fn generic_projection_where_clause() {
                 bar: <P as PointerFamily>::Pointer<String>
             }
         } error_msg {
-            "type declaration `Foo` does not meet well-formedness requirements"
+            "type declaration `Foo` does not meet well-formedness requirements: \
+             could not prove `forall<_1_0> { WellFormed(<_1_0 as PointerFamily>::Pointer<String>) }`"
         }
     }
 }
@@ -1302,6 +1308,28 @@ fn coerce_unsized_struct() {
         }
     }
 
+    // Field coercions can use the built-in pointer impls
+    lowering_success! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+
+            struct MyBox<T> {
+                ptr: *const T,
+            }
+
+            struct MyRef<'a, T> {
+                r: &'a mut T,
+            }
+
+            impl<T, U> CoerceUnsized<MyBox<U>> for MyBox<T> where T: Unsize<U> {}
+            impl<'a, T, U> CoerceUnsized<MyRef<'a, U>> for MyRef<'a, T> where T: Unsize<U> {}
+        }
+    }
+
     // Unsizing different structs
     lowering_error! {
         program {